@@ -1,55 +1,6 @@
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
-/// Represents a position in the source code
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Position {
-    pub line: usize,
-    pub column: usize,
-}
-
-impl Position {
-    pub fn new(line: usize, column: usize) -> Self {
-        Self { line, column }
-    }
-}
-
-impl Default for Position {
-    fn default() -> Self {
-        Position { line: 1, column: 1 }
-    }
-}
-
-impl fmt::Display for Position {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}:{}", self.line, self.column)
-    }
-}
-
-/// Represents a span of source code
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Span {
-    pub start: Position,
-    pub end: Position,
-}
-
-impl Span {
-    pub fn new(start: Position, end: Position) -> Self {
-        Self { start, end }
-    }
-
-    pub fn from_positions(
-        start_line: usize,
-        start_col: usize,
-        end_line: usize,
-        end_col: usize,
-    ) -> Self {
-        Self {
-            start: Position::new(start_line, start_col),
-            end: Position::new(end_line, end_col),
-        }
-    }
-}
+pub use lumi_span::{FileId, Position, Span};
 
 /// Main AST node enum containing all possible node types
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -60,13 +11,21 @@ pub enum Node {
     // Declarations
     VariableDeclaration(VariableDeclaration),
     FunctionDeclaration(FunctionDeclaration),
+    ClassDeclaration(ClassDeclaration),
+    EnumDeclaration(EnumDeclaration),
 
     // Expressions
     BinaryExpression(BinaryExpression),
     UnaryExpression(UnaryExpression),
     AssignmentExpression(AssignmentExpression),
     LogicalExpression(LogicalExpression),
+    ConditionalExpression(ConditionalExpression),
     CallExpression(CallExpression),
+    NamedArgument(NamedArgument),
+    MemberExpression(MemberExpression),
+    FunctionExpression(FunctionExpression),
+    NewExpression(NewExpression),
+    MatchExpression(MatchExpression),
 
     // Statements
     PrintStatement(PrintStatement),
@@ -74,20 +33,99 @@ pub enum Node {
     BlockStatement(BlockStatement),
     IfStatement(IfStatement),
     ForStatement(ForStatement),
+    ForEachStatement(ForEachStatement),
+    WhileStatement(WhileStatement),
+    DoWhileStatement(DoWhileStatement),
+    LoopStatement(LoopStatement),
+    BreakStatement(BreakStatement),
+    ContinueStatement(ContinueStatement),
+    LabeledStatement(LabeledStatement),
+    ReturnStatement(ReturnStatement),
+    ThrowStatement(ThrowStatement),
+    TryStatement(TryStatement),
+    SwitchStatement(SwitchStatement),
 
     // Literals
     ArrayLiteral(ArrayLiteral),
+    ObjectLiteral(ObjectLiteral),
+    SpreadElement(SpreadElement),
+    InterpolatedString(InterpolatedString),
+
+    /// A destructuring target: `[first, ...rest]` or `{ x, y: renamed, z -> 0 }`
+    Pattern(Pattern),
 
     // Other
     String(String),
     Identifier(String),
     Boolean(bool),
+    Integer(i64),
+    /// Floating point number
     Number(f64),
+    /// Arbitrary-precision integer, as its decimal digits
+    BigInt(String),
     Null,
     Undefined,
+    /// The instance a method runs on
+    This,
+    /// The parent class, in `super(...)` and `super.method(...)`
+    Super,
 }
 
 impl Node {
+    /// Source span of the node, literals and identifiers carry none
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Node::Program(n) => n.span.as_ref(),
+            Node::VariableDeclaration(n) => n.span.as_ref(),
+            Node::FunctionDeclaration(n) => n.span.as_ref(),
+            Node::ClassDeclaration(n) => n.span.as_ref(),
+            Node::EnumDeclaration(n) => n.span.as_ref(),
+            Node::BinaryExpression(n) => n.span.as_ref(),
+            Node::UnaryExpression(n) => n.span.as_ref(),
+            Node::AssignmentExpression(n) => n.span.as_ref(),
+            Node::LogicalExpression(n) => n.span.as_ref(),
+            Node::ConditionalExpression(n) => n.span.as_ref(),
+            Node::CallExpression(n) => n.span.as_ref(),
+            Node::NamedArgument(n) => n.span.as_ref(),
+            Node::MemberExpression(n) => n.span.as_ref(),
+            Node::FunctionExpression(n) => n.span.as_ref(),
+            Node::NewExpression(n) => n.span.as_ref(),
+            Node::PrintStatement(n) => n.span.as_ref(),
+            Node::ExpressionStatement(n) => n.span.as_ref(),
+            Node::BlockStatement(n) => n.span.as_ref(),
+            Node::IfStatement(n) => n.span.as_ref(),
+            Node::ForStatement(n) => n.span.as_ref(),
+            Node::ForEachStatement(n) => n.span.as_ref(),
+            Node::WhileStatement(n) => n.span.as_ref(),
+            Node::DoWhileStatement(n) => n.span.as_ref(),
+            Node::LoopStatement(n) => n.span.as_ref(),
+            Node::BreakStatement(n) => n.span.as_ref(),
+            Node::ContinueStatement(n) => n.span.as_ref(),
+            Node::LabeledStatement(n) => n.span.as_ref(),
+            Node::ReturnStatement(n) => n.span.as_ref(),
+            Node::ThrowStatement(n) => n.span.as_ref(),
+            Node::TryStatement(n) => n.span.as_ref(),
+            Node::SwitchStatement(n) => n.span.as_ref(),
+            Node::MatchExpression(n) => n.span.as_ref(),
+            Node::ArrayLiteral(n) => n.span.as_ref(),
+            Node::ObjectLiteral(n) => n.span.as_ref(),
+            Node::SpreadElement(n) => n.span.as_ref(),
+            Node::InterpolatedString(n) => n.span.as_ref(),
+            Node::Pattern(_)
+            | Node::String(_)
+            | Node::Identifier(_)
+            | Node::Boolean(_)
+            | Node::Integer(_)
+            | Node::Number(_)
+            | Node::BigInt(_)
+            | Node::Null
+            | Node::Undefined
+            | Node::This
+            | Node::Super => None,
+        }
+    }
+
+    /// The name of an identifier, see `as_identifier` for nodes that may be patterns
     pub fn name(&self) -> String {
         match self {
             Node::Identifier(s) => s.to_string(),
@@ -95,7 +133,19 @@ impl Node {
         }
     }
 
+    /// The name of an identifier, `None` for a destructuring pattern
+    pub fn as_identifier(&self) -> Option<&str> {
+        match self {
+            Node::Identifier(name) => Some(name),
+            _ => None,
+        }
+    }
+
     pub fn get_default_int_value() -> Self {
+        Node::Integer(0)
+    }
+
+    pub fn get_default_float_value() -> Self {
         Node::Number(0.0)
     }
 
@@ -107,10 +157,11 @@ impl Node {
         Node::Boolean(false)
     }
 
-    pub fn get_default_value(var_type: &Box<Node>) -> Result<Self, String> {
-        match &**var_type {
+    pub fn get_default_value(var_type: &Node) -> Result<Self, String> {
+        match var_type {
             Node::Identifier(id) => match id.as_str() {
                 "int" => Ok(Node::get_default_int_value()),
+                "float" => Ok(Node::get_default_float_value()),
                 "str" => Ok(Node::get_default_str_value()),
                 "bool" => Ok(Node::get_default_bool_value()),
                 _ => Err(format!("'{}' is not a valid value type.", id)),
@@ -141,12 +192,94 @@ pub struct VariableDeclaration {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionDeclaration {
     pub id: Option<Box<Node>>,
-    pub params: Vec<Node>,
+    pub params: Vec<Parameter>,
+    pub return_type: Option<Box<Node>>, // Optional return type annotation
     pub body: Box<Node>,
     pub is_async: bool,
     pub span: Option<Span>,
 }
 
+/// Class declaration: `class Dog extends Animal { ... }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassDeclaration {
+    pub id: Box<Node>,
+    pub superclass: Option<Box<Node>>,
+    pub members: Vec<ClassMember>,
+    pub span: Option<Span>,
+}
+
+/// Enum declaration: `enum Shape { Circle(r: float), Rect(w: float, h: float), Empty }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnumDeclaration {
+    pub id: Box<Node>,
+    pub variants: Vec<EnumVariant>,
+    pub span: Option<Span>,
+}
+
+/// A variant of an enum, its fields are the payload its values carry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnumVariant {
+    pub name: String,
+    pub fields: Vec<EnumField>, // Empty for a variant without payload
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnumField {
+    pub name: String,
+    pub field_type: Option<Box<Node>>,
+}
+
+/// A field, constructor, method, getter or setter in a class body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassMember {
+    pub name: String,
+    pub kind: ClassMemberKind,
+    pub is_static: bool,
+    pub member_type: Option<Box<Node>>, // Type annotation of a field
+    pub value: Option<Box<Node>>, // Initializer of a field, the `FunctionExpression` of the other kinds
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClassMemberKind {
+    Field,       // `name: int -> 0;`
+    Constructor, // `constructor(a) { ... }`
+    Method,      // `fn name(a) { ... }`
+    Getter,      // `get name() { ... }`
+    Setter,      // `set name(value) { ... }`
+}
+
+/// Instantiation of a class: `new Point(1, 2)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewExpression {
+    pub callee: Box<Node>,
+    pub arguments: Vec<Node>,
+    pub span: Option<Span>,
+}
+
+/// A function used as a value: `fn (a) { ... }`, `fn name(a) { ... }` or the arrow `(a) => a + 1`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionExpression {
+    pub id: Option<Box<Node>>,
+    pub params: Vec<Parameter>,
+    pub return_type: Option<Box<Node>>, // Optional return type annotation
+    pub body: Box<Node>, // A block, or the returned expression of an arrow like `x => x * 2`
+    pub is_arrow: bool,
+    pub span: Option<Span>,
+}
+
+/// A function parameter, `name`, `name: int`, `name?`, `name -> 1` or `...rest`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: Box<Node>,
+    pub param_type: Option<Box<Node>>, // Optional type annotation
+    pub default_value: Option<Box<Node>>, // Used when the argument is missing or undefined
+    pub optional: bool,
+    pub is_rest: bool, // Collects the remaining arguments into an array
+    pub span: Option<Span>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VariableDeclarator {
     pub var_name: Box<Node>,
@@ -162,6 +295,24 @@ pub struct CallExpression {
     pub span: Option<Span>,
 }
 
+/// An argument passed by parameter name, `greet(name: "lumi")`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedArgument {
+    pub name: Box<Node>,
+    pub value: Box<Node>,
+    pub span: Option<Span>,
+}
+
+/// Property access `obj.field`, indexing `obj[key]` and optional chaining `obj?.field`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberExpression {
+    pub object: Box<Node>,
+    pub property: Box<Node>, // Identifier for `.field`, any expression for `[key]`
+    pub computed: bool,      // Accessed with `[ ]`
+    pub optional: bool,      // Accessed with `?.`, undefined when the object is null or undefined
+    pub span: Option<Span>,
+}
+
 // Expressions
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BinaryExpression {
@@ -174,11 +325,20 @@ pub struct BinaryExpression {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LogicalExpression {
     pub left: Box<Node>,
-    pub operator: String, // "and", "or"
+    pub operator: String, // "and", "or", "??"
     pub right: Box<Node>,
     pub span: Option<Span>,
 }
 
+/// Ternary `test ? consequent : alternate`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionalExpression {
+    pub test: Box<Node>,
+    pub consequent: Box<Node>,
+    pub alternate: Box<Node>,
+    pub span: Option<Span>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AssignmentExpression {
     pub left: Box<Node>,  // The left-hand side (e.g. x in x = 5)
@@ -226,6 +386,197 @@ pub struct ForStatement {
     pub span: Option<Span>,
 }
 
+/// `for item in items { }`, runs the body for each element of an array
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForEachStatement {
+    pub iterator: Box<Node>, // An identifier or a destructuring pattern
+    pub iterable: Box<Node>,
+    pub body: Box<Node>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WhileStatement {
+    pub condition: Box<Node>,
+    pub body: Box<Node>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoWhileStatement {
+    pub body: Box<Node>,
+    pub condition: Box<Node>,
+    pub span: Option<Span>,
+}
+
+/// Infinite `loop { }`, only left through `break`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoopStatement {
+    pub body: Box<Node>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReturnStatement {
+    pub argument: Option<Box<Node>>, // Value to return, undefined if None
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThrowStatement {
+    pub argument: Box<Node>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TryStatement {
+    pub block: Box<Node>,
+    pub handler: Option<CatchClause>, // At least one of handler and finalizer is set
+    pub finalizer: Option<Box<Node>>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatchClause {
+    pub param: Option<Box<Node>>, // `catch { ... }` ignores the thrown value
+    pub body: Box<Node>,
+    pub span: Option<Span>,
+}
+
+/// `switch (value) { case 1, 2: ... default: ... }`, only the body of the matching case runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwitchStatement {
+    pub discriminant: Box<Node>,
+    pub cases: Vec<SwitchCase>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwitchCase {
+    pub tests: Vec<Node>, // Empty for `default`
+    pub body: Vec<Node>,
+    pub span: Option<Span>,
+}
+
+/// `match (value) { pattern if guard => result, ... }`, evaluates to the result of the
+/// first arm whose pattern and guard match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchExpression {
+    pub discriminant: Box<Node>,
+    pub arms: Vec<MatchArm>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Box<Node>>,
+    pub body: Box<Node>,
+    pub span: Option<Span>,
+}
+
+/// The shape a value is tested against, binding names to its parts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Pattern {
+    /// `_`, matches anything
+    Wildcard,
+    /// A literal compared by equality: `1`, `-2.5`, `"text"`, `true`, `null`
+    Literal(Box<Node>),
+    /// `low to high`, both ends included
+    Range(Box<Node>, Box<Node>),
+    /// A name the matched value is bound to
+    Binding(String),
+    /// `[first, second, ...rest]`
+    Array(ArrayPattern),
+    /// `{ x, y: pattern }`
+    Object(ObjectPattern),
+    /// `Shape.Circle(r)`, a value of an enum variant and the patterns of its fields
+    Variant(VariantPattern),
+    /// `pattern -> default`, destructures the default when the value is undefined
+    Default(Box<Pattern>, Box<Node>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArrayPattern {
+    pub elements: Vec<Pattern>,
+    pub rest: Option<Box<Pattern>>, // Any number of remaining elements, collected into an array
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariantPattern {
+    pub enum_name: String,
+    pub variant: String,
+    pub fields: Option<Vec<Pattern>>, // `Shape.Circle` without parentheses matches any payload
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectPattern {
+    pub properties: Vec<PropertyPattern>, // Other properties of the object are ignored
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyPattern {
+    pub key: String,
+    pub value: Pattern, // A binding of the same name for the shorthand `{ x }`
+}
+
+impl Pattern {
+    /// Whether the pattern matches every value
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            Pattern::Wildcard | Pattern::Binding(_) => true,
+            Pattern::Default(pattern, _) => pattern.is_irrefutable(),
+            _ => false,
+        }
+    }
+
+    /// The names the pattern binds, in source order
+    pub fn bindings(&self) -> Vec<&str> {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) | Pattern::Range(..) => Vec::new(),
+            Pattern::Binding(name) => vec![name.as_str()],
+            Pattern::Default(pattern, _) => pattern.bindings(),
+            Pattern::Array(array) => array
+                .elements
+                .iter()
+                .chain(array.rest.as_deref())
+                .flat_map(Pattern::bindings)
+                .collect(),
+            Pattern::Object(object) => object
+                .properties
+                .iter()
+                .flat_map(|property| property.value.bindings())
+                .collect(),
+            Pattern::Variant(variant) => variant
+                .fields
+                .iter()
+                .flatten()
+                .flat_map(Pattern::bindings)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakStatement {
+    pub label: Option<String>, // Label of the loop to leave, the innermost loop if None
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContinueStatement {
+    pub label: Option<String>, // Label of the loop to continue, the innermost loop if None
+    pub span: Option<Span>,
+}
+
+/// A loop with a name that `break` and `continue` can refer to, e.g. `outer: while (x) { }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LabeledStatement {
+    pub label: String,
+    pub body: Box<Node>,
+    pub span: Option<Span>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExpressionStatement {
     pub expression: Box<Node>,
@@ -235,6 +586,41 @@ pub struct ExpressionStatement {
 // Literals
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ArrayLiteral {
-    pub elements: Vec<Option<Node>>,
+    pub elements: Vec<Option<Node>>, // None for a hole, e.g. [1, , 3]
+    pub span: Option<Span>,
+}
+
+/// `...expr`, expands the elements of an iterable in place
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpreadElement {
+    pub argument: Box<Node>,
+    pub span: Option<Span>,
+}
+
+/// Object literal: { key: value, "quoted": value, [computed]: value, shorthand }
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectLiteral {
+    pub properties: Vec<ObjectProperty>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectProperty {
+    pub key: PropertyKey,
+    pub value: Node,
+    pub shorthand: bool, // Written as `{ name }`, the value is the variable with the same name
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PropertyKey {
+    /// Identifier or quoted key
+    Named(String),
+    /// `[expr]` key, evaluated at runtime
+    Computed(Box<Node>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterpolatedString {
+    pub parts: Vec<Node>, // String literal pieces and the embedded expressions, in source order
     pub span: Option<Span>,
 }
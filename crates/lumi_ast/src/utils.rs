@@ -1,6 +1,6 @@
 use crate::{
-    BinaryExpression, BlockStatement, IfStatement, Node, Program, VariableDeclaration,
-    VariableDeclarator,
+    BinaryExpression, BlockStatement, IfStatement, InterpolatedString, Node, Program,
+    VariableDeclaration, VariableDeclarator,
 };
 
 // Helpers
@@ -12,6 +12,10 @@ pub fn create_identifier(name: &str) -> Node {
     Node::Identifier(name.to_string())
 }
 
+pub fn create_integer(n: i64) -> Node {
+    Node::Integer(n)
+}
+
 pub fn create_number(n: f64) -> Node {
     Node::Number(n)
 }
@@ -20,6 +24,10 @@ pub fn create_string(s: &str) -> Node {
     Node::String(s.to_string())
 }
 
+pub fn create_interpolated_string(parts: Vec<Node>) -> Node {
+    Node::InterpolatedString(InterpolatedString { parts, span: None })
+}
+
 pub fn create_variable_declaration(
     kind: &str,
     name: &str,
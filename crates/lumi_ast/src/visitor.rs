@@ -1,60 +1,350 @@
-use crate::Node;
+use crate::{Node, Parameter, Pattern};
 
-/// Visitor trait for AST traversal
+/// Visitor trait for AST traversal. Every `visit_*` method walks the children of its
+/// node by default, so a visitor only overrides the kinds it cares about
 pub trait Visitor {
-    type Output;
+    type Output: Default;
 
     fn visit_node(&mut self, node: &Node) -> Self::Output {
         match node {
-            Node::Program(program) => self.visit_program(program),
-            Node::VariableDeclaration(decl) => self.visit_variable_declaration(decl),
-            Node::BinaryExpression(expr) => self.visit_binary_expression(expr),
+            Node::Program(node) => self.visit_program(node),
+            Node::VariableDeclaration(node) => self.visit_variable_declaration(node),
+            Node::FunctionDeclaration(node) => self.visit_function_declaration(node),
+            Node::ClassDeclaration(node) => self.visit_class_declaration(node),
+            Node::EnumDeclaration(node) => self.visit_enum_declaration(node),
+            Node::BinaryExpression(node) => self.visit_binary_expression(node),
+            Node::UnaryExpression(node) => self.visit_unary_expression(node),
+            Node::AssignmentExpression(node) => self.visit_assignment_expression(node),
+            Node::LogicalExpression(node) => self.visit_logical_expression(node),
+            Node::ConditionalExpression(node) => self.visit_conditional_expression(node),
+            Node::CallExpression(node) => self.visit_call_expression(node),
+            Node::NamedArgument(node) => self.visit_named_argument(node),
+            Node::MemberExpression(node) => self.visit_member_expression(node),
+            Node::FunctionExpression(node) => self.visit_function_expression(node),
+            Node::NewExpression(node) => self.visit_new_expression(node),
+            Node::MatchExpression(node) => self.visit_match_expression(node),
+            Node::PrintStatement(node) => self.visit_print_statement(node),
+            Node::ExpressionStatement(node) => self.visit_expression_statement(node),
+            Node::BlockStatement(node) => self.visit_block_statement(node),
+            Node::IfStatement(node) => self.visit_if_statement(node),
+            Node::ForStatement(node) => self.visit_for_statement(node),
+            Node::ForEachStatement(node) => self.visit_for_each_statement(node),
+            Node::WhileStatement(node) => self.visit_while_statement(node),
+            Node::DoWhileStatement(node) => self.visit_do_while_statement(node),
+            Node::LoopStatement(node) => self.visit_loop_statement(node),
+            Node::BreakStatement(node) => self.visit_break_statement(node),
+            Node::ContinueStatement(node) => self.visit_continue_statement(node),
+            Node::LabeledStatement(node) => self.visit_labeled_statement(node),
+            Node::ReturnStatement(node) => self.visit_return_statement(node),
+            Node::ThrowStatement(node) => self.visit_throw_statement(node),
+            Node::TryStatement(node) => self.visit_try_statement(node),
+            Node::SwitchStatement(node) => self.visit_switch_statement(node),
+            Node::ArrayLiteral(node) => self.visit_array_literal(node),
+            Node::ObjectLiteral(node) => self.visit_object_literal(node),
+            Node::SpreadElement(node) => self.visit_spread_element(node),
+            Node::InterpolatedString(node) => self.visit_interpolated_string(node),
+            Node::Pattern(pattern) => self.visit_pattern(pattern),
             Node::String(s) => self.visit_string(s),
+            Node::Identifier(i) => self.visit_identifier(i),
             Node::Boolean(b) => self.visit_boolean(*b),
+            Node::Integer(n) => self.visit_integer(*n),
             Node::Number(n) => self.visit_number(*n),
+            Node::BigInt(n) => self.visit_big_int(n),
             Node::Null => self.visit_null(),
-            Node::Identifier(i) => self.visit_identifier(i),
             Node::Undefined => self.visit_undefined(),
-            Node::UnaryExpression(_) => todo!(),
-            Node::PrintStatement(_) => todo!(),
-            Node::ArrayLiteral(_) => todo!(),
-            Node::ExpressionStatement(_) => todo!(),
-            Node::AssignmentExpression(_) => todo!(),
-            Node::LogicalExpression(_) => todo!(),
-            Node::IfStatement(if_statement) => todo!(),
-            Node::BlockStatement(block_statement) => todo!(),
-            Node::FunctionDeclaration(function_declaration) => todo!(),
-            Node::CallExpression(call_expression) => todo!(),
-            Node::ForStatement(for_statement) => todo!(),
+            Node::This => self.visit_this(),
+            Node::Super => self.visit_super(),
+        }
+    }
+
+    /// Visit each node in order
+    fn visit_all(&mut self, nodes: &[Node]) -> Self::Output {
+        for node in nodes {
+            self.visit_node(node);
         }
+        Self::Output::default()
     }
 
-    fn visit_program(&mut self, _: &crate::Program) -> Self::Output {
-        unimplemented!()
+    /// Visit a node that may be left out, like an initializer or type annotation
+    fn visit_optional(&mut self, node: Option<&Node>) -> Self::Output {
+        match node {
+            Some(node) => self.visit_node(node),
+            None => Self::Output::default(),
+        }
+    }
+
+    /// The parameters, return type and body shared by declarations and expressions
+    fn visit_function(
+        &mut self,
+        params: &[Parameter],
+        return_type: Option<&Node>,
+        body: &Node,
+    ) -> Self::Output {
+        for param in params {
+            self.visit_node(&param.name);
+            self.visit_optional(param.param_type.as_deref());
+            self.visit_optional(param.default_value.as_deref());
+        }
+        self.visit_optional(return_type);
+        self.visit_node(body);
+        Self::Output::default()
+    }
+
+    fn visit_program(&mut self, node: &crate::Program) -> Self::Output {
+        self.visit_all(&node.body)
+    }
+    fn visit_variable_declaration(&mut self, node: &crate::VariableDeclaration) -> Self::Output {
+        for declarator in &node.declarations {
+            self.visit_node(&declarator.var_name);
+            self.visit_optional(declarator.var_type.as_deref());
+            self.visit_optional(declarator.init.as_deref());
+        }
+        Self::Output::default()
+    }
+    fn visit_function_declaration(&mut self, node: &crate::FunctionDeclaration) -> Self::Output {
+        self.visit_optional(node.id.as_deref());
+        self.visit_function(&node.params, node.return_type.as_deref(), &node.body)
+    }
+    fn visit_class_declaration(&mut self, node: &crate::ClassDeclaration) -> Self::Output {
+        self.visit_node(&node.id);
+        self.visit_optional(node.superclass.as_deref());
+        for member in &node.members {
+            self.visit_optional(member.member_type.as_deref());
+            self.visit_optional(member.value.as_deref());
+        }
+        Self::Output::default()
+    }
+    fn visit_enum_declaration(&mut self, node: &crate::EnumDeclaration) -> Self::Output {
+        self.visit_node(&node.id);
+        for variant in &node.variants {
+            for field in &variant.fields {
+                self.visit_optional(field.field_type.as_deref());
+            }
+        }
+        Self::Output::default()
+    }
+    fn visit_binary_expression(&mut self, node: &crate::BinaryExpression) -> Self::Output {
+        self.visit_node(&node.left);
+        self.visit_node(&node.right);
+        Self::Output::default()
+    }
+    fn visit_unary_expression(&mut self, node: &crate::UnaryExpression) -> Self::Output {
+        self.visit_node(&node.argument);
+        Self::Output::default()
+    }
+    fn visit_assignment_expression(&mut self, node: &crate::AssignmentExpression) -> Self::Output {
+        self.visit_node(&node.left);
+        self.visit_node(&node.right);
+        Self::Output::default()
+    }
+    fn visit_logical_expression(&mut self, node: &crate::LogicalExpression) -> Self::Output {
+        self.visit_node(&node.left);
+        self.visit_node(&node.right);
+        Self::Output::default()
+    }
+    fn visit_conditional_expression(
+        &mut self,
+        node: &crate::ConditionalExpression,
+    ) -> Self::Output {
+        self.visit_node(&node.test);
+        self.visit_node(&node.consequent);
+        self.visit_node(&node.alternate);
+        Self::Output::default()
+    }
+    fn visit_call_expression(&mut self, node: &crate::CallExpression) -> Self::Output {
+        self.visit_node(&node.callee);
+        self.visit_all(&node.arguments)
+    }
+    fn visit_named_argument(&mut self, node: &crate::NamedArgument) -> Self::Output {
+        self.visit_node(&node.name);
+        self.visit_node(&node.value);
+        Self::Output::default()
+    }
+    fn visit_member_expression(&mut self, node: &crate::MemberExpression) -> Self::Output {
+        self.visit_node(&node.object);
+        self.visit_node(&node.property);
+        Self::Output::default()
+    }
+    fn visit_function_expression(&mut self, node: &crate::FunctionExpression) -> Self::Output {
+        self.visit_optional(node.id.as_deref());
+        self.visit_function(&node.params, node.return_type.as_deref(), &node.body)
+    }
+    fn visit_new_expression(&mut self, node: &crate::NewExpression) -> Self::Output {
+        self.visit_node(&node.callee);
+        self.visit_all(&node.arguments)
+    }
+    fn visit_match_expression(&mut self, node: &crate::MatchExpression) -> Self::Output {
+        self.visit_node(&node.discriminant);
+        for arm in &node.arms {
+            self.visit_pattern(&arm.pattern);
+            self.visit_optional(arm.guard.as_deref());
+            self.visit_node(&arm.body);
+        }
+        Self::Output::default()
+    }
+    fn visit_print_statement(&mut self, node: &crate::PrintStatement) -> Self::Output {
+        self.visit_node(&node.argument);
+        Self::Output::default()
+    }
+    fn visit_expression_statement(&mut self, node: &crate::ExpressionStatement) -> Self::Output {
+        self.visit_node(&node.expression);
+        Self::Output::default()
+    }
+    fn visit_block_statement(&mut self, node: &crate::BlockStatement) -> Self::Output {
+        self.visit_all(&node.body)
+    }
+    fn visit_if_statement(&mut self, node: &crate::IfStatement) -> Self::Output {
+        self.visit_node(&node.expr);
+        self.visit_node(&node.stmt);
+        self.visit_optional(node.else_part.as_deref());
+        Self::Output::default()
+    }
+    fn visit_for_statement(&mut self, node: &crate::ForStatement) -> Self::Output {
+        self.visit_node(&node.iterator);
+        self.visit_node(&node.start);
+        self.visit_node(&node.end);
+        self.visit_optional(node.step.as_deref());
+        self.visit_node(&node.body);
+        Self::Output::default()
+    }
+    fn visit_for_each_statement(&mut self, node: &crate::ForEachStatement) -> Self::Output {
+        self.visit_node(&node.iterator);
+        self.visit_node(&node.iterable);
+        self.visit_node(&node.body);
+        Self::Output::default()
     }
-    fn visit_variable_declaration(&mut self, _: &crate::VariableDeclaration) -> Self::Output {
-        unimplemented!()
+    fn visit_while_statement(&mut self, node: &crate::WhileStatement) -> Self::Output {
+        self.visit_node(&node.condition);
+        self.visit_node(&node.body);
+        Self::Output::default()
     }
-    fn visit_binary_expression(&mut self, _: &crate::BinaryExpression) -> Self::Output {
-        unimplemented!()
+    fn visit_do_while_statement(&mut self, node: &crate::DoWhileStatement) -> Self::Output {
+        self.visit_node(&node.body);
+        self.visit_node(&node.condition);
+        Self::Output::default()
+    }
+    fn visit_loop_statement(&mut self, node: &crate::LoopStatement) -> Self::Output {
+        self.visit_node(&node.body);
+        Self::Output::default()
+    }
+    fn visit_break_statement(&mut self, _: &crate::BreakStatement) -> Self::Output {
+        Self::Output::default()
+    }
+    fn visit_continue_statement(&mut self, _: &crate::ContinueStatement) -> Self::Output {
+        Self::Output::default()
+    }
+    fn visit_labeled_statement(&mut self, node: &crate::LabeledStatement) -> Self::Output {
+        self.visit_node(&node.body);
+        Self::Output::default()
+    }
+    fn visit_return_statement(&mut self, node: &crate::ReturnStatement) -> Self::Output {
+        self.visit_optional(node.argument.as_deref());
+        Self::Output::default()
+    }
+    fn visit_throw_statement(&mut self, node: &crate::ThrowStatement) -> Self::Output {
+        self.visit_node(&node.argument);
+        Self::Output::default()
+    }
+    fn visit_try_statement(&mut self, node: &crate::TryStatement) -> Self::Output {
+        self.visit_node(&node.block);
+        if let Some(handler) = &node.handler {
+            self.visit_optional(handler.param.as_deref());
+            self.visit_node(&handler.body);
+        }
+        self.visit_optional(node.finalizer.as_deref());
+        Self::Output::default()
+    }
+    fn visit_switch_statement(&mut self, node: &crate::SwitchStatement) -> Self::Output {
+        self.visit_node(&node.discriminant);
+        for case in &node.cases {
+            self.visit_all(&case.tests);
+            self.visit_all(&case.body);
+        }
+        Self::Output::default()
+    }
+    fn visit_array_literal(&mut self, node: &crate::ArrayLiteral) -> Self::Output {
+        for element in node.elements.iter().flatten() {
+            self.visit_node(element);
+        }
+        Self::Output::default()
+    }
+    fn visit_object_literal(&mut self, node: &crate::ObjectLiteral) -> Self::Output {
+        for property in &node.properties {
+            if let crate::PropertyKey::Computed(key) = &property.key {
+                self.visit_node(key);
+            }
+            self.visit_node(&property.value);
+        }
+        Self::Output::default()
+    }
+    fn visit_spread_element(&mut self, node: &crate::SpreadElement) -> Self::Output {
+        self.visit_node(&node.argument);
+        Self::Output::default()
+    }
+    fn visit_interpolated_string(&mut self, node: &crate::InterpolatedString) -> Self::Output {
+        self.visit_all(&node.parts)
+    }
+    fn visit_pattern(&mut self, pattern: &Pattern) -> Self::Output {
+        match pattern {
+            Pattern::Wildcard | Pattern::Binding(_) => {}
+            Pattern::Literal(literal) => {
+                self.visit_node(literal);
+            }
+            Pattern::Range(low, high) => {
+                self.visit_node(low);
+                self.visit_node(high);
+            }
+            Pattern::Array(array) => {
+                for element in array.elements.iter().chain(array.rest.as_deref()) {
+                    self.visit_pattern(element);
+                }
+            }
+            Pattern::Object(object) => {
+                for property in &object.properties {
+                    self.visit_pattern(&property.value);
+                }
+            }
+            Pattern::Variant(variant) => {
+                for field in variant.fields.iter().flatten() {
+                    self.visit_pattern(field);
+                }
+            }
+            Pattern::Default(pattern, default) => {
+                self.visit_pattern(pattern);
+                self.visit_node(default);
+            }
+        }
+        Self::Output::default()
     }
     fn visit_string(&mut self, _: &str) -> Self::Output {
-        unimplemented!()
+        Self::Output::default()
     }
     fn visit_identifier(&mut self, _: &str) -> Self::Output {
-        unimplemented!()
+        Self::Output::default()
+    }
+    fn visit_integer(&mut self, _: i64) -> Self::Output {
+        Self::Output::default()
     }
     fn visit_number(&mut self, _: f64) -> Self::Output {
-        unimplemented!()
+        Self::Output::default()
+    }
+    fn visit_big_int(&mut self, _: &str) -> Self::Output {
+        Self::Output::default()
     }
     fn visit_boolean(&mut self, _: bool) -> Self::Output {
-        unimplemented!()
+        Self::Output::default()
     }
     fn visit_null(&mut self) -> Self::Output {
-        unimplemented!()
+        Self::Output::default()
     }
     fn visit_undefined(&mut self) -> Self::Output {
-        unimplemented!()
+        Self::Output::default()
+    }
+    fn visit_this(&mut self) -> Self::Output {
+        Self::Output::default()
+    }
+    fn visit_super(&mut self) -> Self::Output {
+        Self::Output::default()
     }
 }
 
@@ -63,6 +353,12 @@ pub struct NodeCounter {
     pub count: usize,
 }
 
+impl Default for NodeCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NodeCounter {
     pub fn new() -> Self {
         Self { count: 0 }
@@ -92,6 +388,12 @@ impl Visitor for NodeCounter {
                 self.visit_node(&expr.left);
                 self.visit_node(&expr.right);
             }
+            Node::ArrayLiteral(array) => {
+                for element in array.elements.iter().flatten() {
+                    self.visit_node(element);
+                }
+            }
+            Node::SpreadElement(spread) => self.visit_node(&spread.argument),
             _ => {}
         }
     }
@@ -1,7 +1,7 @@
 use lumi_ast::{
     create_binary_expression, create_block_statement, create_identifier, create_if_statement,
-    create_multiple_variable_declarations, create_number, create_string,
-    create_variable_declaration, Node, Position, Span,
+    create_integer, create_interpolated_string, create_multiple_variable_declarations,
+    create_number, create_string, create_variable_declaration, Node, Position, Span,
 };
 
 #[test]
@@ -33,6 +33,10 @@ fn test_literal_nodes() {
     let id = create_number(42.0);
     assert!(matches!(id, Node::Number(n) if n == 42.0));
 
+    // Integer
+    let id = create_integer(42);
+    assert!(matches!(id, Node::Integer(42)));
+
     // String
     let id = create_string("hello world");
     assert!(matches!(id, Node::String(s) if s == "hello world"));
@@ -76,3 +80,13 @@ fn test_if_statement() {
 
     assert!(matches!(if_stmt, Node::IfStatement(_)));
 }
+
+#[test]
+fn test_interpolated_string() {
+    let interpolated = create_interpolated_string(vec![
+        create_string("count: "),
+        create_binary_expression(create_identifier("x"), "+", create_number(1.0)),
+    ]);
+
+    assert!(matches!(interpolated, Node::InterpolatedString(s) if s.parts.len() == 2));
+}
@@ -1,5 +1,6 @@
 use lumi_ast::{
-    create_binary_expression, create_identifier, create_program, create_variable_declaration, Node,
+    create_binary_expression, create_block_statement, create_identifier, create_if_statement,
+    create_integer, create_interpolated_string, create_program, create_variable_declaration, Node,
     Visitor,
 };
 
@@ -69,3 +70,71 @@ fn test_identifier_collector_visitor() {
     assert!(collector.identifiers.contains(&"a".to_string()));
     assert!(collector.identifiers.contains(&"b".to_string()));
 }
+
+#[test]
+fn test_node_counter_visits_array_elements() {
+    let array = Node::ArrayLiteral(lumi_ast::ArrayLiteral {
+        elements: vec![
+            Some(Node::Integer(1)),
+            None,
+            Some(Node::SpreadElement(lumi_ast::SpreadElement {
+                argument: Box::new(create_identifier("rest")),
+                span: None,
+            })),
+        ],
+        span: None,
+    });
+
+    let mut counter = lumi_ast::NodeCounter::new();
+    counter.visit_node(&array);
+
+    // The array, the integer, the spread and its argument
+    assert_eq!(counter.count, 4);
+}
+
+#[derive(Default)]
+struct NameVisitor {
+    names: Vec<String>,
+}
+
+impl Visitor for NameVisitor {
+    type Output = ();
+
+    fn visit_identifier(&mut self, id: &str) -> Self::Output {
+        self.names.push(id.to_string());
+    }
+}
+
+#[test]
+fn test_default_methods_walk_children() {
+    // if (ready) { print "${greet(name)}"; } else { count = count + 1; }
+    let call = Node::CallExpression(lumi_ast::CallExpression {
+        callee: Box::new(create_identifier("greet")),
+        arguments: vec![create_identifier("name")],
+        span: None,
+    });
+    let print = Node::PrintStatement(lumi_ast::PrintStatement {
+        argument: Box::new(create_interpolated_string(vec![call])),
+        span: None,
+    });
+    let assignment = Node::AssignmentExpression(lumi_ast::AssignmentExpression {
+        left: Box::new(create_identifier("count")),
+        operator: "=".to_string(),
+        right: Box::new(create_binary_expression(
+            create_identifier("count"),
+            "+",
+            create_integer(1),
+        )),
+        span: None,
+    });
+    let ast = create_program(vec![create_if_statement(
+        create_identifier("ready"),
+        create_block_statement(vec![print]),
+        Some(create_block_statement(vec![assignment])),
+    )]);
+
+    let mut visitor = NameVisitor::default();
+    visitor.visit_node(&ast);
+
+    assert_eq!(visitor.names, ["ready", "greet", "name", "count", "count"]);
+}
@@ -1,56 +1,40 @@
-use std::{cell::RefCell, rc::Rc};
-
 use crate::{Constant, Instruction};
 
-/// An environment for variable and function scopes
-#[derive(Debug, Clone, PartialEq)]
-pub struct Env {
-    pub functions: Vec<FunctionObj>,
-    pub parent: Option<Rc<RefCell<Env>>>,
-}
-
 /// A function object
 #[derive(Debug, Clone, PartialEq)]
 pub struct FunctionObj {
     pub name: Option<String>,
-    pub arity: usize,
+    pub arity: usize,             // Parameters before a `...rest` one
+    pub param_names: Vec<String>, // Looked up by named arguments
+    pub has_rest: bool,           // Extra arguments are collected into an array after `arity`
+    pub is_method: bool,          // `this` is local 0 and the parameters follow it
     pub instructions: Vec<Instruction>,
     pub constants: Vec<Constant>,
+    pub captures: Vec<Capture>, // Variables of enclosing functions, indexed by `LoadUpvalue`
+    pub handlers: Vec<Handler>, // `try` ranges of `instructions`, innermost first
 }
 
-// NOTE: when creating a new Env, pass the current as the parent
-// let env = Env::new(Some(Rc::clone(&self.env)));
-impl Env {
-    pub fn new(closure: Option<Rc<RefCell<Env>>>) -> Self {
-        Env {
-            functions: Vec::new(),
-            parent: closure,
-        }
-    }
+/// A range of instructions guarded by a `try`, a value thrown by one of them is pushed
+/// and execution continues at `target`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handler {
+    pub start: usize, // First guarded instruction
+    pub end: usize,   // First instruction after the range
+    pub target: usize,
+    pub depth: usize, // Values on the stack above the frame's when the `try` is entered
+}
 
-    pub fn add_function(&mut self, func: FunctionObj) -> usize {
-        self.functions.push(func);
-        self.functions.len() - 1 // Return the index of the newly added function
+impl Handler {
+    pub fn covers(&self, ip: usize) -> bool {
+        (self.start..self.end).contains(&ip)
     }
+}
 
-    pub fn get_function(&self, name: &str, may_check_parent: bool) -> Option<FunctionObj> {
-        for func in &self.functions {
-            if let Some(ref func_name) = func.name {
-                if func_name == name {
-                    return Some(func.clone());
-                }
-            }
-        }
-
-        if !may_check_parent {
-            return None;
-        }
-
-        // If not found in the current environment, check the parent
-        if let Some(ref parent_env) = self.parent {
-            return parent_env.borrow().get_function(name, may_check_parent);
-        }
-
-        None
-    }
+/// Where a closure finds a captured variable when it is created
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capture {
+    /// A local of the function creating the closure
+    Local(usize),
+    /// An upvalue of the function creating the closure
+    Upvalue(usize),
 }
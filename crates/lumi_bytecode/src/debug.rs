@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use lumi_ast::Span;
+
+/// Maps the instructions of one chunk back to the source spans they were generated from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpanTable {
+    /// Pairs of (first instruction index, span), sorted by index
+    entries: Vec<(usize, Span)>,
+}
+
+impl SpanTable {
+    /// Record that the instructions from `ip` onwards were generated from `span`
+    pub fn record(&mut self, ip: usize, span: Span) {
+        match self.entries.last_mut() {
+            Some((last_ip, last_span)) if *last_ip == ip => *last_span = span,
+            Some((_, last_span)) if *last_span == span => {}
+            _ => self.entries.push((ip, span)),
+        }
+    }
+
+    /// Span of the node that produced the instruction at `ip`
+    pub fn span_at(&self, ip: usize) -> Option<&Span> {
+        let idx = self.entries.partition_point(|(start, _)| *start <= ip);
+        idx.checked_sub(1).map(|i| &self.entries[i].1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Debug information of a compiled program
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DebugInfo {
+    /// Spans of the top-level instructions
+    pub main: SpanTable,
+    /// Spans of the instructions of each named function
+    pub functions: HashMap<String, SpanTable>,
+}
+
+impl DebugInfo {
+    /// Span of the instruction at `ip`, in the named function or at the top level
+    pub fn span_at(&self, function: Option<&str>, ip: usize) -> Option<&Span> {
+        match function {
+            Some(name) => self.functions.get(name)?.span_at(ip),
+            None => self.main.span_at(ip),
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::Instruction;
 
 pub trait ArithmeticGenerator {
     fn generate_binary_expression(&mut self, node: &Node);
+    fn generate_unary_expression(&mut self, node: &Node);
 }
 
 pub trait ArithmeticCore {
@@ -11,6 +12,19 @@ pub trait ArithmeticCore {
     fn visit_node(&mut self, node: &Node);
 }
 
+/// The arithmetic instruction behind a compound assignment operator such as `+=`
+pub fn compound_operation(operator: &str) -> Option<Instruction> {
+    match operator {
+        "+=" => Some(Instruction::Add),
+        "-=" => Some(Instruction::Sub),
+        "*=" => Some(Instruction::Mul),
+        "/=" => Some(Instruction::Div),
+        "%=" => Some(Instruction::Mod),
+        "**=" => Some(Instruction::Pow),
+        _ => None,
+    }
+}
+
 impl<T> ArithmeticGenerator for T
 where
     T: ArithmeticCore,
@@ -25,6 +39,7 @@ where
                 "*" => self.instructions().push(Instruction::Mul),
                 "/" => self.instructions().push(Instruction::Div),
                 "%" => self.instructions().push(Instruction::Mod),
+                "**" => self.instructions().push(Instruction::Pow),
                 ">" => self.instructions().push(Instruction::Gt),
                 "<" => self.instructions().push(Instruction::Lt),
                 "<=" => self.instructions().push(Instruction::Leq),
@@ -37,4 +52,13 @@ where
             }
         }
     }
+
+    fn generate_unary_expression(&mut self, node: &Node) {
+        if let Node::UnaryExpression(expr) = node {
+            self.visit_node(&expr.argument);
+            if expr.operator == "-" {
+                self.instructions().push(Instruction::Neg);
+            }
+        }
+    }
 }
@@ -0,0 +1,60 @@
+use lumi_ast::Node;
+
+use crate::{Constant, ConstantPool, Instruction};
+
+pub trait ArrayGenerator {
+    fn generate_array_literal(&mut self, node: &Node);
+}
+
+pub trait ArrayCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn constants(&mut self) -> &mut ConstantPool;
+    fn visit_node(&mut self, node: &Node);
+}
+
+impl<T> ArrayGenerator for T
+where
+    T: ArrayCore,
+{
+    fn generate_array_literal(&mut self, node: &Node) {
+        if let Node::ArrayLiteral(array) = node {
+            // Elements between spreads are collected in one go, spreads extend the array
+            let mut pending = 0;
+            let mut started = false;
+
+            for element in &array.elements {
+                match element {
+                    Some(Node::SpreadElement(spread)) => {
+                        if !started || pending > 0 {
+                            self.instructions().push(Instruction::NewArray(pending));
+                            if started {
+                                self.instructions().push(Instruction::ArrayExtend);
+                            }
+                        }
+                        started = true;
+                        pending = 0;
+
+                        self.visit_node(&spread.argument);
+                        self.instructions().push(Instruction::ArrayExtend);
+                    }
+                    Some(element) => {
+                        self.visit_node(element);
+                        pending += 1;
+                    }
+                    None => {
+                        let idx = self.constants().add(Constant::Undefined);
+                        self.instructions().push(Instruction::PushConst(idx));
+                        pending += 1;
+                    }
+                }
+            }
+
+            if !started || pending > 0 {
+                self.instructions().push(Instruction::NewArray(pending));
+                if started {
+                    self.instructions().push(Instruction::ArrayExtend);
+                }
+            }
+        }
+    }
+}
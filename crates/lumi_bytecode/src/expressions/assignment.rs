@@ -1,6 +1,12 @@
 use lumi_ast::Node;
 
-use crate::{scope::local_vars::ScopeManager, Instruction};
+use crate::{
+    expressions::compound_operation,
+    instruction::Label,
+    scope::{local_vars::ScopeManager, upvalues::UpvalueManager},
+    statements::destructuring::DestructuringGenerator,
+    Instruction,
+};
 
 pub trait AssignmentGenerator {
     fn generate_assignment_expression(&mut self, node: &Node);
@@ -10,43 +16,62 @@ pub trait AssignmentGenerator {
 pub trait AssignmentCore {
     fn instructions(&mut self) -> &mut Vec<Instruction>;
     fn visit_node(&mut self, node: &Node);
+    fn new_label(&mut self) -> Label;
+    fn patch_label(&mut self, label: Label);
+    fn emit_jump(&mut self, label: Label);
+    fn emit_jump_if_nullish(&mut self, label: Label);
 }
 
 impl<T> AssignmentGenerator for T
 where
-    T: AssignmentCore + ScopeManager,
+    T: AssignmentCore + UpvalueManager + ScopeManager + DestructuringGenerator,
 {
     fn generate_assignment_expression(&mut self, node: &Node) {
         if let Node::AssignmentExpression(expr) = node {
+            // The parser only destructures with a plain `->`
+            if let Node::Pattern(pattern) = &*expr.left {
+                self.visit_node(&expr.right);
+                let slot = self.new_temp_local();
+                self.instructions().push(Instruction::StoreVar(slot));
+                self.generate_destructuring(pattern, slot, false);
+                return;
+            }
+
             let var_name = match &*expr.left {
                 Node::Identifier(id) => id.clone(),
                 _ => unreachable!(), // TODO: should also give an informative error
             };
 
-            let idx = self.get_or_create_local(&var_name);
+            let slot = self.resolve_variable(&var_name);
 
-            if expr.operator == "+=" {
-                // Load current value of the variable
-                self.instructions().push(Instruction::LoadVar(idx));
-                // Visit right-hand side (pushes its value onto stack)
+            if expr.operator == "??=" {
+                let assign_label = self.new_label();
+                let end_label = self.new_label();
+
+                // Only a variable holding null or undefined is assigned
+                self.instructions().push(slot.load());
+                self.emit_jump_if_nullish(assign_label);
+                self.instructions().push(Instruction::Pop);
+                self.emit_jump(end_label);
+
+                self.patch_label(assign_label);
+                self.instructions().push(Instruction::Pop);
                 self.visit_node(&expr.right);
-                // Add the two values
-                self.instructions().push(Instruction::Add);
-                // Store the result back
-                self.instructions().push(Instruction::StoreVar(idx));
-            } else if expr.operator == "-=" {
+                self.instructions().push(slot.store());
+                self.patch_label(end_label);
+            } else if let Some(operation) = compound_operation(&expr.operator) {
                 // Load current value of the variable
-                self.instructions().push(Instruction::LoadVar(idx));
+                self.instructions().push(slot.load());
                 // Visit right-hand side (pushes its value onto stack)
                 self.visit_node(&expr.right);
-                // Add the two values
-                self.instructions().push(Instruction::Sub);
+                // Combine the two values, `+=` uses Add, `*=` uses Mul and so on
+                self.instructions().push(operation);
                 // Store the result back
-                self.instructions().push(Instruction::StoreVar(idx));
+                self.instructions().push(slot.store());
             } else {
                 // For simple assignment (=), just visit right and store
                 self.visit_node(&expr.right);
-                self.instructions().push(Instruction::StoreVar(idx));
+                self.instructions().push(slot.store());
             }
 
             // TODO: remove unused constants by doing a compiler pass after byte code generation.
@@ -55,17 +80,43 @@ where
 
     fn generate_call_expression(&mut self, node: &Node) {
         if let Node::CallExpression(expr) = node {
-            self.visit_node(&expr.callee);
+            if let Node::MemberExpression(member) = &*expr.callee {
+                if !member.computed {
+                    self.visit_node(&member.object);
+                    for arg in &expr.arguments {
+                        match arg {
+                            Node::NamedArgument(named) => self.visit_node(&named.value),
+                            _ => self.visit_node(arg),
+                        }
+                    }
+                    self.instructions().push(Instruction::CallMethod(
+                        member.property.name(),
+                        expr.arguments.len(),
+                    ));
+                    return;
+                }
+            }
 
+            // Any callee that evaluates to a function value can be called
+            self.visit_node(&expr.callee);
+            let mut names = Vec::new();
             for arg in &expr.arguments {
-                self.visit_node(arg);
+                match arg {
+                    Node::NamedArgument(named) => {
+                        self.visit_node(&named.value);
+                        names.push(named.name.name());
+                    }
+                    _ => self.visit_node(arg),
+                }
             }
 
-            // NOTE: here we assume that the callee is always an identifier (function name)
-            // In a more complete implementation, we would need to handle other cases (e.g.,
-            // function expressions, member expressions, etc.)
-            self.instructions()
-                .push(Instruction::CallFn(expr.callee.name().to_string()));
+            let argc = expr.arguments.len();
+            if names.is_empty() {
+                self.instructions().push(Instruction::CallValue(argc));
+            } else {
+                self.instructions()
+                    .push(Instruction::CallNamed(argc, names));
+            }
         }
     }
 }
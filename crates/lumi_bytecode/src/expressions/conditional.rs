@@ -0,0 +1,59 @@
+use lumi_ast::Node;
+
+use crate::{instruction::Label, Instruction};
+
+pub trait ConditionalGenerator {
+    fn generate_conditional_expression(&mut self, node: &Node);
+    fn generate_logical_expression(&mut self, node: &Node);
+}
+
+pub trait ConditionalCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn visit_node(&mut self, node: &Node);
+    fn new_label(&mut self) -> Label;
+    fn patch_label(&mut self, label: Label);
+    fn emit_jump(&mut self, label: Label);
+    fn emit_jump_if_false(&mut self, label: Label);
+    fn emit_jump_if_nullish(&mut self, label: Label);
+}
+
+impl<T> ConditionalGenerator for T
+where
+    T: ConditionalCore,
+{
+    fn generate_conditional_expression(&mut self, node: &Node) {
+        if let Node::ConditionalExpression(expr) = node {
+            let else_label = self.new_label();
+            let end_label = self.new_label();
+
+            self.visit_node(&expr.test);
+            self.emit_jump_if_false(else_label);
+            self.visit_node(&expr.consequent);
+            self.emit_jump(end_label);
+
+            self.patch_label(else_label);
+            self.visit_node(&expr.alternate);
+            self.patch_label(end_label);
+        }
+    }
+
+    fn generate_logical_expression(&mut self, node: &Node) {
+        if let Node::LogicalExpression(expr) = node {
+            if expr.operator != "??" {
+                return;
+            }
+            let right_label = self.new_label();
+            let end_label = self.new_label();
+
+            // A left value that isn't null or undefined is the result
+            self.visit_node(&expr.left);
+            self.emit_jump_if_nullish(right_label);
+            self.emit_jump(end_label);
+
+            self.patch_label(right_label);
+            self.instructions().push(Instruction::Pop);
+            self.visit_node(&expr.right);
+            self.patch_label(end_label);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use lumi_ast::{AssignmentExpression, MemberExpression, Node};
+
+use crate::{expressions::compound_operation, instruction::Label, Instruction};
+
+pub trait MemberGenerator {
+    fn generate_member_expression(&mut self, node: &Node);
+    fn generate_member_chain(&mut self, expr: &MemberExpression, end_label: Label);
+    fn generate_member_access(&mut self, expr: &MemberExpression);
+    fn generate_member_assignment(&mut self, expr: &AssignmentExpression);
+    fn generate_member_store(&mut self, target: &MemberExpression);
+}
+
+pub trait MemberCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn visit_node(&mut self, node: &Node);
+    fn new_label(&mut self) -> Label;
+    fn patch_label(&mut self, label: Label);
+    fn emit_jump_if_nullish(&mut self, label: Label);
+    fn emit_jump(&mut self, label: Label);
+}
+
+impl<T> MemberGenerator for T
+where
+    T: MemberCore,
+{
+    fn generate_member_expression(&mut self, node: &Node) {
+        if let Node::MemberExpression(expr) = node {
+            // A `?.` anywhere in the chain skips the rest of the chain
+            let end_label = self.new_label();
+            self.generate_member_chain(expr, end_label);
+            self.patch_label(end_label);
+        }
+    }
+
+    fn generate_member_chain(&mut self, expr: &MemberExpression, end_label: Label) {
+        match &*expr.object {
+            Node::MemberExpression(object) => self.generate_member_chain(object, end_label),
+            object => self.visit_node(object),
+        }
+
+        if expr.optional {
+            self.emit_jump_if_nullish(end_label);
+        }
+        if expr.computed {
+            self.visit_node(&expr.property);
+        }
+        self.generate_member_access(expr);
+    }
+
+    fn generate_member_access(&mut self, expr: &MemberExpression) {
+        if expr.computed {
+            self.instructions().push(Instruction::GetIndex);
+        } else {
+            let name = expr.property.name();
+            self.instructions().push(Instruction::GetProp(name));
+        }
+    }
+
+    fn generate_member_assignment(&mut self, expr: &AssignmentExpression) {
+        let Node::MemberExpression(target) = &*expr.left else {
+            return;
+        };
+
+        self.visit_node(&target.object);
+        if target.computed {
+            self.visit_node(&target.property);
+        }
+        let copy = if target.computed {
+            Instruction::Dup2
+        } else {
+            Instruction::Dup
+        };
+
+        if expr.operator == "??=" {
+            let assign_label = self.new_label();
+            let end_label = self.new_label();
+
+            // A current value that isn't null or undefined is kept, along with the object
+            self.instructions().push(copy);
+            self.generate_member_access(target);
+            self.emit_jump_if_nullish(assign_label);
+            let kept = if target.computed { 3 } else { 2 };
+            for _ in 0..kept {
+                self.instructions().push(Instruction::Pop);
+            }
+            self.emit_jump(end_label);
+
+            self.patch_label(assign_label);
+            self.instructions().push(Instruction::Pop);
+            self.visit_node(&expr.right);
+            self.generate_member_store(target);
+            self.patch_label(end_label);
+            return;
+        }
+
+        if let Some(operation) = compound_operation(&expr.operator) {
+            // Keep the object (and key) for the store, read the current value from copies
+            self.instructions().push(copy);
+            self.generate_member_access(target);
+            self.visit_node(&expr.right);
+            self.instructions().push(operation);
+        } else {
+            self.visit_node(&expr.right);
+        }
+        self.generate_member_store(target);
+    }
+
+    fn generate_member_store(&mut self, target: &MemberExpression) {
+        if target.computed {
+            self.instructions().push(Instruction::SetIndex);
+        } else {
+            let name = target.property.name();
+            self.instructions().push(Instruction::SetProp(name));
+        }
+    }
+}
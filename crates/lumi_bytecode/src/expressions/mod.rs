@@ -1,5 +1,17 @@
 mod arithmetic;
+mod array;
 mod assignment;
+mod conditional;
+mod member;
+mod object;
+mod string;
+mod update;
 
 pub use arithmetic::*;
+pub use array::*;
 pub use assignment::*;
+pub use conditional::*;
+pub use member::*;
+pub use object::*;
+pub use string::*;
+pub use update::*;
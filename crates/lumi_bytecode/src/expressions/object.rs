@@ -0,0 +1,39 @@
+use lumi_ast::{Node, PropertyKey};
+
+use crate::Instruction;
+
+pub trait ObjectGenerator {
+    fn generate_object_literal(&mut self, node: &Node);
+}
+
+pub trait ObjectCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn visit_node(&mut self, node: &Node);
+}
+
+impl<T> ObjectGenerator for T
+where
+    T: ObjectCore,
+{
+    fn generate_object_literal(&mut self, node: &Node) {
+        if let Node::ObjectLiteral(obj) = node {
+            self.instructions().push(Instruction::NewObject);
+
+            for property in &obj.properties {
+                // Setting a property consumes the object, keep a copy for the next one
+                self.instructions().push(Instruction::Dup);
+                match &property.key {
+                    PropertyKey::Named(name) => {
+                        self.visit_node(&property.value);
+                        self.instructions().push(Instruction::SetProp(name.clone()));
+                    }
+                    PropertyKey::Computed(key) => {
+                        self.visit_node(key);
+                        self.visit_node(&property.value);
+                        self.instructions().push(Instruction::SetIndex);
+                    }
+                }
+            }
+        }
+    }
+}
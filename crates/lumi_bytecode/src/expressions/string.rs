@@ -0,0 +1,43 @@
+use lumi_ast::Node;
+
+use crate::{Constant, ConstantPool, Instruction};
+
+pub trait StringGenerator {
+    fn generate_interpolated_string(&mut self, node: &Node);
+}
+
+pub trait StringCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn constants(&mut self) -> &mut ConstantPool;
+    fn visit_node(&mut self, node: &Node);
+}
+
+impl<T> StringGenerator for T
+where
+    T: StringCore,
+{
+    /// Lower an interpolated string to a chain of concatenations, e.g.
+    /// "count: ${x + 1}" becomes "count: " + (x + 1)
+    fn generate_interpolated_string(&mut self, node: &Node) {
+        if let Node::InterpolatedString(s) = node {
+            let mut parts = s.parts.iter();
+
+            // Start from a string so `Add` concatenates even when the first parts are numbers
+            match s.parts.first() {
+                Some(first @ Node::String(_)) => {
+                    parts.next();
+                    self.visit_node(first);
+                }
+                _ => {
+                    let idx = self.constants().add(Constant::String(String::new()));
+                    self.instructions().push(Instruction::PushConst(idx));
+                }
+            }
+
+            for part in parts {
+                self.visit_node(part);
+                self.instructions().push(Instruction::Add);
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use lumi_ast::Node;
+
+use crate::{
+    expressions::MemberGenerator,
+    scope::{local_vars::ScopeManager, upvalues::UpvalueManager},
+    Instruction,
+};
+
+pub trait UpdateGenerator {
+    fn generate_update_expression(&mut self, node: &Node);
+}
+
+pub trait UpdateCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn visit_node(&mut self, node: &Node);
+}
+
+impl<T> UpdateGenerator for T
+where
+    T: UpdateCore + UpvalueManager + ScopeManager + MemberGenerator,
+{
+    /// `++`/`--` leave the new value behind when used as a prefix and the old value as a postfix
+    fn generate_update_expression(&mut self, node: &Node) {
+        let Node::UnaryExpression(expr) = node else {
+            return;
+        };
+        let step = if expr.operator == "++" {
+            Instruction::Inc
+        } else {
+            Instruction::Dec
+        };
+
+        match &*expr.argument {
+            Node::Identifier(name) => {
+                let slot = self.resolve_variable(name);
+                self.instructions().push(slot.load());
+                if expr.prefix {
+                    self.instructions().push(step);
+                    self.instructions().push(Instruction::Dup);
+                } else {
+                    self.instructions().push(Instruction::Dup);
+                    self.instructions().push(step);
+                }
+                self.instructions().push(slot.store());
+            }
+            Node::MemberExpression(target) => {
+                self.visit_node(&target.object);
+                if target.computed {
+                    self.visit_node(&target.property);
+                    self.instructions().push(Instruction::Dup2);
+                } else {
+                    self.instructions().push(Instruction::Dup);
+                }
+                self.generate_member_access(target);
+
+                // The store consumes the object (and key), the result waits in a temporary
+                let result = self.new_temp_local();
+                if expr.prefix {
+                    self.instructions().push(step);
+                    self.instructions().push(Instruction::Dup);
+                    self.instructions().push(Instruction::StoreVar(result));
+                } else {
+                    self.instructions().push(Instruction::Dup);
+                    self.instructions().push(Instruction::StoreVar(result));
+                    self.instructions().push(step);
+                }
+                self.generate_member_store(target);
+                self.instructions().push(Instruction::LoadVar(result));
+            }
+            _ => {}
+        }
+    }
+}
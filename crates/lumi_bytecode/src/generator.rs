@@ -1,14 +1,28 @@
 use std::collections::HashMap;
 
-use lumi_ast::Node;
+use lumi_ast::{Node, Span};
 
 use crate::{
-    expressions::{ArithmeticCore, ArithmeticGenerator, AssignmentCore, AssignmentGenerator},
+    core::{Capture, Handler},
+    debug::DebugInfo,
+    expressions::{
+        ArithmeticCore, ArithmeticGenerator, ArrayCore, ArrayGenerator, AssignmentCore,
+        AssignmentGenerator, ConditionalCore, ConditionalGenerator, MemberCore, MemberGenerator,
+        ObjectCore, ObjectGenerator, StringCore, StringGenerator, UpdateCore, UpdateGenerator,
+    },
     instruction::{Constant, ConstantPool, Instruction, Label, PendingJump},
-    scope::local_vars::{ScopeCore, ScopeManager},
+    scope::{
+        local_vars::{ScopeCore, ScopeManager},
+        upvalues::{FunctionScope, UpvalueCore, UpvalueManager},
+    },
     statements::{
-        control_flow::{ControlFlowCore, ControlFlowGenerator},
+        class::{is_super_call, ClassCore, ClassGenerator},
+        control_flow::{ControlFlowCore, ControlFlowGenerator, LoopLabels},
+        destructuring::DestructuringCore,
+        enums::{EnumCore, EnumGenerator},
+        exception::{ExceptionCore, ExceptionGenerator, Finalizer},
         function::{FunctionCore, FunctionGenerator},
+        matching::{MatchCore, MatchGenerator},
         variable::{VariableCore, VariableGenerator},
     },
 };
@@ -16,6 +30,8 @@ use crate::{
 pub struct Bytecode {
     pub instructions: Vec<Instruction>,
     pub constants: Vec<Constant>,
+    pub handlers: Vec<Handler>,
+    pub debug_info: DebugInfo,
 }
 
 /// Bytecode generator for Lumi engine
@@ -26,6 +42,21 @@ pub struct BytecodeGenerator {
     pub unpatched_jumps: HashMap<Label, Vec<PendingJump>>,
     pub symbol_table: HashMap<String, usize>, // Maps variable names to their indices
     pub next_label_id: usize,                 // Index for the next variable to be added
+    pub next_label: usize,                    // Index for the next jump label
+    pub loops: Vec<LoopLabels>, // Loops and switches enclosing the node being generated
+    pub upvalues: Vec<(String, Capture)>, // Variables the current function captures
+    pub enclosing_functions: Vec<FunctionScope>, // Functions around the current one, innermost last
+    pub handlers: Vec<Handler>, // `try` ranges of the current function
+    pub finalizers: Vec<Finalizer>, // Finally blocks around the node being generated, innermost last
+    pub debug_info: DebugInfo,
+    current_span: Option<Span>, // Span of the node being generated
+    script_values: usize,       // Values the script's expression statements left on the stack
+}
+
+impl Default for BytecodeGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl BytecodeGenerator {
@@ -37,6 +68,15 @@ impl BytecodeGenerator {
             unpatched_jumps: HashMap::new(),
             symbol_table: HashMap::new(),
             next_label_id: 0,
+            next_label: 0,
+            loops: Vec::new(),
+            upvalues: Vec::new(),
+            enclosing_functions: Vec::new(),
+            handlers: Vec::new(),
+            finalizers: Vec::new(),
+            debug_info: DebugInfo::default(),
+            current_span: None,
+            script_values: 0,
         }
     }
 
@@ -45,14 +85,48 @@ impl BytecodeGenerator {
         Bytecode {
             instructions: self.instructions.clone(),
             constants: self.constants.values.clone(),
+            handlers: self.handlers.clone(),
+            debug_info: self.debug_info.clone(),
         }
     }
 
     fn visit_node(&mut self, node: &Node) {
+        let Some(span) = node.span() else {
+            return self.generate_node(node);
+        };
+
+        // Attribute the node's instructions to its span, then hand back to the parent
+        let parent_span = self.current_span.replace(span.clone());
+        self.debug_info
+            .main
+            .record(self.instructions.len(), span.clone());
+
+        self.generate_node(node);
+
+        self.current_span = parent_span;
+        if let Some(parent_span) = &self.current_span {
+            self.debug_info
+                .main
+                .record(self.instructions.len(), parent_span.clone());
+        }
+    }
+
+    fn generate_node(&mut self, node: &Node) {
         match node {
             Node::Program(program) => {
+                <Self as FunctionGenerator>::declare_functions(self, &program.body);
                 for stmt in &program.body {
-                    self.visit_node(stmt);
+                    match stmt {
+                        // The values of the script's own expression statements are left
+                        // on the stack for the host to read
+                        Node::ExpressionStatement(stmt) => {
+                            self.visit_node(&stmt.expression);
+                            if !matches!(*stmt.expression, Node::AssignmentExpression(_)) {
+                                self.script_values += 1;
+                            }
+                        }
+                        stmt => self.visit_node(stmt),
+                    }
                 }
             }
             Node::VariableDeclaration(_decl) => {
@@ -61,16 +135,62 @@ impl BytecodeGenerator {
             Node::FunctionDeclaration(_fn) => {
                 <Self as FunctionGenerator>::generate_function_declaration(self, node);
             }
+            Node::FunctionExpression(_expr) => {
+                <Self as FunctionGenerator>::generate_function_expression(self, node);
+            }
+            Node::ClassDeclaration(_class) => {
+                <Self as ClassGenerator>::generate_class_declaration(self, node);
+            }
+            Node::EnumDeclaration(_decl) => {
+                <Self as EnumGenerator>::generate_enum_declaration(self, node);
+            }
+            Node::NewExpression(_expr) => {
+                <Self as ClassGenerator>::generate_new_expression(self, node);
+            }
+            Node::ReturnStatement(_stmt) => {
+                <Self as FunctionGenerator>::generate_return_statement(self, node);
+            }
+            Node::ThrowStatement(_stmt) => {
+                <Self as ExceptionGenerator>::generate_throw_statement(self, node);
+            }
+            Node::TryStatement(_stmt) => {
+                <Self as ExceptionGenerator>::generate_try_statement(self, node);
+            }
+            Node::SwitchStatement(_stmt) => {
+                <Self as MatchGenerator>::generate_switch_statement(self, node);
+            }
+            Node::MatchExpression(_expr) => {
+                <Self as MatchGenerator>::generate_match_expression(self, node);
+            }
             Node::IfStatement(_stmt) => {
                 <Self as ControlFlowGenerator>::generate_if_statement(self, node);
             }
-            Node::ForStatement(_stmt) => {
+            Node::ForStatement(_) | Node::ForEachStatement(_) => {
                 <Self as ControlFlowGenerator>::generate_for_statement(self, node);
             }
+            Node::WhileStatement(_stmt) => {
+                <Self as ControlFlowGenerator>::generate_while_statement(self, node);
+            }
+            Node::DoWhileStatement(_stmt) => {
+                <Self as ControlFlowGenerator>::generate_do_while_statement(self, node);
+            }
+            Node::LoopStatement(_stmt) => {
+                <Self as ControlFlowGenerator>::generate_loop_statement(self, node);
+            }
+            Node::LabeledStatement(_stmt) => {
+                <Self as ControlFlowGenerator>::generate_labeled_statement(self, node);
+            }
+            Node::BreakStatement(_stmt) => {
+                <Self as ControlFlowGenerator>::generate_break_statement(self, node);
+            }
+            Node::ContinueStatement(_stmt) => {
+                <Self as ControlFlowGenerator>::generate_continue_statement(self, node);
+            }
             Node::BlockStatement(block) => {
                 // NOTE: push instruction for entering a new block scope
+                <Self as FunctionGenerator>::declare_functions(self, &block.body);
                 for stmt in &block.body {
-                    self.visit_node(&stmt);
+                    self.visit_node(stmt);
                 }
                 // NOTE: push instruction that we are leaving the block scope
             }
@@ -80,6 +200,22 @@ impl BytecodeGenerator {
             }
             Node::ExpressionStatement(stmt) => {
                 self.visit_node(&stmt.expression);
+                // Assignments store their value without leaving it on the stack
+                if !matches!(*stmt.expression, Node::AssignmentExpression(_)) {
+                    self.instructions.push(Instruction::Pop);
+                }
+            }
+            Node::AssignmentExpression(expr) if matches!(*expr.left, Node::MemberExpression(_)) => {
+                <Self as MemberGenerator>::generate_member_assignment(self, expr)
+            }
+            Node::ArrayLiteral(_array) => {
+                <Self as ArrayGenerator>::generate_array_literal(self, node);
+            }
+            Node::ObjectLiteral(_obj) => {
+                <Self as ObjectGenerator>::generate_object_literal(self, node);
+            }
+            Node::MemberExpression(_expr) => {
+                <Self as MemberGenerator>::generate_member_expression(self, node);
             }
             Node::AssignmentExpression(_expr) => {
                 <Self as AssignmentGenerator>::generate_assignment_expression(self, node)
@@ -87,16 +223,46 @@ impl BytecodeGenerator {
             Node::BinaryExpression(_expr) => {
                 <Self as ArithmeticGenerator>::generate_binary_expression(self, node);
             }
+            Node::ConditionalExpression(_expr) => {
+                <Self as ConditionalGenerator>::generate_conditional_expression(self, node);
+            }
+            Node::LogicalExpression(_expr) => {
+                <Self as ConditionalGenerator>::generate_logical_expression(self, node);
+            }
+            Node::UnaryExpression(expr) if matches!(expr.operator.as_str(), "++" | "--") => {
+                <Self as UpdateGenerator>::generate_update_expression(self, node);
+            }
+            Node::UnaryExpression(_expr) => {
+                <Self as ArithmeticGenerator>::generate_unary_expression(self, node);
+            }
+            Node::CallExpression(expr) if is_super_call(expr) => {
+                <Self as ClassGenerator>::generate_super_call(self, expr);
+            }
             Node::CallExpression(_expr) => {
                 <Self as AssignmentGenerator>::generate_call_expression(self, node);
             }
+            Node::InterpolatedString(_s) => {
+                <Self as StringGenerator>::generate_interpolated_string(self, node);
+            }
             Node::Identifier(id) => {
                 if let Some(idx) = <Self as ScopeManager>::get_local(self, id) {
                     self.instructions.push(Instruction::LoadVar(*idx));
+                } else if let Some(idx) = <Self as UpvalueManager>::resolve_upvalue(self, id) {
+                    self.instructions.push(Instruction::LoadUpvalue(idx));
                 } else {
                     <Self as ScopeManager>::get_or_create_local(self, &id.to_string());
                 }
             }
+            Node::Integer(num) => {
+                let idx = self.constants.add(Constant::Integer(*num));
+                self.instructions.push(Instruction::PushConst(idx));
+            }
+            Node::BigInt(digits) => {
+                // The lexer only produces valid decimal digits
+                let value = digits.parse().unwrap_or_default();
+                let idx = self.constants.add(Constant::BigInt(value));
+                self.instructions.push(Instruction::PushConst(idx));
+            }
             Node::Number(num) => {
                 let idx = self.constants.add(Constant::Number(*num));
                 self.instructions.push(Instruction::PushConst(idx));
@@ -109,6 +275,23 @@ impl BytecodeGenerator {
                 let idx = self.constants.add(Constant::Boolean(*b));
                 self.instructions.push(Instruction::PushConst(idx));
             }
+            Node::Null => {
+                let idx = self.constants.add(Constant::Null);
+                self.instructions.push(Instruction::PushConst(idx));
+            }
+            Node::Undefined => {
+                let idx = self.constants.add(Constant::Undefined);
+                self.instructions.push(Instruction::PushConst(idx));
+            }
+            Node::This | Node::Super => {
+                let name = if matches!(node, Node::This) {
+                    "this"
+                } else {
+                    "super"
+                };
+                let slot = <Self as UpvalueManager>::resolve_variable(self, name);
+                self.instructions.push(slot.load());
+            }
             // Handle other node types...
             _ => {}
         }
@@ -133,6 +316,16 @@ impl ScopeCore for BytecodeGenerator {
     }
 }
 
+impl UpvalueCore for BytecodeGenerator {
+    fn enclosing_functions(&mut self) -> &mut Vec<FunctionScope> {
+        &mut self.enclosing_functions
+    }
+
+    fn upvalues(&mut self) -> &mut Vec<(String, Capture)> {
+        &mut self.upvalues
+    }
+}
+
 impl AssignmentCore for BytecodeGenerator {
     fn instructions(&mut self) -> &mut Vec<Instruction> {
         &mut self.instructions
@@ -141,6 +334,52 @@ impl AssignmentCore for BytecodeGenerator {
     fn visit_node(&mut self, node: &Node) {
         self.visit_node(node)
     }
+
+    fn new_label(&mut self) -> Label {
+        <Self as ControlFlowCore>::new_label(self)
+    }
+
+    fn patch_label(&mut self, label: Label) {
+        <Self as ControlFlowCore>::patch_label(self, label)
+    }
+
+    fn emit_jump(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump(self, label)
+    }
+
+    fn emit_jump_if_nullish(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump_if_nullish(self, label)
+    }
+}
+
+impl ConditionalCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+
+    fn new_label(&mut self) -> Label {
+        <Self as ControlFlowCore>::new_label(self)
+    }
+
+    fn patch_label(&mut self, label: Label) {
+        <Self as ControlFlowCore>::patch_label(self, label)
+    }
+
+    fn emit_jump(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump(self, label)
+    }
+
+    fn emit_jump_if_false(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump_if_false(self, label)
+    }
+
+    fn emit_jump_if_nullish(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump_if_nullish(self, label)
+    }
 }
 
 impl ArithmeticCore for BytecodeGenerator {
@@ -153,6 +392,104 @@ impl ArithmeticCore for BytecodeGenerator {
     }
 }
 
+impl ClassCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn constants(&mut self) -> &mut ConstantPool {
+        &mut self.constants
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+}
+
+impl EnumCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn constants(&mut self) -> &mut ConstantPool {
+        &mut self.constants
+    }
+}
+
+impl UpdateCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+}
+
+impl MemberCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+
+    fn new_label(&mut self) -> Label {
+        <Self as ControlFlowCore>::new_label(self)
+    }
+
+    fn patch_label(&mut self, label: Label) {
+        <Self as ControlFlowCore>::patch_label(self, label)
+    }
+
+    fn emit_jump_if_nullish(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump_if_nullish(self, label)
+    }
+
+    fn emit_jump(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump(self, label)
+    }
+}
+
+impl ArrayCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn constants(&mut self) -> &mut ConstantPool {
+        &mut self.constants
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+}
+
+impl ObjectCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+}
+
+impl StringCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn constants(&mut self) -> &mut ConstantPool {
+        &mut self.constants
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+}
+
 impl VariableCore for BytecodeGenerator {
     fn instructions(&mut self) -> &mut Vec<Instruction> {
         &mut self.instructions
@@ -168,6 +505,18 @@ impl FunctionCore for BytecodeGenerator {
         &mut self.instructions
     }
 
+    fn new_label(&mut self) -> Label {
+        <Self as ControlFlowCore>::new_label(self)
+    }
+
+    fn patch_label(&mut self, label: Label) {
+        <Self as ControlFlowCore>::patch_label(self, label)
+    }
+
+    fn emit_jump_if_false(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump_if_false(self, label)
+    }
+
     fn visit_node(&mut self, node: &Node) {
         self.visit_node(node)
     }
@@ -180,6 +529,18 @@ impl FunctionCore for BytecodeGenerator {
         &mut self.symbol_table
     }
 
+    fn debug_info(&mut self) -> &mut DebugInfo {
+        &mut self.debug_info
+    }
+
+    fn handlers(&mut self) -> &mut Vec<Handler> {
+        &mut self.handlers
+    }
+
+    fn finalizers(&mut self) -> &mut Vec<Finalizer> {
+        &mut self.finalizers
+    }
+
     fn next_label_id(&self) -> usize {
         self.next_label_id
     }
@@ -189,6 +550,108 @@ impl FunctionCore for BytecodeGenerator {
     }
 }
 
+impl ExceptionCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn handlers(&mut self) -> &mut Vec<Handler> {
+        &mut self.handlers
+    }
+
+    fn finalizers(&mut self) -> &mut Vec<Finalizer> {
+        &mut self.finalizers
+    }
+
+    fn loop_depth(&self) -> usize {
+        self.loops.len()
+    }
+
+    /// Statements inside a function start on an empty stack
+    fn stack_depth(&self) -> usize {
+        match self.enclosing_functions.is_empty() {
+            true => self.script_values,
+            false => 0,
+        }
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+
+    fn new_label(&mut self) -> Label {
+        <Self as ControlFlowCore>::new_label(self)
+    }
+
+    fn patch_label(&mut self, label: Label) {
+        <Self as ControlFlowCore>::patch_label(self, label)
+    }
+
+    fn emit_jump(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump(self, label)
+    }
+}
+
+impl MatchCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn constants(&mut self) -> &mut ConstantPool {
+        &mut self.constants
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+
+    fn new_label(&mut self) -> Label {
+        <Self as ControlFlowCore>::new_label(self)
+    }
+
+    fn patch_label(&mut self, label: Label) {
+        <Self as ControlFlowCore>::patch_label(self, label)
+    }
+
+    fn emit_jump(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump(self, label)
+    }
+
+    fn emit_jump_if_false(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump_if_false(self, label)
+    }
+
+    fn loops(&mut self) -> &mut Vec<LoopLabels> {
+        &mut self.loops
+    }
+}
+
+impl DestructuringCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn constants(&mut self) -> &mut ConstantPool {
+        &mut self.constants
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+
+    fn new_label(&mut self) -> Label {
+        <Self as ControlFlowCore>::new_label(self)
+    }
+
+    fn patch_label(&mut self, label: Label) {
+        <Self as ControlFlowCore>::patch_label(self, label)
+    }
+
+    fn emit_jump_if_false(&mut self, label: Label) {
+        <Self as ControlFlowCore>::emit_jump_if_false(self, label)
+    }
+}
+
 impl ControlFlowCore for BytecodeGenerator {
     fn instructions(&mut self) -> &mut Vec<Instruction> {
         &mut self.instructions
@@ -200,8 +663,8 @@ impl ControlFlowCore for BytecodeGenerator {
 
     /// Create a new label
     fn new_label(&mut self) -> Label {
-        let label = Label(self.next_label_id);
-        self.next_label_id += 1;
+        let label = Label(self.next_label);
+        self.next_label += 1;
         label
     }
 
@@ -233,6 +696,12 @@ impl ControlFlowCore for BytecodeGenerator {
                         }
                         self.instructions[pos] = Instruction::JumpIfFalse(position);
                     }
+                    PendingJump::JumpIfNullish(pos) => {
+                        if pos >= self.instructions.len() {
+                            panic!("Pending jump position {} out of range", pos);
+                        }
+                        self.instructions[pos] = Instruction::JumpIfNullish(position);
+                    }
                 }
             }
         }
@@ -272,6 +741,26 @@ impl ControlFlowCore for BytecodeGenerator {
         }
     }
 
+    /// Emit a JumpIfNullish instruction and push to unpatched_jumps
+    fn emit_jump_if_nullish(&mut self, label: Label) {
+        let pos = self.instructions.len();
+
+        if let Some(&target) = self.label_positions.get(&label) {
+            self.instructions.push(Instruction::JumpIfNullish(target));
+        } else {
+            self.instructions
+                .push(Instruction::JumpIfNullish(usize::MAX));
+            self.unpatched_jumps
+                .entry(label)
+                .or_default()
+                .push(PendingJump::JumpIfNullish(pos));
+        }
+    }
+
+    fn loops(&mut self) -> &mut Vec<LoopLabels> {
+        &mut self.loops
+    }
+
     /// Emit an instruction
     fn emit(&mut self, instr: Instruction) {
         self.instructions.push(instr);
@@ -1,3 +1,5 @@
+use num_bigint::BigInt;
+
 use crate::core::FunctionObj;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -7,6 +9,7 @@ pub struct Label(pub usize);
 pub enum PendingJump {
     Jump(usize),
     JumpIfFalse(usize),
+    JumpIfNullish(usize),
 }
 
 /// This module defines the bytecode instructions for the Lumi engine.
@@ -14,12 +17,16 @@ pub enum PendingJump {
 pub enum Instruction {
     PushConst(usize), // Push a constant value onto the stack with index of the constant in the (bytecode) constant pool.
     Pop,              // Pop the top value from the stack
+    Dup,              // Push a copy of the top value
+    Dup2,             // Push copies of the top two values, keeping their order
     Add,
     // Arithmetic operations
     Sub,
     Mul,
     Div,
     Mod,
+    Pow,
+    Neg,
     Inc,
     Dec,
     Eq,
@@ -27,17 +34,54 @@ pub enum Instruction {
     Lt,
     Gt,
     Leq,
-    Geq,                // Comparison operations
-    Jump(usize),        // Unconditional jump to a specific instruction index
-    JumpIfTrue(usize),  // Conditional jump if the top value is true
-    JumpIfFalse(usize), // Conditional jump if the top value is false
-    CallFn(String),     // Call a function with a given name
-    Return,             // Return from the current function
-    LoadVar(usize),     // Load a variable by index
-    StoreVar(usize),    // Store the top value into a variable by index
-    Print,              // Print the top value on the stack
-    Nop,                // No operation (used for padding or alignment)
-    Halt,               // Stop execution
+    Geq,                                      // Comparison operations
+    Jump(usize),                              // Unconditional jump to a specific instruction index
+    JumpIfTrue(usize),                        // Conditional jump if the top value is true
+    JumpIfFalse(usize),                       // Conditional jump if the top value is false
+    JumpIfNullish(usize), // Replace a null or undefined top value with undefined and jump, used by `?.`
+    JumpTable(i64, Vec<usize>, usize), // Pop an integer and jump to the target at its offset from the minimum, or to the default
+    CallValue(usize),                  // Call the function below the given number of arguments
+    CallNamed(usize, Vec<String>), // Like `CallValue`, the last arguments are passed by the given parameter names
+    CallMethod(String, usize), // Call a built-in method on the value below the given number of arguments
+    Return,                    // Return from the current function
+    LoadVar(usize),            // Load a variable by index
+    StoreVar(usize),           // Store the top value into a variable by index
+    LoadUpvalue(usize),        // Load a variable captured from an enclosing function
+    StoreUpvalue(usize),       // Store the top value into a captured variable
+    MakeClosure(usize),        // Push a closure of the function constant, capturing its upvalues
+    CloseVar(usize), // Move a captured variable back out of its cell, later closures capture a new cell
+    NewObject,       // Allocate an empty object and push it
+    NewArray(usize), // Pop the given number of values and push an array holding them
+    ArrayExtend,     // Pop an array or string and append its elements to the array on top
+    GetProp(String), // Pop an object and push the value of the named property
+    SetProp(String), // Pop a value and an object and set the named property
+    GetIndex,        // Pop a key and an object or array and push the element
+    SetIndex,        // Pop a value, a key and an object or array and set the element
+    NewClass(String), // Pop the parent class, or undefined, and push a new class
+    DefineMethod(String, MethodKind, bool), // Pop a closure and add it to the class on top, static when the flag is set
+    New(usize), // Create an instance of the class below the given number of arguments and run its constructor
+    CallSuperConstructor, // Pop an array of arguments, an instance and a class and run the class constructor on the instance
+    CallSuperMethod(String, usize), // Call a method of the class below an instance and the given number of arguments on that instance
+    Throw,   // Pop a value and unwind to the innermost handler covering the instruction
+    Rethrow, // Like `Throw`, but the value keeps the stack trace of where it was first thrown
+    MatchArray(usize, bool), // Pop a value and push whether it is an array of the given length, or at least that long when the flag is set
+    MatchObject(Vec<String>), // Pop a value and push whether it is an object with the named properties
+    ArraySlice(usize), // Pop an array and push a new array of its elements from the given index on
+    NoMatch,           // Pop a value and fail because no match arm matched it
+    MakeVariant(String, String, Vec<String>), // Pop a value per field name and push a value of the named enum variant
+    MatchVariant(String, String), // Pop a value and push whether it is a value of the named enum variant
+    Print,                        // Print the top value on the stack
+    Nop,                          // No operation (used for padding or alignment)
+    Halt,                         // Stop execution
+}
+
+/// The kinds of functions a class holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodKind {
+    Constructor,
+    Method,
+    Getter,
+    Setter,
 }
 
 /// Represents a constant pool for bytecode instructions
@@ -56,7 +100,9 @@ impl ConstantPool {
 /// Represents a constant value in the bytecode
 #[derive(Debug, Clone, PartialEq)]
 pub enum Constant {
+    Integer(i64),
     Number(f64),
+    BigInt(BigInt),
     String(String),
     Boolean(bool),
     Function(FunctionObj),
@@ -1,4 +1,5 @@
 mod core;
+mod debug;
 mod expressions;
 mod generator;
 mod instruction;
@@ -6,9 +7,10 @@ mod scope;
 mod statements;
 
 pub use core::*;
+pub use debug::{DebugInfo, SpanTable};
 pub use generator::Bytecode;
 pub use generator::BytecodeGenerator;
-pub use instruction::{Constant, ConstantPool, Instruction};
+pub use instruction::{Constant, ConstantPool, Instruction, MethodKind};
 
 pub fn generate(source: &str) {
     let mut parser = lumi_parser::Parser::new(source);
@@ -1 +1,2 @@
 pub mod local_vars;
+pub mod upvalues;
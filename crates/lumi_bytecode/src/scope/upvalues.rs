@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use crate::{
+    core::Capture,
+    scope::local_vars::{ScopeCore, ScopeManager},
+    Instruction,
+};
+
+/// The variables of a function that encloses the one being generated
+#[derive(Debug, Default)]
+pub struct FunctionScope {
+    pub locals: HashMap<String, usize>,
+    pub upvalues: Vec<(String, Capture)>,
+}
+
+/// Where a variable lives for the function being generated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VarSlot {
+    Local(usize),
+    Upvalue(usize),
+}
+
+impl VarSlot {
+    pub fn load(self) -> Instruction {
+        match self {
+            VarSlot::Local(idx) => Instruction::LoadVar(idx),
+            VarSlot::Upvalue(idx) => Instruction::LoadUpvalue(idx),
+        }
+    }
+
+    pub fn store(self) -> Instruction {
+        match self {
+            VarSlot::Local(idx) => Instruction::StoreVar(idx),
+            VarSlot::Upvalue(idx) => Instruction::StoreUpvalue(idx),
+        }
+    }
+}
+
+pub trait UpvalueManager {
+    fn resolve_upvalue(&mut self, name: &str) -> Option<usize>;
+    fn resolve_variable(&mut self, name: &str) -> VarSlot;
+}
+
+pub trait UpvalueCore {
+    fn enclosing_functions(&mut self) -> &mut Vec<FunctionScope>;
+    fn upvalues(&mut self) -> &mut Vec<(String, Capture)>;
+}
+
+impl<T> UpvalueManager for T
+where
+    T: UpvalueCore + ScopeCore,
+{
+    /// Index of the upvalue holding a variable of an enclosing function, capturing it
+    /// through every function in between
+    fn resolve_upvalue(&mut self, name: &str) -> Option<usize> {
+        let current = FunctionScope {
+            locals: std::mem::take(self.local_vars_mut()),
+            upvalues: std::mem::take(self.upvalues()),
+        };
+        self.enclosing_functions().push(current);
+        let idx = capture(self.enclosing_functions(), name);
+        let current = self.enclosing_functions().pop().unwrap_or_default();
+
+        *self.local_vars_mut() = current.locals;
+        *self.upvalues() = current.upvalues;
+        idx
+    }
+
+    /// The local or upvalue for a variable, unknown names become new locals
+    fn resolve_variable(&mut self, name: &str) -> VarSlot {
+        if let Some(&idx) = self.local_vars().get(name) {
+            return VarSlot::Local(idx);
+        }
+        if let Some(idx) = self.resolve_upvalue(name) {
+            return VarSlot::Upvalue(idx);
+        }
+        VarSlot::Local(self.get_or_create_local(name))
+    }
+}
+
+/// Find `name` as an upvalue of the innermost function in `functions`, adding it to
+/// that function and the ones around it when it isn't captured yet
+fn capture(functions: &mut [FunctionScope], name: &str) -> Option<usize> {
+    let (function, enclosing) = functions.split_last_mut()?;
+    if let Some(idx) = function.upvalues.iter().position(|(n, _)| n == name) {
+        return Some(idx);
+    }
+
+    let parent = enclosing.last()?;
+    let source = match parent.locals.get(name) {
+        Some(&idx) => Capture::Local(idx),
+        None => Capture::Upvalue(capture(enclosing, name)?),
+    };
+    function.upvalues.push((name.to_string(), source));
+    Some(function.upvalues.len() - 1)
+}
@@ -0,0 +1,304 @@
+use lumi_ast::{
+    AssignmentExpression, BlockStatement, CallExpression, ClassDeclaration, ClassMemberKind,
+    ExpressionStatement, MemberExpression, Node, Parameter, SpreadElement,
+};
+
+use crate::{
+    scope::{
+        local_vars::{ScopeCore, ScopeManager},
+        upvalues::UpvalueManager,
+    },
+    statements::function::FunctionGenerator,
+    Constant, ConstantPool, Instruction, MethodKind,
+};
+
+pub trait ClassGenerator {
+    fn generate_class_declaration(&mut self, node: &Node);
+    fn generate_new_expression(&mut self, node: &Node);
+    fn generate_super_call(&mut self, expr: &CallExpression);
+    fn generate_method(
+        &mut self,
+        class_name: &str,
+        key: &str,
+        params: &[Parameter],
+        body: &Node,
+        kind: MethodKind,
+        is_static: bool,
+    );
+}
+
+pub trait ClassCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn constants(&mut self) -> &mut ConstantPool;
+    fn visit_node(&mut self, node: &Node);
+}
+
+impl<T> ClassGenerator for T
+where
+    T: ClassCore + FunctionGenerator + ScopeCore + UpvalueManager,
+{
+    fn generate_class_declaration(&mut self, node: &Node) {
+        let Node::ClassDeclaration(class) = node else {
+            return;
+        };
+        let name = class.id.name();
+        let slot = self.get_or_create_local(&name);
+
+        // Methods reach the parent class through `super`, a local of its own per class
+        let shadowed_super = match &class.superclass {
+            Some(superclass) => {
+                self.visit_node(superclass);
+                let super_slot = self.new_temp_local();
+                self.instructions().push(Instruction::Dup);
+                self.instructions().push(Instruction::StoreVar(super_slot));
+                Some(
+                    self.local_vars_mut()
+                        .insert("super".to_string(), super_slot),
+                )
+            }
+            None => {
+                let idx = self.constants().add(Constant::Undefined);
+                self.instructions().push(Instruction::PushConst(idx));
+                None
+            }
+        };
+
+        // The class is stored before its members are generated, so they can refer to it
+        self.instructions()
+            .push(Instruction::NewClass(name.clone()));
+        self.instructions().push(Instruction::Dup);
+        self.instructions().push(Instruction::StoreVar(slot));
+
+        let (params, body) = constructor(class);
+        self.generate_method(
+            &name,
+            "constructor",
+            &params,
+            &body,
+            MethodKind::Constructor,
+            false,
+        );
+
+        for member in &class.members {
+            let kind = match member.kind {
+                ClassMemberKind::Field if member.is_static => {
+                    self.instructions().push(Instruction::Dup);
+                    match &member.value {
+                        Some(value) => self.visit_node(value),
+                        None => {
+                            let idx = self.constants().add(Constant::Undefined);
+                            self.instructions().push(Instruction::PushConst(idx));
+                        }
+                    }
+                    self.instructions()
+                        .push(Instruction::SetProp(member.name.clone()));
+                    continue;
+                }
+                // Instance fields are assigned by the constructor
+                ClassMemberKind::Field | ClassMemberKind::Constructor => continue,
+                ClassMemberKind::Method => MethodKind::Method,
+                ClassMemberKind::Getter => MethodKind::Getter,
+                ClassMemberKind::Setter => MethodKind::Setter,
+            };
+            if let Some(Node::FunctionExpression(method)) = member.value.as_deref() {
+                self.generate_method(
+                    &name,
+                    &member.name,
+                    &method.params,
+                    &method.body,
+                    kind,
+                    member.is_static,
+                );
+            }
+        }
+        self.instructions().push(Instruction::Pop);
+
+        match shadowed_super {
+            Some(Some(shadowed)) => self.local_vars_mut().insert("super".to_string(), shadowed),
+            Some(None) => self.local_vars_mut().remove("super"),
+            None => None,
+        };
+    }
+
+    fn generate_new_expression(&mut self, node: &Node) {
+        if let Node::NewExpression(expr) = node {
+            self.visit_node(&expr.callee);
+            for arg in &expr.arguments {
+                match arg {
+                    Node::NamedArgument(named) => self.visit_node(&named.value),
+                    _ => self.visit_node(arg),
+                }
+            }
+            self.instructions()
+                .push(Instruction::New(expr.arguments.len()));
+        }
+    }
+
+    /// `super(...)` runs the parent constructor on `this`, `super.name(...)` a parent method
+    fn generate_super_call(&mut self, expr: &CallExpression) {
+        let super_slot = self.resolve_variable("super");
+        let this_slot = self.resolve_variable("this");
+        self.instructions().push(super_slot.load());
+        self.instructions().push(this_slot.load());
+
+        if let Node::MemberExpression(member) = &*expr.callee {
+            for arg in &expr.arguments {
+                self.visit_node(arg);
+            }
+            self.instructions().push(Instruction::CallSuperMethod(
+                member.property.name(),
+                expr.arguments.len(),
+            ));
+            return;
+        }
+
+        // The arguments travel as one array, so they can be spread from a rest parameter
+        let has_spread = expr
+            .arguments
+            .iter()
+            .any(|arg| matches!(arg, Node::SpreadElement(_)));
+        if has_spread {
+            self.instructions().push(Instruction::NewArray(0));
+            for arg in &expr.arguments {
+                match arg {
+                    Node::SpreadElement(spread) => self.visit_node(&spread.argument),
+                    _ => {
+                        self.visit_node(arg);
+                        self.instructions().push(Instruction::NewArray(1));
+                    }
+                }
+                self.instructions().push(Instruction::ArrayExtend);
+            }
+        } else {
+            for arg in &expr.arguments {
+                self.visit_node(arg);
+            }
+            self.instructions()
+                .push(Instruction::NewArray(expr.arguments.len()));
+        }
+        self.instructions().push(Instruction::CallSuperConstructor);
+    }
+
+    /// Add a method to the class on top of the stack, its function is named after the
+    /// class so errors and debug info can tell methods of different classes apart
+    fn generate_method(
+        &mut self,
+        class_name: &str,
+        key: &str,
+        params: &[Parameter],
+        body: &Node,
+        kind: MethodKind,
+        is_static: bool,
+    ) {
+        let name = match kind {
+            MethodKind::Constructor => class_name.to_string(),
+            _ => format!("{}.{}", class_name, key),
+        };
+        let method = self.code_gen_function_body(Some(name), params, body, true);
+        let idx = self.constants().add(Constant::Function(method));
+        self.instructions().push(Instruction::MakeClosure(idx));
+        self.instructions()
+            .push(Instruction::DefineMethod(key.to_string(), kind, is_static));
+    }
+}
+
+/// Whether `expr` calls the parent constructor or a parent method
+pub fn is_super_call(expr: &CallExpression) -> bool {
+    match &*expr.callee {
+        Node::Super => true,
+        Node::MemberExpression(member) => matches!(*member.object, Node::Super),
+        _ => false,
+    }
+}
+
+/// The parameters and body of the constructor of `class`, its own or an implicit one that
+/// passes every argument on to the parent. Instance fields are assigned at the start, or
+/// right after `super(...)` returns in a class with a parent
+fn constructor(class: &ClassDeclaration) -> (Vec<Parameter>, Node) {
+    let fields: Vec<Node> = class
+        .members
+        .iter()
+        .filter(|member| member.kind == ClassMemberKind::Field && !member.is_static)
+        .map(|member| {
+            let target = Node::MemberExpression(MemberExpression {
+                object: Box::new(Node::This),
+                property: Box::new(Node::Identifier(member.name.clone())),
+                computed: false,
+                optional: false,
+                span: None,
+            });
+            let value = member.value.clone().unwrap_or(Box::new(Node::Undefined));
+            statement(Node::AssignmentExpression(AssignmentExpression {
+                left: Box::new(target),
+                operator: "->".to_string(),
+                right: value,
+                span: member.span.clone(),
+            }))
+        })
+        .collect();
+
+    let own = class
+        .members
+        .iter()
+        .find_map(|member| match member.value.as_deref() {
+            Some(Node::FunctionExpression(method))
+                if member.kind == ClassMemberKind::Constructor =>
+            {
+                Some(method)
+            }
+            _ => None,
+        });
+
+    let (params, mut body) = match own {
+        Some(method) => {
+            let body = match &*method.body {
+                Node::BlockStatement(block) => block.body.clone(),
+                body => vec![body.clone()],
+            };
+            (method.params.clone(), body)
+        }
+        None if class.superclass.is_some() => {
+            let args = Node::Identifier("args".to_string());
+            let rest = Parameter {
+                name: Box::new(args.clone()),
+                param_type: None,
+                default_value: None,
+                optional: false,
+                is_rest: true,
+                span: None,
+            };
+            let forward = Node::CallExpression(CallExpression {
+                callee: Box::new(Node::Super),
+                arguments: vec![Node::SpreadElement(SpreadElement {
+                    argument: Box::new(args),
+                    span: None,
+                })],
+                span: None,
+            });
+            (vec![rest], vec![statement(forward)])
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+
+    let after_super = body
+        .iter()
+        .position(|stmt| match stmt {
+            Node::ExpressionStatement(stmt) => {
+                matches!(&*stmt.expression, Node::CallExpression(call) if matches!(*call.callee, Node::Super))
+            }
+            _ => false,
+        })
+        .map_or(0, |idx| idx + 1);
+    body.splice(after_super..after_super, fields);
+
+    let body = Node::BlockStatement(BlockStatement { body, span: None });
+    (params, body)
+}
+
+fn statement(expression: Node) -> Node {
+    let span = expression.span().cloned();
+    Node::ExpressionStatement(ExpressionStatement {
+        expression: Box::new(expression),
+        span,
+    })
+}
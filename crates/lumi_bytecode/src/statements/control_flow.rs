@@ -1,10 +1,35 @@
+use std::ops::Range;
+
 use lumi_ast::Node;
 
-use crate::{instruction::Label, scope::local_vars::ScopeManager, Instruction};
+use crate::{
+    instruction::Label,
+    scope::local_vars::{ScopeCore, ScopeManager},
+    statements::{destructuring::DestructuringGenerator, exception::ExceptionGenerator},
+    Instruction,
+};
+
+/// Jump targets of a loop that `break` and `continue` can refer to
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoopLabels {
+    pub name: Option<String>,
+    pub break_label: Label,
+    pub continue_label: Option<Label>, // `None` for a switch, only `break` leaves it
+}
 
 pub trait ControlFlowGenerator {
     fn generate_if_statement(&mut self, node: &Node);
     fn generate_for_statement(&mut self, node: &Node);
+    fn generate_while_statement(&mut self, node: &Node);
+    fn generate_do_while_statement(&mut self, node: &Node);
+    fn generate_loop_statement(&mut self, node: &Node);
+    fn generate_labeled_statement(&mut self, node: &Node);
+    fn generate_break_statement(&mut self, node: &Node);
+    fn generate_continue_statement(&mut self, node: &Node);
+    fn generate_loop(&mut self, node: &Node, name: Option<String>);
+    fn close_loop_locals(&mut self, locals: Range<usize>, body_start: usize);
+    fn find_loop(&mut self, name: &Option<String>, is_continue: bool) -> LoopLabels;
+    fn leave_try_blocks(&mut self, target: &LoopLabels);
 }
 
 pub trait ControlFlowCore {
@@ -12,14 +37,16 @@ pub trait ControlFlowCore {
     fn visit_node(&mut self, node: &Node);
     fn emit_jump(&mut self, label: Label);
     fn emit_jump_if_false(&mut self, label: Label);
+    fn emit_jump_if_nullish(&mut self, label: Label);
     fn emit(&mut self, instr: Instruction);
     fn new_label(&mut self) -> Label;
     fn patch_label(&mut self, label: Label);
+    fn loops(&mut self) -> &mut Vec<LoopLabels>;
 }
 
 impl<T> ControlFlowGenerator for T
 where
-    T: ControlFlowCore + ScopeManager,
+    T: ControlFlowCore + ScopeCore + ExceptionGenerator + DestructuringGenerator,
 {
     fn generate_if_statement(&mut self, node: &Node) {
         if let Node::IfStatement(stmt) = node {
@@ -29,11 +56,9 @@ where
             let end_label = self.new_label();
 
             self.emit_jump_if_false(else_label);
-            self.emit(Instruction::Pop);
 
             self.visit_node(&stmt.stmt);
             self.emit_jump(end_label);
-            self.emit(Instruction::Pop);
 
             self.patch_label(else_label);
             if let Some(else_part) = &stmt.else_part {
@@ -45,48 +70,251 @@ where
     }
 
     fn generate_for_statement(&mut self, node: &Node) {
-        if let Node::ForStatement(stmt) = node {
-            let var_idx = match &*stmt.iterator {
-                Node::Identifier(id) => self.get_or_create_local(&id.clone()),
-                _ => unreachable!("Iterator must be an identifier"),
-            };
-
-            self.visit_node(&stmt.start);
-            self.emit(Instruction::StoreVar(var_idx));
-
-            let end_idx = self.new_temp_local();
-            self.visit_node(&stmt.end);
-            self.emit(Instruction::StoreVar(end_idx));
-
-            let step_idx = self.new_temp_local();
-            if let Some(step) = &stmt.step {
-                self.visit_node(step);
-            } else {
-                self.emit(Instruction::PushConst(1));
+        self.generate_loop(node, None);
+    }
+
+    fn generate_while_statement(&mut self, node: &Node) {
+        self.generate_loop(node, None);
+    }
+
+    fn generate_do_while_statement(&mut self, node: &Node) {
+        self.generate_loop(node, None);
+    }
+
+    fn generate_loop_statement(&mut self, node: &Node) {
+        self.generate_loop(node, None);
+    }
+
+    fn generate_labeled_statement(&mut self, node: &Node) {
+        if let Node::LabeledStatement(stmt) = node {
+            self.generate_loop(&stmt.body, Some(stmt.label.clone()));
+        }
+    }
+
+    fn generate_break_statement(&mut self, node: &Node) {
+        if let Node::BreakStatement(stmt) = node {
+            let target = self.find_loop(&stmt.label, false);
+            self.leave_try_blocks(&target);
+            self.emit_jump(target.break_label);
+        }
+    }
+
+    fn generate_continue_statement(&mut self, node: &Node) {
+        if let Node::ContinueStatement(stmt) = node {
+            let target = self.find_loop(&stmt.label, true);
+            self.leave_try_blocks(&target);
+            if let Some(continue_label) = target.continue_label {
+                self.emit_jump(continue_label);
             }
-            self.emit(Instruction::StoreVar(step_idx));
+        }
+    }
 
-            let start_label = self.new_label();
-            let end_label = self.new_label();
+    /// Generate any kind of loop, registering its jump targets while the body is generated
+    fn generate_loop(&mut self, node: &Node, name: Option<String>) {
+        let start_label = self.new_label();
+        let end_label = self.new_label();
+        let first_local = self.next_local();
 
-            self.patch_label(start_label);
+        match node {
+            Node::ForStatement(stmt) => {
+                let var_idx = match &*stmt.iterator {
+                    Node::Identifier(id) => self.get_or_create_local(&id.clone()),
+                    _ => unreachable!("Iterator must be an identifier"),
+                };
 
-            self.emit(Instruction::LoadVar(var_idx));
-            self.emit(Instruction::LoadVar(end_idx));
-            self.emit(Instruction::Leq);
+                self.visit_node(&stmt.start);
+                self.emit(Instruction::StoreVar(var_idx));
 
-            self.emit_jump_if_false(end_label);
+                let end_idx = self.new_temp_local();
+                self.visit_node(&stmt.end);
+                self.emit(Instruction::StoreVar(end_idx));
 
-            self.visit_node(&stmt.body);
-            self.emit(Instruction::Pop);
+                let step_idx = self.new_temp_local();
+                if let Some(step) = &stmt.step {
+                    self.visit_node(step);
+                } else {
+                    self.visit_node(&Node::Integer(1));
+                }
+                self.emit(Instruction::StoreVar(step_idx));
 
-            self.emit(Instruction::LoadVar(var_idx));
-            self.emit(Instruction::LoadVar(step_idx));
-            self.emit(Instruction::Add);
-            self.emit(Instruction::StoreVar(var_idx));
+                let step_label = self.new_label();
 
-            self.emit_jump(start_label);
-            self.patch_label(end_label);
+                self.patch_label(start_label);
+
+                self.emit(Instruction::LoadVar(var_idx));
+                self.emit(Instruction::LoadVar(end_idx));
+                self.emit(Instruction::Leq);
+
+                self.emit_jump_if_false(end_label);
+
+                self.loops().push(LoopLabels {
+                    name,
+                    break_label: end_label,
+                    continue_label: Some(step_label),
+                });
+                let body_start = self.instructions().len();
+                self.visit_node(&stmt.body);
+                self.loops().pop();
+
+                self.patch_label(step_label);
+                self.close_loop_locals(first_local..self.next_local(), body_start);
+                self.emit(Instruction::LoadVar(var_idx));
+                self.emit(Instruction::LoadVar(step_idx));
+                self.emit(Instruction::Add);
+                self.emit(Instruction::StoreVar(var_idx));
+
+                self.emit_jump(start_label);
+            }
+            Node::ForEachStatement(stmt) => {
+                let array_idx = self.new_temp_local();
+                self.visit_node(&stmt.iterable);
+                self.emit(Instruction::StoreVar(array_idx));
+
+                let index_idx = self.new_temp_local();
+                self.visit_node(&Node::Integer(0));
+                self.emit(Instruction::StoreVar(index_idx));
+
+                let step_label = self.new_label();
+
+                self.patch_label(start_label);
+
+                self.emit(Instruction::LoadVar(index_idx));
+                self.emit(Instruction::LoadVar(array_idx));
+                self.emit(Instruction::GetProp("len".to_string()));
+                self.emit(Instruction::Lt);
+
+                self.emit_jump_if_false(end_label);
+
+                self.emit(Instruction::LoadVar(array_idx));
+                self.emit(Instruction::LoadVar(index_idx));
+                self.emit(Instruction::GetIndex);
+                match &*stmt.iterator {
+                    Node::Pattern(pattern) => {
+                        let element_idx = self.new_temp_local();
+                        self.emit(Instruction::StoreVar(element_idx));
+                        self.generate_destructuring(pattern, element_idx, true);
+                    }
+                    iterator => {
+                        let var_idx = self.get_or_create_local(&iterator.name());
+                        self.emit(Instruction::StoreVar(var_idx));
+                    }
+                }
+
+                self.loops().push(LoopLabels {
+                    name,
+                    break_label: end_label,
+                    continue_label: Some(step_label),
+                });
+                let body_start = self.instructions().len();
+                self.visit_node(&stmt.body);
+                self.loops().pop();
+
+                self.patch_label(step_label);
+                self.close_loop_locals(first_local..self.next_local(), body_start);
+                self.emit(Instruction::LoadVar(index_idx));
+                self.visit_node(&Node::Integer(1));
+                self.emit(Instruction::Add);
+                self.emit(Instruction::StoreVar(index_idx));
+
+                self.emit_jump(start_label);
+            }
+            Node::WhileStatement(stmt) => {
+                let continue_label = self.new_label();
+
+                self.patch_label(start_label);
+
+                self.visit_node(&stmt.condition);
+                self.emit_jump_if_false(end_label);
+
+                self.loops().push(LoopLabels {
+                    name,
+                    break_label: end_label,
+                    continue_label: Some(continue_label),
+                });
+                let body_start = self.instructions().len();
+                self.visit_node(&stmt.body);
+                self.loops().pop();
+
+                self.patch_label(continue_label);
+                self.close_loop_locals(first_local..self.next_local(), body_start);
+                self.emit_jump(start_label);
+            }
+            Node::DoWhileStatement(stmt) => {
+                let condition_label = self.new_label();
+
+                self.patch_label(start_label);
+
+                self.loops().push(LoopLabels {
+                    name,
+                    break_label: end_label,
+                    continue_label: Some(condition_label),
+                });
+                let body_start = self.instructions().len();
+                self.visit_node(&stmt.body);
+                self.loops().pop();
+
+                self.patch_label(condition_label);
+                self.close_loop_locals(first_local..self.next_local(), body_start);
+                self.visit_node(&stmt.condition);
+                self.emit_jump_if_false(end_label);
+                self.emit_jump(start_label);
+            }
+            Node::LoopStatement(stmt) => {
+                let continue_label = self.new_label();
+
+                self.patch_label(start_label);
+
+                self.loops().push(LoopLabels {
+                    name,
+                    break_label: end_label,
+                    continue_label: Some(continue_label),
+                });
+                let body_start = self.instructions().len();
+                self.visit_node(&stmt.body);
+                self.loops().pop();
+
+                self.patch_label(continue_label);
+                self.close_loop_locals(first_local..self.next_local(), body_start);
+                self.emit_jump(start_label);
+            }
+            _ => unreachable!("Only loops can be labeled"),
+        }
+
+        self.patch_label(end_label);
+    }
+
+    /// Each iteration of a loop has its own copies of the variables declared in it. When
+    /// the body made closures, the `locals` it captured get a new cell for the next one
+    fn close_loop_locals(&mut self, locals: Range<usize>, body_start: usize) {
+        let made_closures = self.instructions()[body_start..]
+            .iter()
+            .any(|instr| matches!(instr, Instruction::MakeClosure(_)));
+        if made_closures {
+            for idx in locals {
+                self.emit(Instruction::CloseVar(idx));
+            }
         }
     }
+
+    /// Find the innermost loop, or the loop with the given label. An unlabeled `break`
+    /// can also leave a switch. The semantic analyzer guarantees the target exists.
+    fn find_loop(&mut self, name: &Option<String>, is_continue: bool) -> LoopLabels {
+        self.loops()
+            .iter()
+            .rev()
+            .filter(|labels| !is_continue || labels.continue_label.is_some())
+            .find(|labels| name.is_none() || labels.name == *name)
+            .cloned()
+            .unwrap_or_else(|| panic!("No enclosing loop for jump to {:?}", name))
+    }
+
+    /// Run the finally blocks a jump to `target` leaves, the ones around the loop stay
+    fn leave_try_blocks(&mut self, target: &LoopLabels) {
+        let depth = self
+            .loops()
+            .iter()
+            .rposition(|labels| labels == target)
+            .map_or(0, |idx| idx + 1);
+        self.generate_finalizers(depth);
+    }
 }
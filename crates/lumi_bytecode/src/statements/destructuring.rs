@@ -0,0 +1,93 @@
+use lumi_ast::{Node, Pattern};
+
+use crate::{
+    instruction::Label,
+    scope::{
+        local_vars::ScopeManager,
+        upvalues::{UpvalueManager, VarSlot},
+    },
+    Constant, ConstantPool, Instruction,
+};
+
+pub trait DestructuringGenerator {
+    fn generate_destructuring(&mut self, pattern: &Pattern, slot: usize, declare: bool);
+}
+
+pub trait DestructuringCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn constants(&mut self) -> &mut ConstantPool;
+    fn visit_node(&mut self, node: &Node);
+    fn new_label(&mut self) -> Label;
+    fn patch_label(&mut self, label: Label);
+    fn emit_jump_if_false(&mut self, label: Label);
+}
+
+impl<T> DestructuringGenerator for T
+where
+    T: DestructuringCore + ScopeManager + UpvalueManager,
+{
+    /// Store the parts of the value in `slot` in the names `pattern` binds, declaring them
+    /// as locals or assigning the variables they resolve to. Unlike a match arm nothing
+    /// is tested, missing elements and properties are undefined
+    fn generate_destructuring(&mut self, pattern: &Pattern, slot: usize, declare: bool) {
+        match pattern {
+            Pattern::Wildcard => {}
+            Pattern::Binding(name) => {
+                let target = match declare {
+                    true => VarSlot::Local(self.get_or_create_local(name)),
+                    false => self.resolve_variable(name),
+                };
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.instructions().push(target.store());
+            }
+            Pattern::Default(pattern, default) => {
+                let skip = self.new_label();
+                let undefined = self.constants().add(Constant::Undefined);
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.instructions().push(Instruction::PushConst(undefined));
+                self.instructions().push(Instruction::Eq);
+                self.emit_jump_if_false(skip);
+                self.visit_node(default);
+                self.instructions().push(Instruction::StoreVar(slot));
+                self.patch_label(skip);
+                self.generate_destructuring(pattern, slot, declare);
+            }
+            Pattern::Array(array) => {
+                for (idx, element) in array.elements.iter().enumerate() {
+                    if *element == Pattern::Wildcard {
+                        continue;
+                    }
+                    let index = self.constants().add(Constant::Integer(idx as i64));
+                    self.instructions().push(Instruction::LoadVar(slot));
+                    self.instructions().push(Instruction::PushConst(index));
+                    self.instructions().push(Instruction::GetIndex);
+                    let element_slot = self.new_temp_local();
+                    self.instructions()
+                        .push(Instruction::StoreVar(element_slot));
+                    self.generate_destructuring(element, element_slot, declare);
+                }
+                if let Some(rest) = &array.rest {
+                    self.instructions().push(Instruction::LoadVar(slot));
+                    self.instructions()
+                        .push(Instruction::ArraySlice(array.elements.len()));
+                    let rest_slot = self.new_temp_local();
+                    self.instructions().push(Instruction::StoreVar(rest_slot));
+                    self.generate_destructuring(rest, rest_slot, declare);
+                }
+            }
+            Pattern::Object(object) => {
+                for property in &object.properties {
+                    self.instructions().push(Instruction::LoadVar(slot));
+                    self.instructions()
+                        .push(Instruction::GetProp(property.key.clone()));
+                    let property_slot = self.new_temp_local();
+                    self.instructions()
+                        .push(Instruction::StoreVar(property_slot));
+                    self.generate_destructuring(&property.value, property_slot, declare);
+                }
+            }
+            // The parser only allows tests of the value in match arms
+            Pattern::Literal(_) | Pattern::Range(..) | Pattern::Variant(_) => {}
+        }
+    }
+}
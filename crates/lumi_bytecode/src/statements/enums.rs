@@ -0,0 +1,77 @@
+use lumi_ast::{EnumVariant, Node};
+
+use crate::{
+    core::FunctionObj, scope::local_vars::ScopeManager, Constant, ConstantPool, Instruction,
+};
+
+pub trait EnumGenerator {
+    fn generate_enum_declaration(&mut self, node: &Node);
+}
+
+pub trait EnumCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn constants(&mut self) -> &mut ConstantPool;
+}
+
+impl<T> EnumGenerator for T
+where
+    T: EnumCore + ScopeManager,
+{
+    /// An enum is an object holding a constructor function for each variant with a
+    /// payload and the single value of each variant without one
+    fn generate_enum_declaration(&mut self, node: &Node) {
+        let Node::EnumDeclaration(decl) = node else {
+            return;
+        };
+        let name = decl.id.name();
+        let slot = self.get_or_create_local(&name);
+
+        self.instructions().push(Instruction::NewObject);
+        for variant in &decl.variants {
+            self.instructions().push(Instruction::Dup);
+            if variant.fields.is_empty() {
+                self.instructions().push(Instruction::MakeVariant(
+                    name.clone(),
+                    variant.name.clone(),
+                    Vec::new(),
+                ));
+            } else {
+                let idx = self
+                    .constants()
+                    .add(Constant::Function(constructor(&name, variant)));
+                self.instructions().push(Instruction::MakeClosure(idx));
+            }
+            self.instructions()
+                .push(Instruction::SetProp(variant.name.clone()));
+        }
+        self.instructions().push(Instruction::StoreVar(slot));
+    }
+}
+
+/// The function creating values of `variant`, it takes the payload in field order
+fn constructor(enum_name: &str, variant: &EnumVariant) -> FunctionObj {
+    let fields: Vec<String> = variant
+        .fields
+        .iter()
+        .map(|field| field.name.clone())
+        .collect();
+    let mut instructions: Vec<Instruction> = (0..fields.len()).map(Instruction::LoadVar).collect();
+    instructions.push(Instruction::MakeVariant(
+        enum_name.to_string(),
+        variant.name.clone(),
+        fields.clone(),
+    ));
+    instructions.push(Instruction::Return);
+
+    FunctionObj {
+        name: Some(format!("{}.{}", enum_name, variant.name)),
+        arity: fields.len(),
+        param_names: fields,
+        has_rest: false,
+        is_method: false,
+        instructions,
+        constants: Vec::new(),
+        captures: Vec::new(),
+        handlers: Vec::new(),
+    }
+}
@@ -0,0 +1,148 @@
+use lumi_ast::Node;
+
+use crate::{
+    core::Handler,
+    instruction::Label,
+    scope::local_vars::{ScopeCore, ScopeManager},
+    Instruction,
+};
+
+/// A `finally` block that `break`, `continue` and `return` run before they leave its `try`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finalizer {
+    pub body: Node,
+    pub loop_depth: usize, // Loops around the `try` statement
+}
+
+pub trait ExceptionGenerator {
+    fn generate_throw_statement(&mut self, node: &Node);
+    fn generate_try_statement(&mut self, node: &Node);
+    fn generate_finalizers(&mut self, loop_depth: usize);
+}
+
+pub trait ExceptionCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn handlers(&mut self) -> &mut Vec<Handler>;
+    fn finalizers(&mut self) -> &mut Vec<Finalizer>;
+    fn loop_depth(&self) -> usize;
+    fn stack_depth(&self) -> usize;
+    fn visit_node(&mut self, node: &Node);
+    fn new_label(&mut self) -> Label;
+    fn patch_label(&mut self, label: Label);
+    fn emit_jump(&mut self, label: Label);
+}
+
+impl<T> ExceptionGenerator for T
+where
+    T: ExceptionCore + ScopeCore,
+{
+    fn generate_throw_statement(&mut self, node: &Node) {
+        if let Node::ThrowStatement(stmt) = node {
+            self.visit_node(&stmt.argument);
+            self.instructions().push(Instruction::Throw);
+        }
+    }
+
+    /// The try block is followed by the catch block and a copy of the finally block for
+    /// each way out of them. Only the handler table points at the catch block, and at
+    /// the copy of the finally block that rethrows what the others didn't catch
+    fn generate_try_statement(&mut self, node: &Node) {
+        let Node::TryStatement(stmt) = node else {
+            return;
+        };
+        let end_label = self.new_label();
+
+        let start = self.instructions().len();
+        if let Some(finalizer) = &stmt.finalizer {
+            let loop_depth = self.loop_depth();
+            self.finalizers().push(Finalizer {
+                body: (**finalizer).clone(),
+                loop_depth,
+            });
+        }
+        self.visit_node(&stmt.block);
+        let mut guarded = (start, self.instructions().len());
+        generate_finally(self, stmt.finalizer.as_deref());
+        self.emit_jump(end_label);
+
+        if let Some(handler) = &stmt.handler {
+            let target = self.instructions().len();
+            let depth = self.stack_depth();
+            self.handlers().push(Handler {
+                start: guarded.0,
+                end: guarded.1,
+                target,
+                depth,
+            });
+
+            // The parameter is only visible inside the catch block
+            let shadowed = match handler.param.as_deref() {
+                Some(param) => {
+                    let name = param.name();
+                    let slot = self.new_temp_local();
+                    self.instructions().push(Instruction::StoreVar(slot));
+                    Some((name.clone(), self.local_vars_mut().insert(name, slot)))
+                }
+                None => {
+                    self.instructions().push(Instruction::Pop);
+                    None
+                }
+            };
+            self.visit_node(&handler.body);
+            if let Some((name, shadowed)) = shadowed {
+                match shadowed {
+                    Some(shadowed) => self.local_vars_mut().insert(name, shadowed),
+                    None => self.local_vars_mut().remove(&name),
+                };
+            }
+
+            guarded = (target, self.instructions().len());
+            generate_finally(self, stmt.finalizer.as_deref());
+            self.emit_jump(end_label);
+        }
+
+        if let Some(finalizer) = &stmt.finalizer {
+            self.finalizers().pop();
+
+            let target = self.instructions().len();
+            let depth = self.stack_depth();
+            self.handlers().push(Handler {
+                start: guarded.0,
+                end: guarded.1,
+                target,
+                depth,
+            });
+            let slot = self.new_temp_local();
+            self.instructions().push(Instruction::StoreVar(slot));
+            self.visit_node(finalizer);
+            self.instructions().push(Instruction::LoadVar(slot));
+            self.instructions().push(Instruction::Rethrow);
+        }
+
+        self.patch_label(end_label);
+    }
+
+    /// Generate the finally blocks of the `try` statements entered with at least
+    /// `loop_depth` loops around them, innermost first
+    fn generate_finalizers(&mut self, loop_depth: usize) {
+        let finalizers = self.finalizers().clone();
+        for (idx, finalizer) in finalizers.iter().enumerate().rev() {
+            if finalizer.loop_depth < loop_depth {
+                break;
+            }
+            // A jump inside the finally block only runs the ones around it
+            self.finalizers().truncate(idx);
+            self.visit_node(&finalizer.body);
+        }
+        *self.finalizers() = finalizers;
+    }
+}
+
+/// The finally block that ends the try or catch block, jumps inside it don't run it again
+fn generate_finally<T: ExceptionCore>(generator: &mut T, finalizer: Option<&Node>) {
+    if let Some(finalizer) = finalizer {
+        let own = generator.finalizers().pop();
+        generator.visit_node(finalizer);
+        generator.finalizers().extend(own);
+    }
+}
@@ -1,17 +1,34 @@
 use std::collections::HashMap;
 
-use lumi_ast::{FunctionDeclaration, Node};
+use lumi_ast::{Node, Parameter};
 
 use crate::{
-    core::FunctionObj, scope::local_vars::ScopeManager, Constant, ConstantPool, Instruction,
+    core::{FunctionObj, Handler},
+    debug::DebugInfo,
+    instruction::Label,
+    scope::{
+        local_vars::ScopeManager,
+        upvalues::{FunctionScope, UpvalueCore},
+    },
+    statements::{
+        destructuring::DestructuringGenerator,
+        exception::{ExceptionGenerator, Finalizer},
+    },
+    Constant, ConstantPool, Instruction,
 };
 
 pub trait FunctionGenerator {
     fn generate_function_declaration(&mut self, node: &Node);
+    fn generate_function_expression(&mut self, node: &Node);
+    fn generate_return_statement(&mut self, node: &Node);
+    fn declare_functions(&mut self, body: &[Node]);
     fn code_gen_function_body(
         &mut self,
-        decl: &FunctionDeclaration,
-    ) -> (Vec<Instruction>, Vec<Constant>);
+        name: Option<String>,
+        params: &[Parameter],
+        body: &Node,
+        is_method: bool,
+    ) -> FunctionObj;
 }
 
 pub trait FunctionCore {
@@ -20,65 +37,209 @@ pub trait FunctionCore {
     fn next_label_id(&self) -> usize;
     fn set_next_local(&mut self, next: usize);
     fn constants(&mut self) -> &mut ConstantPool;
+    fn debug_info(&mut self) -> &mut DebugInfo;
+    fn handlers(&mut self) -> &mut Vec<Handler>;
+    fn finalizers(&mut self) -> &mut Vec<Finalizer>;
     fn visit_node(&mut self, node: &Node);
+    fn new_label(&mut self) -> Label;
+    fn patch_label(&mut self, label: Label);
+    fn emit_jump_if_false(&mut self, label: Label);
 }
 
 impl<T> FunctionGenerator for T
 where
-    T: FunctionCore + ScopeManager,
+    T: FunctionCore + ScopeManager + UpvalueCore + ExceptionGenerator + DestructuringGenerator,
 {
     fn generate_function_declaration(&mut self, node: &Node) {
         if let Node::FunctionDeclaration(decl) = node {
-            let (chunk, constants) = self.code_gen_function_body(decl);
+            let fn_name = decl.id.as_ref().map(|node| node.name());
 
-            let fn_name = if let Some(node) = &decl.id {
-                Some(node.name())
-            } else {
-                None
-            };
+            // The local exists before the body is generated so the function can call itself
+            let slot = fn_name.as_ref().map(|name| self.get_or_create_local(name));
 
-            let func_obj = FunctionObj {
-                name: fn_name,
-                arity: decl.params.len(),
-                instructions: chunk,
-                constants,
-            };
+            let func_obj = self.code_gen_function_body(fn_name, &decl.params, &decl.body, false);
 
             let idx = self.constants().add(Constant::Function(func_obj));
-            self.instructions().push(Instruction::PushConst(idx));
+            self.instructions().push(Instruction::MakeClosure(idx));
+            match slot {
+                Some(slot) => self.instructions().push(Instruction::StoreVar(slot)),
+                None => self.instructions().push(Instruction::Pop),
+            }
+        }
+    }
+
+    fn generate_function_expression(&mut self, node: &Node) {
+        if let Node::FunctionExpression(expr) = node {
+            let fn_name = expr.id.as_ref().map(|node| node.name());
+
+            // The name of `fn name() {}` only refers to the function inside its own body
+            let self_slot = fn_name.as_ref().map(|name| {
+                let slot = self.new_temp_local();
+                (
+                    name.clone(),
+                    slot,
+                    self.symbol_table().insert(name.clone(), slot),
+                )
+            });
+
+            let func_obj = self.code_gen_function_body(fn_name, &expr.params, &expr.body, false);
+
+            let idx = self.constants().add(Constant::Function(func_obj));
+            self.instructions().push(Instruction::MakeClosure(idx));
+
+            if let Some((name, slot, shadowed)) = self_slot {
+                match shadowed {
+                    Some(shadowed) => self.symbol_table().insert(name, shadowed),
+                    None => self.symbol_table().remove(&name),
+                };
+                self.instructions().push(Instruction::Dup);
+                self.instructions().push(Instruction::StoreVar(slot));
+            }
+        }
+    }
+
+    fn generate_return_statement(&mut self, node: &Node) {
+        if let Node::ReturnStatement(stmt) = node {
+            match &stmt.argument {
+                Some(argument) => self.visit_node(argument),
+                None => {
+                    let idx = self.constants().add(Constant::Undefined);
+                    self.instructions().push(Instruction::PushConst(idx));
+                }
+            }
+
+            // The value is put aside while the finally blocks around the return run
+            if !self.finalizers().is_empty() {
+                let slot = self.new_temp_local();
+                self.instructions().push(Instruction::StoreVar(slot));
+                self.generate_finalizers(0);
+                self.instructions().push(Instruction::LoadVar(slot));
+            }
+            self.instructions().push(Instruction::Return);
+        }
+    }
+
+    /// Give the functions and classes declared in a block their locals up front, so a
+    /// function can use one that is declared after it
+    fn declare_functions(&mut self, body: &[Node]) {
+        for stmt in body {
+            match stmt {
+                Node::FunctionDeclaration(decl) => {
+                    if let Some(id) = &decl.id {
+                        self.get_or_create_local(&id.name());
+                    }
+                }
+                Node::ClassDeclaration(class) => {
+                    self.get_or_create_local(&class.id.name());
+                }
+                _ => {}
+            }
         }
     }
 
     fn code_gen_function_body(
         &mut self,
-        decl: &FunctionDeclaration,
-    ) -> (Vec<Instruction>, Vec<Constant>) {
+        name: Option<String>,
+        params: &[Parameter],
+        body: &Node,
+        is_method: bool,
+    ) -> FunctionObj {
         let mut old_instructions = Vec::new();
         let mut old_constants = Vec::new();
-        let mut old_locals = HashMap::new();
         let old_next_label = self.next_label_id();
+        let old_spans = std::mem::take(&mut self.debug_info().main);
+        let old_handlers = std::mem::take(self.handlers());
+        let old_finalizers = std::mem::take(self.finalizers());
 
         std::mem::swap(&mut old_instructions, self.instructions());
         std::mem::swap(&mut old_constants, &mut self.constants().values);
-        std::mem::swap(&mut old_locals, &mut self.symbol_table());
+
+        // The enclosing function's variables stay reachable as upvalues
+        let enclosing = FunctionScope {
+            locals: std::mem::take(self.symbol_table()),
+            upvalues: std::mem::take(self.upvalues()),
+        };
+        self.enclosing_functions().push(enclosing);
         self.set_next_local(0);
 
-        for param in &decl.params {
-            self.visit_node(param);
+        // A method finds the instance it runs on in the first local
+        if is_method {
+            self.get_or_create_local("this");
+        }
+        // A destructured argument gets a slot of its own, its parts are bound below
+        let slots: Vec<usize> = params
+            .iter()
+            .map(|param| match param.name.as_identifier() {
+                Some(name) => self.get_or_create_local(name),
+                None => self.new_temp_local(),
+            })
+            .collect();
+
+        // A default replaces an argument that is missing or undefined
+        for (param, &slot) in params.iter().zip(&slots) {
+            if let Some(default) = &param.default_value {
+                let skip = self.new_label();
+                let undefined = self.constants().add(Constant::Undefined);
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.instructions().push(Instruction::PushConst(undefined));
+                self.instructions().push(Instruction::Eq);
+                self.emit_jump_if_false(skip);
+                self.visit_node(default);
+                self.instructions().push(Instruction::StoreVar(slot));
+                self.patch_label(skip);
+            }
+            if let Node::Pattern(pattern) = &*param.name {
+                self.generate_destructuring(pattern, slot, true);
+            }
         }
 
-        self.visit_node(&decl.body);
-
+        // The body of an arrow like `x => x * 2` is its value, a block that runs to the
+        // end returns undefined
+        self.visit_node(body);
+        if matches!(body, Node::BlockStatement(_)) {
+            let undefined = self.constants().add(Constant::Undefined);
+            self.instructions().push(Instruction::PushConst(undefined));
+        }
         self.instructions().push(Instruction::Return);
 
         let func_instructions = std::mem::take(self.instructions());
         let func_constants = std::mem::take(self.constants());
+        let func_handlers = std::mem::replace(self.handlers(), old_handlers);
+        *self.finalizers() = old_finalizers;
+        let captures = std::mem::take(self.upvalues())
+            .into_iter()
+            .map(|(_, capture)| capture)
+            .collect();
+
+        let enclosing = self.enclosing_functions().pop().unwrap_or_default();
+        *self.symbol_table() = enclosing.locals;
+        *self.upvalues() = enclosing.upvalues;
 
         std::mem::swap(self.instructions(), &mut old_instructions);
         std::mem::swap(&mut self.constants().values, &mut old_constants);
-        std::mem::swap(self.symbol_table(), &mut old_locals);
         self.set_next_local(old_next_label);
 
-        (func_instructions, func_constants.values)
+        // The body's spans are indexed by the function's own instructions
+        let func_spans = std::mem::replace(&mut self.debug_info().main, old_spans);
+        if let Some(name) = &name {
+            self.debug_info().functions.insert(name.clone(), func_spans);
+        }
+
+        let has_rest = params.last().is_some_and(|param| param.is_rest);
+        FunctionObj {
+            name,
+            arity: params.len() - usize::from(has_rest),
+            // Destructured parameters can't be passed by name
+            param_names: params
+                .iter()
+                .map(|param| param.name.as_identifier().unwrap_or_default().to_string())
+                .collect(),
+            has_rest,
+            is_method,
+            instructions: func_instructions,
+            constants: func_constants.values,
+            captures,
+            handlers: func_handlers,
+        }
     }
 }
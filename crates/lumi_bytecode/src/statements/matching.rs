@@ -0,0 +1,352 @@
+use lumi_ast::{MatchArm, Node, Pattern};
+
+use crate::{
+    instruction::Label,
+    scope::local_vars::{ScopeCore, ScopeManager},
+    statements::control_flow::LoopLabels,
+    Constant, ConstantPool, Instruction,
+};
+
+/// Names a pattern bound, with the variables they shadowed
+type Bindings = Vec<(String, Option<usize>)>;
+
+/// Integer case values, with the case or arm each one selects
+type Cases = Vec<(i64, usize)>;
+
+pub trait MatchGenerator {
+    fn generate_switch_statement(&mut self, node: &Node);
+    fn generate_match_expression(&mut self, node: &Node);
+    fn generate_pattern(
+        &mut self,
+        pattern: &Pattern,
+        slot: usize,
+        fail: Label,
+        bindings: &mut Bindings,
+    );
+    fn restore_bindings(&mut self, bindings: Bindings);
+}
+
+pub trait MatchCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn constants(&mut self) -> &mut ConstantPool;
+    fn visit_node(&mut self, node: &Node);
+    fn new_label(&mut self) -> Label;
+    fn patch_label(&mut self, label: Label);
+    fn emit_jump(&mut self, label: Label);
+    fn emit_jump_if_false(&mut self, label: Label);
+    fn loops(&mut self) -> &mut Vec<LoopLabels>;
+}
+
+impl<T> MatchGenerator for T
+where
+    T: MatchCore + ScopeCore,
+{
+    /// Cases with dense integer tests go through a jump table, the others compare the
+    /// value with each test in turn
+    fn generate_switch_statement(&mut self, node: &Node) {
+        let Node::SwitchStatement(stmt) = node else {
+            return;
+        };
+        let slot = self.new_temp_local();
+        self.visit_node(&stmt.discriminant);
+        self.instructions().push(Instruction::StoreVar(slot));
+        let default = stmt.cases.iter().position(|case| case.tests.is_empty());
+        // A `break` in a case leaves the switch, a `continue` the loop around it
+        let end_label = self.new_label();
+        self.loops().push(LoopLabels {
+            name: None,
+            break_label: end_label,
+            continue_label: None,
+        });
+
+        let values: Option<Cases> = stmt
+            .cases
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, case)| case.tests.iter().map(move |test| (test, idx)))
+            .map(|(test, idx)| match test {
+                Node::Integer(n) => Some((*n, idx)),
+                _ => None,
+            })
+            .collect();
+        if let Some((values, range)) = values.and_then(dense) {
+            self.instructions().push(Instruction::LoadVar(slot));
+            let table = self.instructions().len();
+            self.instructions()
+                .push(Instruction::JumpTable(range.0, Vec::new(), usize::MAX));
+
+            let mut starts = Vec::with_capacity(stmt.cases.len());
+            for case in &stmt.cases {
+                starts.push(self.instructions().len());
+                for stmt in &case.body {
+                    self.visit_node(stmt);
+                }
+                self.emit_jump(end_label);
+            }
+            let end = self.instructions().len();
+            self.patch_label(end_label);
+
+            let default = default.map_or(end, |idx| starts[idx]);
+            self.instructions()[table] = jump_table(range, &values, &starts, default);
+            self.loops().pop();
+            return;
+        }
+
+        // `Neq` is false for the case to run, so `JumpIfFalse` jumps into its body
+        let labels: Vec<Label> = stmt.cases.iter().map(|_| self.new_label()).collect();
+        for (case, label) in stmt.cases.iter().zip(&labels) {
+            for test in &case.tests {
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.visit_node(test);
+                self.instructions().push(Instruction::Neq);
+                self.emit_jump_if_false(*label);
+            }
+        }
+        self.emit_jump(default.map_or(end_label, |idx| labels[idx]));
+
+        for (case, label) in stmt.cases.iter().zip(labels) {
+            self.patch_label(label);
+            for stmt in &case.body {
+                self.visit_node(stmt);
+            }
+            self.emit_jump(end_label);
+        }
+        self.loops().pop();
+        self.patch_label(end_label);
+    }
+
+    /// Each arm tests its pattern and guard and falls through to the next arm when they
+    /// don't match. Arms of dense integer literals go through a jump table instead
+    fn generate_match_expression(&mut self, node: &Node) {
+        let Node::MatchExpression(expr) = node else {
+            return;
+        };
+        let slot = self.new_temp_local();
+        self.visit_node(&expr.discriminant);
+        self.instructions().push(Instruction::StoreVar(slot));
+        let end_label = self.new_label();
+
+        if let Some((values, range)) = integer_arms(&expr.arms).and_then(dense) {
+            self.instructions().push(Instruction::LoadVar(slot));
+            let table = self.instructions().len();
+            self.instructions()
+                .push(Instruction::JumpTable(range.0, Vec::new(), usize::MAX));
+
+            let mut starts = Vec::with_capacity(expr.arms.len());
+            for arm in &expr.arms {
+                starts.push(self.instructions().len());
+                // The table already tested the literals, only the catch-all arm can bind a name
+                let mut bindings = Vec::new();
+                if arm.pattern.is_irrefutable() {
+                    let unused = self.new_label();
+                    self.generate_pattern(&arm.pattern, slot, unused, &mut bindings);
+                    self.patch_label(unused);
+                }
+                self.visit_node(&arm.body);
+                self.restore_bindings(bindings);
+                self.emit_jump(end_label);
+            }
+
+            let default = match expr.arms.last() {
+                Some(arm) if arm.pattern.is_irrefutable() => starts[starts.len() - 1],
+                _ => self.instructions().len(),
+            };
+            self.instructions()[table] = jump_table(range, &values, &starts, default);
+        } else {
+            for arm in &expr.arms {
+                let next_label = self.new_label();
+                let mut bindings = Vec::new();
+                self.generate_pattern(&arm.pattern, slot, next_label, &mut bindings);
+                if let Some(guard) = &arm.guard {
+                    self.visit_node(guard);
+                    self.emit_jump_if_false(next_label);
+                }
+                self.visit_node(&arm.body);
+                self.restore_bindings(bindings);
+                self.emit_jump(end_label);
+                self.patch_label(next_label);
+            }
+        }
+
+        self.instructions().push(Instruction::LoadVar(slot));
+        self.instructions().push(Instruction::NoMatch);
+        self.patch_label(end_label);
+    }
+
+    /// Test the value in `slot` against `pattern`, jumping to `fail` when it doesn't match.
+    /// The parts of the value are stored in locals of their own and tested in turn
+    fn generate_pattern(
+        &mut self,
+        pattern: &Pattern,
+        slot: usize,
+        fail: Label,
+        bindings: &mut Bindings,
+    ) {
+        match pattern {
+            Pattern::Wildcard => {}
+            Pattern::Binding(name) => {
+                let binding = self.new_temp_local();
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.instructions().push(Instruction::StoreVar(binding));
+                let shadowed = self.local_vars_mut().insert(name.clone(), binding);
+                bindings.push((name.clone(), shadowed));
+            }
+            Pattern::Literal(literal) => {
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.visit_node(literal);
+                self.instructions().push(Instruction::Eq);
+                self.emit_jump_if_false(fail);
+            }
+            Pattern::Range(low, high) => {
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.visit_node(low);
+                self.instructions().push(Instruction::Geq);
+                self.emit_jump_if_false(fail);
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.visit_node(high);
+                self.instructions().push(Instruction::Leq);
+                self.emit_jump_if_false(fail);
+            }
+            Pattern::Array(array) => {
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.instructions().push(Instruction::MatchArray(
+                    array.elements.len(),
+                    array.rest.is_some(),
+                ));
+                self.emit_jump_if_false(fail);
+
+                for (idx, element) in array.elements.iter().enumerate() {
+                    if *element == Pattern::Wildcard {
+                        continue;
+                    }
+                    let index = self.constants().add(Constant::Integer(idx as i64));
+                    self.instructions().push(Instruction::LoadVar(slot));
+                    self.instructions().push(Instruction::PushConst(index));
+                    self.instructions().push(Instruction::GetIndex);
+                    let element_slot = self.new_temp_local();
+                    self.instructions()
+                        .push(Instruction::StoreVar(element_slot));
+                    self.generate_pattern(element, element_slot, fail, bindings);
+                }
+                if let Some(rest) = array
+                    .rest
+                    .as_deref()
+                    .filter(|rest| **rest != Pattern::Wildcard)
+                {
+                    self.instructions().push(Instruction::LoadVar(slot));
+                    self.instructions()
+                        .push(Instruction::ArraySlice(array.elements.len()));
+                    let rest_slot = self.new_temp_local();
+                    self.instructions().push(Instruction::StoreVar(rest_slot));
+                    self.generate_pattern(rest, rest_slot, fail, bindings);
+                }
+            }
+            Pattern::Object(object) => {
+                let keys = object
+                    .properties
+                    .iter()
+                    .map(|property| property.key.clone())
+                    .collect();
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.instructions().push(Instruction::MatchObject(keys));
+                self.emit_jump_if_false(fail);
+
+                for property in &object.properties {
+                    if property.value == Pattern::Wildcard {
+                        continue;
+                    }
+                    self.instructions().push(Instruction::LoadVar(slot));
+                    self.instructions()
+                        .push(Instruction::GetProp(property.key.clone()));
+                    let property_slot = self.new_temp_local();
+                    self.instructions()
+                        .push(Instruction::StoreVar(property_slot));
+                    self.generate_pattern(&property.value, property_slot, fail, bindings);
+                }
+            }
+            // The parser only allows defaults in destructuring patterns
+            Pattern::Default(pattern, _) => self.generate_pattern(pattern, slot, fail, bindings),
+            Pattern::Variant(variant) => {
+                self.instructions().push(Instruction::LoadVar(slot));
+                self.instructions().push(Instruction::MatchVariant(
+                    variant.enum_name.clone(),
+                    variant.variant.clone(),
+                ));
+                self.emit_jump_if_false(fail);
+
+                // Fields are read by position, the names are the constructor's business
+                for (idx, field) in variant.fields.iter().flatten().enumerate() {
+                    if *field == Pattern::Wildcard {
+                        continue;
+                    }
+                    let index = self.constants().add(Constant::Integer(idx as i64));
+                    self.instructions().push(Instruction::LoadVar(slot));
+                    self.instructions().push(Instruction::PushConst(index));
+                    self.instructions().push(Instruction::GetIndex);
+                    let field_slot = self.new_temp_local();
+                    self.instructions().push(Instruction::StoreVar(field_slot));
+                    self.generate_pattern(field, field_slot, fail, bindings);
+                }
+            }
+        }
+    }
+
+    /// Make the names a pattern bound refer to what they shadowed again
+    fn restore_bindings(&mut self, bindings: Bindings) {
+        for (name, shadowed) in bindings.into_iter().rev() {
+            match shadowed {
+                Some(shadowed) => self.local_vars_mut().insert(name, shadowed),
+                None => self.local_vars_mut().remove(&name),
+            };
+        }
+    }
+}
+
+/// The values of a match whose arms are unguarded integer literals, save for a
+/// catch-all arm at the end, with the arm each one selects
+fn integer_arms(arms: &[MatchArm]) -> Option<Cases> {
+    let (last, literals) = arms.split_last()?;
+    let literals = if last.pattern.is_irrefutable() && last.guard.is_none() {
+        literals
+    } else {
+        arms
+    };
+    literals
+        .iter()
+        .enumerate()
+        .map(|(idx, arm)| match (&arm.pattern, &arm.guard) {
+            (Pattern::Literal(literal), None) => match **literal {
+                Node::Integer(n) => Some((n, idx)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// The range of the integer cases, when there are enough of them and they fill at
+/// least half of it, so a table is smaller than comparing them one by one
+fn dense(values: Cases) -> Option<(Cases, (i64, i64))> {
+    let min = values.iter().map(|(value, _)| *value).min()?;
+    let max = values.iter().map(|(value, _)| *value).max()?;
+    let span = max.checked_sub(min)?.checked_add(1)?;
+    if values.len() < 3 || span > 2 * values.len() as i64 {
+        return None;
+    }
+    Some((values, (min, max)))
+}
+
+/// The jump table over `range`, the first case of a value wins and values without
+/// one go to `default`
+fn jump_table(
+    range: (i64, i64),
+    values: &[(i64, usize)],
+    starts: &[usize],
+    default: usize,
+) -> Instruction {
+    let mut targets = vec![default; (range.1 - range.0 + 1) as usize];
+    for (value, idx) in values.iter().rev() {
+        targets[(value - range.0) as usize] = starts[*idx];
+    }
+    Instruction::JumpTable(range.0, targets, default)
+}
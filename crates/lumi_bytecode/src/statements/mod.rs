@@ -1,3 +1,8 @@
+pub mod class;
 pub mod control_flow;
+pub mod destructuring;
+pub mod enums;
+pub mod exception;
 pub mod function;
+pub mod matching;
 pub mod variable;
@@ -1,6 +1,8 @@
 use lumi_ast::Node;
 
-use crate::{scope::local_vars::ScopeManager, Instruction};
+use crate::{
+    scope::local_vars::ScopeManager, statements::destructuring::DestructuringGenerator, Instruction,
+};
 
 pub trait VariableGenerator {
     fn generate_variable_declaration(&mut self, node: &Node);
@@ -13,11 +15,18 @@ pub trait VariableCore {
 
 impl<T> VariableGenerator for T
 where
-    T: VariableCore + ScopeManager,
+    T: VariableCore + ScopeManager + DestructuringGenerator,
 {
     fn generate_variable_declaration(&mut self, node: &Node) {
         if let Node::VariableDeclaration(decl) = node {
             for var in &decl.declarations {
+                // The parser requires an initializer to destructure
+                if let (Node::Pattern(pattern), Some(init)) = (&*var.var_name, &var.init) {
+                    self.visit_node(init);
+                    let slot = self.new_temp_local();
+                    self.instructions().push(Instruction::StoreVar(slot));
+                    self.generate_destructuring(pattern, slot, true);
+                }
                 if let Node::Identifier(name) = &*var.var_name {
                     if let Some(init) = &var.init {
                         self.visit_node(init);
@@ -22,16 +22,23 @@ fn test_call_expression() {
             Constant::Function(FunctionObj {
                 name: Some("test".to_string()),
                 arity: 2,
+                param_names: vec!["x".to_string(), "y".to_string()],
+                has_rest: false,
+                is_method: false,
                 instructions: vec![
                     Instruction::LoadVar(0),
                     Instruction::LoadVar(1),
                     Instruction::Add,
+                    Instruction::Pop,
+                    Instruction::PushConst(0),
                     Instruction::Return
                 ],
-                constants: vec![]
+                constants: vec![Constant::Undefined],
+                captures: vec![],
+                handlers: vec![],
             }),
-            Constant::Number(1.0),
-            Constant::Number(2.0)
+            Constant::Integer(1),
+            Constant::Integer(2)
         ]
     );
 }
@@ -52,7 +59,7 @@ fn test_plus_assign_expression() {
 
     assert_eq!(
         bytecode.constants,
-        vec![Constant::Number(5.0), Constant::Number(1.0),]
+        vec![Constant::Integer(5), Constant::Integer(1),]
     );
 }
 
@@ -72,6 +79,6 @@ fn test_minus_assign_expression() {
 
     assert_eq!(
         bytecode.constants,
-        vec![Constant::Number(5.0), Constant::Number(1.0),]
+        vec![Constant::Integer(5), Constant::Integer(1),]
     );
 }
@@ -10,7 +10,7 @@ fn test_generate_number_literal() {
     generator.generate(&ast);
 
     assert_eq!(generator.instructions, vec![Instruction::PushConst(0)]);
-    assert_eq!(generator.constants.values, vec![Constant::Number(42.0)]);
+    assert_eq!(generator.constants.values, vec![Constant::Integer(42)]);
 }
 
 #[test]
@@ -27,3 +27,58 @@ fn test_generate_string_literal() {
         vec![Constant::String("hello world".to_string())]
     );
 }
+
+#[test]
+fn test_generate_interpolated_string() {
+    let mut parser = Parser::new("\"sum: ${1 + 2}!\"");
+    let ast = parser.parse().unwrap();
+
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&ast);
+
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::PushConst(2),
+            Instruction::Add,
+            Instruction::Add,
+            Instruction::PushConst(3),
+            Instruction::Add,
+        ]
+    );
+    assert_eq!(
+        generator.constants.values,
+        vec![
+            Constant::String("sum: ".to_string()),
+            Constant::Integer(1),
+            Constant::Integer(2),
+            Constant::String("!".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_generate_interpolated_string_starting_with_expression() {
+    let mut parser = Parser::new("\"${1}${2}\"");
+    let ast = parser.parse().unwrap();
+
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&ast);
+
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::Add,
+            Instruction::PushConst(2),
+            Instruction::Add,
+        ]
+    );
+    assert_eq!(
+        generator.constants.values[0],
+        Constant::String(String::new())
+    );
+}
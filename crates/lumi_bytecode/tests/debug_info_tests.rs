@@ -0,0 +1,36 @@
+use lumi_bytecode::{BytecodeGenerator, Instruction};
+use lumi_parser::Parser;
+
+#[test]
+fn test_instructions_map_to_statement_spans() {
+    let source = "let a -> 1;\nprint a;";
+    let mut parser = Parser::new(source);
+    let ast = parser.parse().unwrap();
+    let mut generator = BytecodeGenerator::new();
+    let bytecode = generator.generate(&ast);
+
+    let print_ip = bytecode
+        .instructions
+        .iter()
+        .position(|i| *i == Instruction::Print)
+        .unwrap();
+
+    let first = bytecode.debug_info.span_at(None, 0).unwrap();
+    let print = bytecode.debug_info.span_at(None, print_ip).unwrap();
+    assert_eq!(first.start.line, 1);
+    assert_eq!(print.end.line, 2);
+}
+
+#[test]
+fn test_function_body_has_its_own_span_table() {
+    let source = "fn add(a, b) {\n  a + b;\n}\nadd(1, 2);";
+    let mut parser = Parser::new(source);
+    let ast = parser.parse().unwrap();
+    let mut generator = BytecodeGenerator::new();
+    let bytecode = generator.generate(&ast);
+
+    let body = &bytecode.debug_info.functions["add"];
+    assert!(!body.is_empty());
+    assert_eq!(body.span_at(0).unwrap().start.line, 2);
+    assert!(bytecode.debug_info.span_at(None, 0).is_some());
+}
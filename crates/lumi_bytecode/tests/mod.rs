@@ -1,4 +1,5 @@
 mod basic_literal_tests;
 mod benchmark_tests;
+mod debug_info_tests;
 mod statements_tests;
 mod variable_tests;
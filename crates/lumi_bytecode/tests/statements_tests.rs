@@ -1,4 +1,4 @@
-use lumi_bytecode::{BytecodeGenerator, Constant, FunctionObj, Instruction};
+use lumi_bytecode::{BytecodeGenerator, Capture, Constant, FunctionObj, Instruction};
 use lumi_parser::Parser;
 
 #[test]
@@ -9,7 +9,7 @@ fn test_print_statement() {
     let mut bytecode_generator = BytecodeGenerator::new();
     let bytecode = bytecode_generator.generate(&ast);
 
-    assert_eq!(bytecode.constants, vec![Constant::Number(5.0)]);
+    assert_eq!(bytecode.constants, vec![Constant::Integer(5)]);
 }
 
 #[test]
@@ -22,7 +22,7 @@ fn test_simple_print_statement() {
 
     assert_eq!(
         bytecode.constants,
-        vec![Constant::Number(1.0), Constant::Number(2.0)]
+        vec![Constant::Integer(1), Constant::Integer(2)]
     );
 }
 
@@ -45,8 +45,8 @@ fn test_if_statement() {
     assert_eq!(
         bytecode.constants,
         vec![
-            Constant::Number(42.0),
-            Constant::Number(30.0),
+            Constant::Integer(42),
+            Constant::Integer(30),
             Constant::String("ok".to_string()),
             Constant::String("not ok".to_string())
         ]
@@ -71,13 +71,20 @@ fn test_fn_statement() {
         vec![Constant::Function(FunctionObj {
             name: Some("test".to_string()),
             arity: 2,
+            param_names: vec!["x".to_string(), "y".to_string()],
+            has_rest: false,
+            is_method: false,
             instructions: vec![
                 Instruction::LoadVar(0),
                 Instruction::LoadVar(1),
                 Instruction::Add,
+                Instruction::Pop,
+                Instruction::PushConst(0),
                 Instruction::Return
             ],
-            constants: vec![]
+            constants: vec![Constant::Undefined],
+            captures: vec![],
+            handlers: vec![],
         })]
     );
 }
@@ -98,9 +105,9 @@ fn test_for_statement() {
     assert_eq!(
         bytecode.constants,
         vec![
-            Constant::Number(1.0),
-            Constant::Number(5.0),
-            Constant::Number(2.0)
+            Constant::Integer(1),
+            Constant::Integer(5),
+            Constant::Integer(2)
         ]
     );
 }
@@ -127,16 +134,65 @@ fn test_for_statement_with_function_call() {
             Constant::Function(FunctionObj {
                 name: Some("printSomething".to_string()),
                 arity: 1,
+                param_names: vec!["x".to_string()],
+                has_rest: false,
+                is_method: false,
                 instructions: vec![
                     Instruction::LoadVar(0),
                     Instruction::Print,
+                    Instruction::PushConst(0),
                     Instruction::Return
                 ],
-                constants: vec![]
+                constants: vec![Constant::Undefined],
+                captures: vec![],
+                handlers: vec![],
             }),
-            Constant::Number(1.0),
-            Constant::Number(10.0),
-            Constant::Number(1.0)
+            Constant::Integer(1),
+            Constant::Integer(10),
+            Constant::Integer(1)
         ]
     );
 }
+
+#[test]
+fn test_closure_captures_enclosing_local() {
+    let mut parser = Parser::new("fn outer() { let x -> 1; return () => x; }");
+    let ast = parser.parse().unwrap();
+    let mut bytecode_generator = BytecodeGenerator::new();
+    let bytecode = bytecode_generator.generate(&ast);
+
+    assert_eq!(
+        bytecode.instructions,
+        vec![Instruction::MakeClosure(0), Instruction::StoreVar(0)]
+    );
+
+    let Constant::Function(outer) = &bytecode.constants[0] else {
+        panic!("Expected a function constant");
+    };
+    assert!(outer.captures.is_empty());
+    assert_eq!(
+        outer.instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::StoreVar(0),
+            Instruction::MakeClosure(1),
+            Instruction::Return,
+            Instruction::PushConst(2),
+            Instruction::Return
+        ]
+    );
+    assert_eq!(
+        outer.constants[1],
+        Constant::Function(FunctionObj {
+            name: None,
+            arity: 0,
+            param_names: vec![],
+            has_rest: false,
+            is_method: false,
+            instructions: vec![Instruction::LoadUpvalue(0), Instruction::Return],
+            constants: vec![],
+            captures: vec![Capture::Local(0)],
+            handlers: vec![],
+        })
+    );
+}
@@ -9,7 +9,7 @@ fn store_empty_variable_test() {
     let mut bytecode_generator = BytecodeGenerator::new();
     let bytecode = bytecode_generator.generate(&ast);
 
-    assert_eq!(bytecode.constants, vec![Constant::Number(0.0)]); // Default value for int should be in the constant pool.
+    assert_eq!(bytecode.constants, vec![Constant::Integer(0)]); // Default value for int should be in the constant pool.
 }
 
 #[test]
@@ -1,7 +1,7 @@
 use lumi_bytecode::BytecodeGenerator;
 use lumi_parser::Parser;
 use lumi_semantic::analyzer::SemanticAnalyzer;
-use lumi_vm::Vm;
+use lumi_vm::{VMError, Vm};
 
 pub struct Engine {
     semantic_analyzer: SemanticAnalyzer,
@@ -9,6 +9,12 @@ pub struct Engine {
     vm: Vm,
 }
 
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Engine {
     pub fn new() -> Self {
         Self {
@@ -21,6 +27,9 @@ impl Engine {
     pub fn evaluate(&mut self, source: &str) -> Result<(), String> {
         let mut parser = Parser::new(source);
         let ast = parser.parse().map_err(|e| format!("Parser error: {e}"))?;
+        if let Some(e) = parser.errors().first() {
+            return Err(format!("Parser error: {e}"));
+        }
 
         self.semantic_analyzer
             .analyze(&ast)
@@ -28,7 +37,12 @@ impl Engine {
 
         let bytecode = self.bytecode_generator.generate(&ast);
 
-        self.vm.execute(bytecode).unwrap();
+        self.vm.execute(bytecode).map_err(|e| match &e {
+            VMError::Uncaught { trace, .. } => {
+                format!("Runtime error: {e}\n  {}", trace.join("\n  "))
+            }
+            _ => format!("Runtime error: {e}"),
+        })?;
 
         Ok(())
     }
@@ -14,10 +14,7 @@ fn prompt(input: &mut String) -> bool {
         return false;
     }
 
-    match stdin().read_line(input) {
-        Ok(_) => true,
-        Err(_) => false,
-    }
+    stdin().read_line(input).is_ok()
 }
 
 // TODO: make a built-in function call to show all variables in the current environment.
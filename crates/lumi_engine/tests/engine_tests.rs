@@ -0,0 +1,16 @@
+use lumi_test::Engine;
+
+#[test]
+fn test_uncaught_throw_is_reported() {
+    let mut engine = Engine::new();
+    engine.evaluate(r#"fn fail() { throw "bad"; }"#).unwrap();
+
+    let error = engine.evaluate("fail();").unwrap_err();
+    assert_eq!(
+        error,
+        "Runtime error: Uncaught bad\n  at fail (line 1)\n  at <main> (line 1)"
+    );
+
+    // The engine keeps working after the error
+    assert!(engine.evaluate("let x -> 1; print x;").is_ok());
+}
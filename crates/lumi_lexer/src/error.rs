@@ -1,15 +1,78 @@
 use thiserror::Error;
 
+use crate::token::Span;
+
 #[derive(Debug, Error, Clone, PartialEq)]
 pub enum LexError {
-    #[error("Unexpected character: {0}")]
-    UnexpectedCharacter(char),
-    #[error("Invalid number: {0}")]
-    InvalidNumber(String),
-    #[error("Unterminated string")]
-    UnterminatedString,
-    #[error("Invalid token: {0}")]
-    InvalidToken(String),
-    #[error("Unterminated comment")]
-    UnterminatedComment,
+    #[error(
+        "Unexpected character: {character} at line {}, column {}",
+        span.start.line,
+        span.start.column
+    )]
+    UnexpectedCharacter { character: char, span: Span },
+    #[error(
+        "Invalid number: {value} at line {}, column {}",
+        span.start.line,
+        span.start.column
+    )]
+    InvalidNumber { value: String, span: Span },
+    #[error(
+        "Integer literal {value} does not fit in 64 bits at line {}, column {}, use an `n` suffix for big integers",
+        span.start.line,
+        span.start.column
+    )]
+    IntegerOverflow { value: String, span: Span },
+    #[error(
+        "Unterminated string at line {}, column {}",
+        span.start.line,
+        span.start.column
+    )]
+    UnterminatedString { span: Span },
+    #[error(
+        "Invalid token: {token} at line {}, column {}",
+        span.start.line,
+        span.start.column
+    )]
+    InvalidToken { token: String, span: Span },
+    #[error(
+        "Unterminated comment at line {}, column {}",
+        span.start.line,
+        span.start.column
+    )]
+    UnterminatedComment { span: Span },
+    #[error(
+        "Invalid escape sequence '{sequence}' at line {}, column {}",
+        span.start.line,
+        span.start.column
+    )]
+    InvalidEscape { sequence: String, span: Span },
+    #[error(
+        "Invalid code point '{value}' at line {}, column {}",
+        span.start.line,
+        span.start.column
+    )]
+    InvalidCodePoint { value: String, span: Span },
+    #[error(
+        "Failed to read source: {message} at line {}, column {}",
+        span.start.line,
+        span.start.column
+    )]
+    Io { message: String, span: Span },
+}
+
+impl LexError {
+    /// The source span of the offending input
+    pub fn span(&self) -> &Span {
+        match self {
+            LexError::UnexpectedCharacter { span, .. }
+            | LexError::InvalidNumber { span, .. }
+            | LexError::IntegerOverflow { span, .. }
+            | LexError::UnterminatedString { span }
+            | LexError::InvalidToken { span, .. }
+            | LexError::UnterminatedComment { span }
+            | LexError::InvalidEscape { span, .. }
+            | LexError::InvalidCodePoint { span, .. }
+            | LexError::Io { span, .. } => span,
+        }
+    }
 }
@@ -0,0 +1,292 @@
+use std::ops::Range;
+
+use crate::{
+    error::LexError,
+    lexer::Lexer,
+    token::{Position, Token, TokenKind},
+};
+
+/// A change to the source text: the byte `range` of the old text is replaced by `replacement`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, replacement: &str) -> Self {
+        Self {
+            range,
+            replacement: replacement.to_string(),
+        }
+    }
+
+    pub fn insert(offset: usize, text: &str) -> Self {
+        Self::new(offset..offset, text)
+    }
+
+    pub fn delete(range: Range<usize>) -> Self {
+        Self::new(range, "")
+    }
+
+    /// Apply the edit to the old text
+    pub fn apply(&self, source: &str) -> String {
+        let mut edited = source.to_string();
+        edited.replace_range(self.range.clone(), &self.replacement);
+        edited
+    }
+
+    /// How far the text after the edit moves, in bytes
+    fn delta(&self) -> isize {
+        self.replacement.len() as isize - self.range.len() as isize
+    }
+}
+
+/// Tokens affected by an incremental re-lex
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenChange {
+    /// Indices of the replaced tokens in the previous token vector
+    pub removed: Range<usize>,
+    /// Indices of the freshly lexed tokens in the updated token vector
+    pub inserted: Range<usize>,
+    /// Lexical errors found in the re-lexed region, reported as `Error` tokens
+    pub errors: Vec<LexError>,
+}
+
+impl<'a> Lexer<'a> {
+    /// Update `tokens` after `edit`, where `source` is the text with the edit applied.
+    ///
+    /// Lexing restarts just before the edit and stops once the new tokens line up with
+    /// the old ones again; the remaining old tokens are kept and only their positions
+    /// are shifted. `tokens` must cover the whole previous text, as produced by
+    /// `tokenize` or `tokenize_with_errors`, or by a lexer built `with_trivia` when
+    /// `preserve_trivia` is set. The new tokens borrow from `source`, so the old text may
+    /// only be dropped once every old token is gone.
+    pub fn relex(
+        source: &'a str,
+        tokens: &mut Vec<Token<'a>>,
+        edit: &TextEdit,
+        preserve_trivia: bool,
+    ) -> TokenChange {
+        let file = tokens.first().map(|t| t.span.file).unwrap_or_default();
+        let delta = edit.delta();
+        let edit_end = edit.range.start + edit.replacement.len();
+
+        // First token that the edit can change. The lexer looks past the end of a token
+        // to decide where it ends, so the one before it may change too
+        let mut first = tokens
+            .iter()
+            .position(|t| full_end(t).offset >= edit.range.start)
+            .unwrap_or(tokens.len())
+            .saturating_sub(1);
+
+        // Text of the old source from an offset on, unknown where the edit replaced it
+        let old_text = |offset: usize| match offset {
+            offset if offset < edit.range.start => Some(&source[offset..]),
+            offset if offset >= edit.range.end => {
+                Some(&source[shift_offset(offset, delta) as usize..])
+            }
+            _ => None,
+        };
+
+        // An error inside a string may have opened or closed an interpolation without a
+        // token to show it, so restart before the first one
+        if let Some(idx) = tokens[..first]
+            .iter()
+            .position(|t| hides_template_state(t, old_text(t.span.start.offset)))
+        {
+            first = idx;
+        }
+
+        // Interpolations carry lexer state, so restart before the outermost open one
+        let mut depth = template_depth(&tokens[..first]);
+        while depth > 0 {
+            first -= 1;
+            match tokens[first].kind {
+                TokenKind::TemplateHead(_) => depth -= 1,
+                TokenKind::TemplateTail(_) => depth += 1,
+                _ => {}
+            }
+        }
+
+        let restart = first
+            .checked_sub(1)
+            .map(|i| full_end(&tokens[i]))
+            .unwrap_or(Position::with_offset(1, 1, 0));
+        let mut lexer = Lexer::resume(&source[restart.offset..], restart, file, preserve_trivia);
+
+        let mut new_tokens = Vec::new();
+        let mut errors = Vec::new();
+        let mut old = first;
+        let mut old_depth = 0;
+        // Whether an old error made the interpolations open at `old` unknown
+        let mut old_state_lost = false;
+        let mut resync = None;
+
+        loop {
+            let token = lexer.next_token_recovering(&mut errors);
+            let start = full_start(&token);
+
+            // Skip the old tokens that now lie before the new token
+            while old < tokens.len()
+                && shift_offset(full_start(&tokens[old]).offset, delta) < start.offset as isize
+            {
+                old_depth += template_depth(std::slice::from_ref(&tokens[old]));
+                old_state_lost |=
+                    hides_template_state(&tokens[old], old_text(tokens[old].span.start.offset));
+                old += 1;
+            }
+
+            if start.offset >= edit_end
+                && old < tokens.len()
+                && old_depth == 0
+                && !old_state_lost
+                && !lexer.in_template()
+                && is_same_token(&tokens[old], &token, delta)
+            {
+                resync = Some((old, start));
+                break;
+            }
+
+            let is_eof = token.kind == TokenKind::Eof;
+            new_tokens.push(token);
+            if is_eof {
+                break;
+            }
+        }
+
+        let removed_end = match resync {
+            Some((old, new_start)) => {
+                let old_start = full_start(&tokens[old]);
+                let shift = Shift {
+                    offset: delta,
+                    line: new_start.line as isize - old_start.line as isize,
+                    column: new_start.column as isize - old_start.column as isize,
+                    anchor_line: old_start.line,
+                };
+                for token in &mut tokens[old..] {
+                    shift.apply(token);
+                }
+                old
+            }
+            None => tokens.len(),
+        };
+
+        let inserted = first..first + new_tokens.len();
+        tokens.splice(first..removed_end, new_tokens);
+
+        TokenChange {
+            removed: first..removed_end,
+            inserted,
+            errors,
+        }
+    }
+}
+
+/// How positions after an edit move
+struct Shift {
+    offset: isize,
+    line: isize,
+    /// Column change, only for positions on the line the edit ends on
+    column: isize,
+    anchor_line: usize,
+}
+
+impl Shift {
+    fn apply(&self, token: &mut Token) {
+        if let TokenKind::Error(message) = &mut token.kind {
+            self.apply_message(message);
+        }
+        let spans = std::iter::once(&mut token.span).chain(
+            token
+                .leading_trivia
+                .iter_mut()
+                .chain(token.trailing_trivia.iter_mut())
+                .map(|t| &mut t.span),
+        );
+        for span in spans {
+            self.apply_position(&mut span.start);
+            self.apply_position(&mut span.end);
+        }
+    }
+
+    /// Move the position an error message names, as in `... at line 3, column 7`
+    fn apply_message(&self, message: &mut String) {
+        let Some(at) = message.rfind(" at line ") else {
+            return;
+        };
+        let rest = &message[at + " at line ".len()..];
+        let Some((line, rest)) = rest.split_once(", column ") else {
+            return;
+        };
+        let column_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (Ok(line), Ok(column)) = (line.parse(), rest[..column_len].parse()) else {
+            return;
+        };
+
+        let (line, column) = self.move_location(line, column);
+        let end = message.len() - rest.len() + column_len;
+        message.replace_range(at..end, &format!(" at line {line}, column {column}"));
+    }
+
+    fn apply_position(&self, position: &mut Position) {
+        (position.line, position.column) = self.move_location(position.line, position.column);
+        position.offset = shift_offset(position.offset, self.offset) as usize;
+    }
+
+    fn move_location(&self, line: usize, column: usize) -> (usize, usize) {
+        let column = match line == self.anchor_line {
+            true => (column as isize + self.column) as usize,
+            false => column,
+        };
+        ((line as isize + self.line) as usize, column)
+    }
+}
+
+fn shift_offset(offset: usize, delta: isize) -> isize {
+    offset as isize + delta
+}
+
+/// Start of a token including its leading trivia
+fn full_start(token: &Token) -> Position {
+    token
+        .leading_trivia
+        .first()
+        .map_or(token.span.start, |t| t.span.start)
+}
+
+/// End of a token including its trailing trivia
+fn full_end(token: &Token) -> Position {
+    token
+        .trailing_trivia
+        .last()
+        .map_or(token.span.end, |t| t.span.end)
+}
+
+/// Number of interpolated strings left open after the tokens
+fn template_depth(tokens: &[Token]) -> isize {
+    tokens
+        .iter()
+        .map(|t| match t.kind {
+            TokenKind::TemplateHead(_) => 1,
+            TokenKind::TemplateTail(_) => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Whether `token` is an error in a string, which can stand in for an interpolation's
+/// head or tail. `text` is the source from the token on, if it is still known
+fn hides_template_state(token: &Token, text: Option<&str>) -> bool {
+    matches!(token.kind, TokenKind::Error(_))
+        && text.is_none_or(|text| text.starts_with(['"', '\'', '}']))
+}
+
+/// Whether a freshly lexed token matches an old one moved by the edit
+fn is_same_token(old: &Token, new: &Token, delta: isize) -> bool {
+    shift_offset(full_start(old).offset, delta) == full_start(new).offset as isize
+        && shift_offset(full_end(old).offset, delta) == full_end(new).offset as isize
+        && old.kind == new.kind
+}
@@ -1,81 +1,262 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    io::{self, BufRead},
+    ops::Range,
+};
+
+use num_bigint::BigInt;
+use unicode_normalization::{UnicodeNormalization, is_nfc};
+use unicode_xid::UnicodeXID;
+
 use crate::{
     error::LexError,
-    token::{Token, TokenKind},
+    token::{FileId, Keyword, Position, Span, Token, TokenKind, Trivia, TriviaKind},
 };
 
-#[derive(Debug)]
-pub struct Lexer {
-    source: Vec<char>,
+/// Whether `c` can start an identifier: Unicode `XID_Start`, `_` or `$` (UAX #31)
+fn is_identifier_start(c: char) -> bool {
+    c.is_xid_start() || c == '_' || c == '$'
+}
+
+/// Whether `c` can continue an identifier: Unicode `XID_Continue` or `$` (UAX #31)
+fn is_identifier_continue(c: char) -> bool {
+    c.is_xid_continue() || c == '$'
+}
+
+/// The quote that opened a string literal and whether it was tripled (`"""`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StringDelimiter {
+    quote: char,
+    triple: bool,
+}
+
+/// Tracks an open `${ ... }` segment inside an interpolated string
+#[derive(Debug, Clone, Copy)]
+struct TemplateState {
+    delimiter: StringDelimiter,
+    start: Position,
+    brace_depth: usize,
+}
+
+pub struct Lexer<'a> {
+    /// The borrowed source, or a buffer filled from a reader
+    source: Cow<'a, str>,
+    /// Byte index of the current character in `source`
     pos: usize,
+    /// Byte offset of `source` in the whole input, text already lexed from a reader is dropped
+    base: usize,
     line: usize,
     column: usize,
+    file: FileId,
+    token_start: Position,
+    template_stack: Vec<TemplateState>,
+    preserve_trivia: bool,
+    /// Streaming input, read a line at a time as the lexer runs out of text
+    reader: Option<Box<dyn BufRead + 'a>>,
+    /// Read failure, reported in place of the next token
+    read_error: Option<io::Error>,
+    /// Whether the iterator has reached the end of the input
+    finished: bool,
 }
 
-impl Lexer {
-    pub fn new(source: &str) -> Self {
+impl<'a> Lexer<'a> {
+    /// Lexer over a string, tokens borrow their text from it where possible
+    pub fn new(source: &'a str) -> Self {
+        Self::from_source(Cow::Borrowed(source))
+    }
+
+    /// Lexer over streaming input, read a line at a time as tokens are requested.
+    ///
+    /// Tokens own their text and the input that was already lexed is dropped,
+    /// so large sources never have to be held in memory at once.
+    pub fn from_reader(reader: impl BufRead + 'a) -> Self {
         Self {
-            source: source.chars().collect(),
+            reader: Some(Box::new(reader)),
+            ..Self::from_source(Cow::Owned(String::new()))
+        }
+    }
+
+    fn from_source(source: Cow<'a, str>) -> Self {
+        Self {
+            source,
             pos: 0,
+            base: 0,
             line: 1,
             column: 1,
+            file: FileId::default(),
+            token_start: Position::default(),
+            template_stack: Vec::new(),
+            preserve_trivia: false,
+            reader: None,
+            read_error: None,
+            finished: false,
         }
     }
 
-    pub fn tokenize(&mut self) -> Result<Vec<Token>, LexError> {
-        let mut tokens = Vec::new();
+    /// Lexer over the tail of a source that starts at `start`, outside of any interpolation
+    pub(crate) fn resume(
+        source_tail: &'a str,
+        start: Position,
+        file: FileId,
+        preserve_trivia: bool,
+    ) -> Self {
+        Self {
+            base: start.offset,
+            line: start.line,
+            column: start.column,
+            file,
+            token_start: start,
+            preserve_trivia,
+            ..Self::new(source_tail)
+        }
+    }
 
-        while self.pos < self.source.len() {
-            let start_line = self.line;
-            let start_col = self.column;
+    /// Whether the lexer is inside an interpolated string
+    pub(crate) fn in_template(&self) -> bool {
+        !self.template_stack.is_empty()
+    }
 
-            let token = self.next_token()?;
+    /// Mark every span produced by this lexer as belonging to `file`
+    pub fn with_file(mut self, file: FileId) -> Self {
+        self.file = file;
+        self
+    }
+
+    /// Keep whitespace and comments as trivia on the tokens instead of dropping them,
+    /// so the token stream can reproduce the source exactly
+    pub fn with_trivia(mut self) -> Self {
+        self.preserve_trivia = true;
+        self
+    }
+
+    pub fn tokenize(&mut self) -> Result<Vec<Token<'a>>, LexError> {
+        let mut tokens = Vec::new();
 
-            if matches!(token.kind, TokenKind::Eof) {
-                tokens.push(token);
+        loop {
+            let token = self.next_token()?;
+            let is_eof = matches!(token.kind, TokenKind::Eof);
+            tokens.push(token);
+            if is_eof {
                 break;
             }
+        }
+
+        Ok(tokens)
+    }
+
+    /// Tokenize the whole source without stopping at the first error.
+    ///
+    /// Every lexical error is collected and replaced by an `Error` token in the
+    /// returned stream, so a parser can report it and recover.
+    pub fn tokenize_with_errors(&mut self) -> (Vec<Token<'a>>, Vec<LexError>) {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
 
+        loop {
+            let token = self.next_token_recovering(&mut errors);
+            let is_eof = matches!(token.kind, TokenKind::Eof);
             tokens.push(token);
+            if is_eof {
+                break;
+            }
+        }
+
+        (tokens, errors)
+    }
+
+    /// Read the next token, turning a lexical error into an `Error` token.
+    ///
+    /// The error is pushed onto `errors` and the lexer is left past the bad input.
+    pub fn next_token_recovering(&mut self, errors: &mut Vec<LexError>) -> Token<'a> {
+        let (token, error) = self.read_next();
+        errors.extend(error);
+        token
+    }
+
+    pub fn next_token(&mut self) -> Result<Token<'a>, LexError> {
+        match self.read_next() {
+            (_, Some(error)) => Err(error),
+            (token, None) => Ok(token),
+        }
+    }
+
+    /// Read the next token, a lexical error comes with the `Error` token that stands in
+    /// for the bad input. With trivia kept the `Error` token has the text and the leading
+    /// trivia of the bad input too, so no source text is lost
+    fn read_next(&mut self) -> (Token<'a>, Option<LexError>) {
+        self.discard_consumed();
 
-            self.update_positions(start_line, start_col);
+        if !self.preserve_trivia {
+            self.skip_whitespace();
+            let start = self.current_position();
+            return match self.read_token() {
+                Ok(token) => (token, None),
+                Err(error) => (self.error_token(&error, start), Some(error)),
+            };
         }
 
-        if tokens.is_empty() || !matches!(tokens.last().unwrap().kind, TokenKind::Eof) {
-            tokens.push(Token::with_positions(
-                TokenKind::Eof,
-                self.line,
-                self.column,
-                self.line,
-                self.column,
-            ));
+        let start = self.current_position();
+        let mut leading_trivia = Vec::new();
+        let read = self
+            .read_leading_trivia(&mut leading_trivia)
+            .and_then(|()| self.read_token());
+        // A bad comment starts where the trivia before it ends
+        let start = leading_trivia.last().map_or(start, |t| t.span.end);
+        let (mut token, error) = match read {
+            Ok(token) => (token, None),
+            Err(error) => (self.error_token(&error, start), Some(error)),
+        };
+        token.lexeme = Some(self.slice(start.offset - self.base..self.pos));
+        token.leading_trivia = leading_trivia;
+        if error.is_none() && token.kind != TokenKind::Eof {
+            token.trailing_trivia = self.read_trailing_trivia();
         }
 
-        Ok(tokens)
+        (token, error)
     }
 
-    pub fn next_token(&mut self) -> Result<Token, LexError> {
-        self.skip_whitespace();
+    /// `Error` token for `error`, spanning the input read since `start`
+    fn error_token(&self, error: &LexError, start: Position) -> Token<'a> {
+        Token::new(TokenKind::Error(error.to_string()), self.span_from(start))
+    }
 
-        if self.pos >= self.source.len() {
-            return Ok(Token::with_positions(
-                TokenKind::Eof,
-                self.line,
-                self.column,
-                self.line,
-                self.column,
-            ));
-        }
+    /// Read the token starting at the current position, whitespace already skipped
+    fn read_token(&mut self) -> Result<Token<'a>, LexError> {
+        self.token_start = self.current_position();
 
-        let start_line = self.line;
-        let start_col = self.column;
-        let c = self.source[self.pos];
+        let Some(c) = self.peek_char(0) else {
+            if let Some(error) = self.read_error.take() {
+                return Err(LexError::Io {
+                    message: error.to_string(),
+                    span: self.error_span(),
+                });
+            }
+            if let Some(state) = self.template_stack.first().copied() {
+                // Drop the open interpolations so the next call reaches EOF
+                self.template_stack.clear();
+                return Err(LexError::UnterminatedString {
+                    span: Span::new(state.start, self.token_start).with_file(self.file),
+                });
+            }
+            return Ok(Token::new(TokenKind::Eof, self.error_span()));
+        };
 
-        let token_kind = if c.is_ascii_alphabetic() || c == '_' || c == '$' || !c.is_ascii() {
+        let token_kind = if c == 'r' && matches!(self.peek_char(1), Some('"') | Some('\'')) {
+            self.read_raw_string()?
+        } else if is_identifier_start(c) {
             self.read_identifier_or_keyword()?
         } else if c.is_ascii_digit() {
             self.read_number()?
         } else if c == '"' || c == '\'' {
             self.read_string()?
+        } else if c == '}'
+            && self
+                .template_stack
+                .last()
+                .is_some_and(|s| s.brace_depth == 0)
+        {
+            self.read_template_continuation()?
         } else if c == '/' {
             if self.peek_char(1) == Some('/') {
                 self.read_line_comment()?
@@ -85,261 +266,480 @@ impl Lexer {
                 self.read_operator()?
             }
         } else {
+            self.track_template_braces(c);
             self.read_operator()?
         };
 
-        let end_line = self.line;
-        let end_col = self.column;
-
-        Ok(Token::with_positions(
-            token_kind, start_line, start_col, end_line, end_col,
-        ))
+        Ok(Token::new(token_kind, self.span_from(self.token_start)))
     }
 
     /// Read an identifier or keyword
-    fn read_identifier_or_keyword(&mut self) -> Result<TokenKind, LexError> {
-        let mut identifier = String::new();
-
-        while self.pos < self.source.len() {
-            let c = self.source[self.pos];
-            // TODO: Support Unicode identifiers
-            if c.is_alphanumeric() || c.is_alphabetic() || !c.is_ascii() {
-                identifier.push(c);
-                self.advance();
-            } else {
-                break;
-            }
+    fn read_identifier_or_keyword(&mut self) -> Result<TokenKind<'a>, LexError> {
+        let start = self.pos;
+
+        while self.peek_char(0).is_some_and(is_identifier_continue) {
+            self.advance();
+        }
+
+        let mut identifier = self.slice(start..self.pos);
+        // Normalize so that visually identical names are the same identifier
+        if !is_nfc(&identifier) {
+            identifier = Cow::Owned(identifier.nfc().collect());
         }
 
         // Check if it's a keyword
-        match identifier.as_str() {
+        match &*identifier {
             "true" => Ok(TokenKind::Boolean(true)),
             "false" => Ok(TokenKind::Boolean(false)),
             "null" => Ok(TokenKind::Null),
             "undefined" => Ok(TokenKind::Undefined),
-            "this" => Ok(TokenKind::Keyword("this".to_string())),
-            "super" => Ok(TokenKind::Keyword("super".to_string())),
-            // keywords
-            "let" | "const" | "fn" | "if" | "else" | "return" | "async" | "await" | "yield"
-            | "import" | "export" | "new" | "class" | "extends" | "static" | "get" | "set"
-            | "try" | "catch" | "finally" | "throw" | "break" | "continue" | "switch" | "case"
-            | "default" | "for" | "while" | "do" | "in" | "of" | "with" | "delete"
-            | "instanceof" | "typeof" | "void" | "debugger" | "enum" | "interface" | "package"
-            | "private" | "protected" | "public" | "implements" | "abstract" | "bool" | "byte"
-            | "char" | "double" | "final" | "float" | "goto" | "int" | "long" | "str"
-            | "native" | "short" | "synchronized" | "throws" | "transient" | "volatile" | "to"
-            | "step" | "print" => Ok(TokenKind::Keyword(identifier)),
-            _ => Ok(TokenKind::Identifier(identifier)),
-        }
-    }
-
-    /// Read a number literal
-    fn read_number(&mut self) -> Result<TokenKind, LexError> {
-        let mut number = String::new();
-        let mut is_hex = false;
-        let mut is_binary = false;
-        let mut is_octal = false;
-
-        // Check for hex, binary, or octal
-        if self.source[self.pos] == '0' && self.pos + 1 < self.source.len() {
-            match self.source[self.pos + 1] {
-                'x' | 'X' => {
-                    is_hex = true;
-                    number.push('0');
-                    number.push(self.source[self.pos + 1]);
-                    self.advance();
-                    self.advance();
-                }
-                'b' | 'B' => {
-                    is_binary = true;
-                    number.push('0');
-                    number.push(self.source[self.pos + 1]);
-                    self.advance();
-                    self.advance();
-                }
-                'o' | 'O' => {
-                    is_octal = true;
-                    number.push('0');
-                    number.push(self.source[self.pos + 1]);
-                    self.advance();
+            _ => Ok(match Keyword::from_word(&identifier) {
+                Some(keyword) => TokenKind::Keyword(keyword),
+                None => TokenKind::Identifier(identifier),
+            }),
+        }
+    }
+
+    /// Read a number literal.
+    ///
+    /// Decimal literals with a fraction or exponent become `Float`, other literals
+    /// (including `0x`, `0b` and `0o` ones) become `Integer`, or `BigInt` with an `n` suffix.
+    /// Digits may be grouped with `_` separators, e.g. `1_000_000`.
+    fn read_number(&mut self) -> Result<TokenKind<'a>, LexError> {
+        let start = self.pos;
+        let radix = match (self.peek_char(0), self.peek_char(1)) {
+            (Some('0'), Some('x') | Some('X')) => 16,
+            (Some('0'), Some('b') | Some('B')) => 2,
+            (Some('0'), Some('o') | Some('O')) => 8,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.advance(); // Skip '0'
+            self.advance(); // Skip the radix prefix
+        }
+
+        let mut digits = String::new();
+        let mut is_float = false;
+        let mut valid = self.read_digits(radix, &mut digits);
+
+        if radix == 10 {
+            // A dot followed by anything but a digit is not part of the number, e.g. `1.foo`
+            if self.peek_char(0) == Some('.')
+                && self.peek_char(1).is_some_and(|c| c.is_ascii_digit())
+            {
+                is_float = true;
+                digits.push('.');
+                self.advance();
+                valid &= self.read_digits(10, &mut digits);
+            }
+            if matches!(self.peek_char(0), Some('e') | Some('E')) {
+                is_float = true;
+                digits.push('e');
+                self.advance();
+                if let Some(sign @ ('+' | '-')) = self.peek_char(0) {
+                    digits.push(sign);
                     self.advance();
                 }
+                valid &= self.read_digits(10, &mut digits);
+            }
+        }
+
+        let is_big = !is_float && self.peek_char(0) == Some('n');
+        if is_big {
+            self.advance(); // Skip 'n'
+        }
+
+        // Anything still attached to the literal makes it malformed, e.g. `1..2`, `1.2.3` or `0b12`
+        while let Some(c) = self.peek_char(0) {
+            let continues_number = c.is_alphanumeric()
+                || c == '_'
+                || (c == '.'
+                    && self
+                        .peek_char(1)
+                        .is_some_and(|next| next.is_ascii_digit() || next == '.'));
+            if !continues_number {
+                break;
+            }
+            valid = false;
+            self.advance();
+        }
+
+        let invalid_number = |lexer: &Self| LexError::InvalidNumber {
+            value: lexer.source[start..lexer.pos].to_string(),
+            span: lexer.error_span(),
+        };
+
+        if !valid {
+            return Err(invalid_number(self));
+        }
+
+        if is_float {
+            match digits.parse::<f64>() {
+                Ok(n) if n.is_finite() => Ok(TokenKind::Float(n)),
+                _ => Err(invalid_number(self)),
+            }
+        } else if is_big {
+            BigInt::parse_bytes(digits.as_bytes(), radix)
+                .map(|n| TokenKind::BigInt(n.to_string()))
+                .ok_or_else(|| invalid_number(self))
+        } else {
+            i64::from_str_radix(&digits, radix)
+                .map(TokenKind::Integer)
+                .map_err(|_| LexError::IntegerOverflow {
+                    value: self.source[start..self.pos].to_string(),
+                    span: self.error_span(),
+                })
+        }
+    }
+
+    /// Read digits of the given radix, skipping `_` separators.
+    ///
+    /// Returns false when there are no digits or a separator is not placed between two digits.
+    fn read_digits(&mut self, radix: u32, digits: &mut String) -> bool {
+        let mut count = 0;
+        let mut valid = true;
+        let mut after_separator = false;
+
+        while let Some(c) = self.peek_char(0) {
+            if c == '_' {
+                valid &= count > 0 && !after_separator;
+                after_separator = true;
+            } else if c.is_digit(radix) {
+                digits.push(c);
+                count += 1;
+                after_separator = false;
+            } else {
+                break;
+            }
+            self.advance();
+        }
+
+        valid && count > 0 && !after_separator
+    }
+
+    /// Read a string literal, either quoted (`"..."`, `'...'`) or triple-quoted (`"""..."""`)
+    fn read_string(&mut self) -> Result<TokenKind<'a>, LexError> {
+        let delimiter = self.read_string_delimiter();
+        self.read_string_body(delimiter, self.token_start, false)
+    }
+
+    /// Read a raw string literal (`r"..."` or `r"""..."""`), escapes and interpolations are kept as is
+    fn read_raw_string(&mut self) -> Result<TokenKind<'a>, LexError> {
+        self.advance(); // Skip 'r'
+        let delimiter = self.read_string_delimiter();
+        let content_start = self.pos;
+
+        while self.peek_char(0).is_some() {
+            if self.is_closing_delimiter(delimiter) {
+                let string = self.slice(content_start..self.pos);
+                self.skip_closing_delimiter(delimiter);
+                return Ok(TokenKind::String(string));
+            }
+            self.advance_tracking_lines();
+        }
+
+        Err(LexError::UnterminatedString {
+            span: self.error_span(),
+        })
+    }
+
+    /// Consume the opening quote(s) of a string
+    fn read_string_delimiter(&mut self) -> StringDelimiter {
+        let quote = self
+            .peek_char(0)
+            .expect("string literal starts with a quote");
+        let triple = self.peek_char(1) == Some(quote) && self.peek_char(2) == Some(quote);
+
+        self.advance(); // Skip opening quote
+        if triple {
+            self.advance();
+            self.advance();
+        }
+
+        StringDelimiter { quote, triple }
+    }
+
+    fn is_closing_delimiter(&mut self, delimiter: StringDelimiter) -> bool {
+        let mut closes = |offset| self.peek_char(offset) == Some(delimiter.quote);
+        closes(0) && (!delimiter.triple || (closes(1) && closes(2)))
+    }
+
+    fn skip_closing_delimiter(&mut self, delimiter: StringDelimiter) {
+        let len = if delimiter.triple { 3 } else { 1 };
+        for _ in 0..len {
+            self.advance();
+        }
+    }
+
+    /// Resume an interpolated string after the `}` closing one of its expressions
+    fn read_template_continuation(&mut self) -> Result<TokenKind<'a>, LexError> {
+        self.advance(); // Skip '}'
+        let state = self
+            .template_stack
+            .pop()
+            .ok_or(LexError::UnexpectedCharacter {
+                character: '}',
+                span: self.error_span(),
+            })?;
+
+        self.read_string_body(state.delimiter, state.start, true)
+    }
+
+    /// Keep count of braces opened inside an interpolation so only the matching `}` ends it
+    fn track_template_braces(&mut self, c: char) {
+        if let Some(state) = self.template_stack.last_mut() {
+            match c {
+                '{' => state.brace_depth += 1,
+                '}' => state.brace_depth -= 1,
                 _ => {}
             }
         }
+    }
 
-        while self.pos < self.source.len() {
-            let c = self.source[self.pos];
+    /// Read the characters of a string up to the closing quote or the next `${`.
+    ///
+    /// A plain string yields `String`; once an interpolation is involved the pieces
+    /// are returned as `TemplateHead`, `TemplateMiddle` and `TemplateTail`.
+    /// A bad escape does not stop the scan, its error is returned once the piece is read.
+    fn read_string_body(
+        &mut self,
+        delimiter: StringDelimiter,
+        start: Position,
+        continuation: bool,
+    ) -> Result<TokenKind<'a>, LexError> {
+        let content_start = self.pos;
+        // Only allocated once an escape makes the text differ from the source
+        let mut unescaped: Option<String> = None;
+        let mut escape_error = None;
 
-            if is_hex {
-                if c.is_ascii_hexdigit() {
-                    number.push(c);
-                    self.advance();
-                } else {
-                    break;
+        while let Some(c) = self.peek_char(0) {
+            if self.is_closing_delimiter(delimiter) {
+                let string = self.string_text(unescaped, content_start);
+                self.skip_closing_delimiter(delimiter);
+                if let Some(error) = escape_error {
+                    return Err(error);
                 }
-            } else if is_binary {
-                if c == '0' || c == '1' {
-                    number.push(c);
-                    self.advance();
+                return Ok(if continuation {
+                    TokenKind::TemplateTail(string)
                 } else {
-                    break;
+                    TokenKind::String(string)
+                });
+            } else if c == '$' && self.peek_char(1) == Some('{') {
+                let string = self.string_text(unescaped, content_start);
+                self.advance(); // Skip '$'
+                self.advance(); // Skip '{'
+                self.template_stack.push(TemplateState {
+                    delimiter,
+                    start,
+                    brace_depth: 0,
+                });
+                if let Some(error) = escape_error {
+                    return Err(error);
                 }
-            } else if is_octal {
-                if c >= '0' && c <= '7' {
-                    number.push(c);
-                    self.advance();
+                return Ok(if continuation {
+                    TokenKind::TemplateMiddle(string)
                 } else {
-                    break;
+                    TokenKind::TemplateHead(string)
+                });
+            } else if c == '\\' {
+                let string = unescaped
+                    .get_or_insert_with(|| self.source[content_start..self.pos].to_string());
+                if let Err(error) = self.read_escape_sequence(string) {
+                    escape_error.get_or_insert(error);
                 }
             } else {
-                if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || c == '+' || c == '-' {
-                    number.push(c);
-                    self.advance();
-                } else {
-                    break;
+                if let Some(string) = &mut unescaped {
+                    string.push(c);
                 }
+                self.advance_tracking_lines();
             }
         }
 
-        // TODO: implement later for LNum
-        // Check for BigInt suffix
-        // if self.pos < self.source.len() && self.source[self.pos] == 'n' {
-        //     number.push('n');
-        //     self.advance();
-        //     return Ok(TokenKind::BigInt(number));
-        // }
+        // We reached the end without finding a closing quote
+        Err(LexError::UnterminatedString {
+            span: self.span_from(start),
+        })
+    }
+
+    /// Text of a string piece that ends at the current position
+    fn string_text(&self, unescaped: Option<String>, content_start: usize) -> Cow<'a, str> {
+        match unescaped {
+            Some(string) => Cow::Owned(string),
+            None => self.slice(content_start..self.pos),
+        }
+    }
+
+    /// Read an escape sequence starting at the backslash and push the resulting character
+    fn read_escape_sequence(&mut self, string: &mut String) -> Result<(), LexError> {
+        let start = self.current_position();
+        self.advance(); // Skip backslash
 
-        // Parse as number
-        if is_hex {
-            // Parse hex number
-            match u64::from_str_radix(&number[2..], 16) {
-                Ok(n) => Ok(TokenKind::Number(n as f64)),
-                Err(_) => Err(LexError::InvalidNumber(number)),
+        let escaped = self.peek_char(0).ok_or(LexError::UnterminatedString {
+            span: self.error_span(),
+        })?;
+        match escaped {
+            'n' => string.push('\n'),
+            't' => string.push('\t'),
+            'r' => string.push('\r'),
+            '0' => string.push('\0'),
+            '\\' => string.push('\\'),
+            '"' => string.push('"'),
+            '\'' => string.push('\''),
+            '$' => string.push('$'),
+            '\n' | '\r' => {
+                // Line continuation: drop the line break and the indentation that follows it
+                if escaped == '\r' && self.peek_char(1) == Some('\n') {
+                    self.advance();
+                }
+                self.advance_tracking_lines();
+                while matches!(self.peek_char(0), Some(' ') | Some('\t')) {
+                    self.advance();
+                }
+                return Ok(());
             }
-        } else if is_binary {
-            // Parse binary number
-            match u64::from_str_radix(&number[2..], 2) {
-                Ok(n) => Ok(TokenKind::Number(n as f64)),
-                Err(_) => Err(LexError::InvalidNumber(number)),
+            'x' => {
+                self.advance(); // Skip 'x'
+                let digits: String = (0..2).filter_map(|i| self.peek_char(i)).collect();
+                if digits.len() != 2 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(LexError::InvalidEscape {
+                        sequence: format!("\\x{}", digits),
+                        span: self.span_from(start),
+                    });
+                }
+                self.advance();
+                self.advance();
+                let value = u32::from_str_radix(&digits, 16).unwrap_or_default();
+                string.push(char::from_u32(value).unwrap_or_default());
+                return Ok(());
             }
-        } else if is_octal {
-            // Parse octal number
-            match u64::from_str_radix(&number[2..], 8) {
-                Ok(n) => Ok(TokenKind::Number(n as f64)),
-                Err(_) => Err(LexError::InvalidNumber(number)),
+            'u' => {
+                self.advance(); // Skip 'u'
+                let value = self.read_unicode_escape(start)?;
+                string.push(value);
+                return Ok(());
             }
-        } else {
-            // Parse decimal number
-            match number.parse::<f64>() {
-                Ok(n) => Ok(TokenKind::Number(n)),
-                Err(_) => Err(LexError::InvalidNumber(number)),
+            _ => {
+                self.advance();
+                return Err(LexError::InvalidEscape {
+                    sequence: format!("\\{}", escaped),
+                    span: self.span_from(start),
+                });
             }
         }
+        self.advance();
+        Ok(())
     }
 
-    /// Read a string literal
-    fn read_string(&mut self) -> Result<TokenKind, LexError> {
-        let quote = self.source[self.pos];
-        self.advance(); // Skip opening quote
-
-        let mut string = String::new();
-
-        let mut found_closing_quote = false;
+    /// Read the `{XXXX}` part of a `\u{XXXX}` escape, with one to six hex digits
+    fn read_unicode_escape(&mut self, start: Position) -> Result<char, LexError> {
+        let mut sequence = String::from("\\u");
 
-        while self.pos < self.source.len() {
-            let c = self.source[self.pos];
+        if self.peek_char(0) != Some('{') {
+            return Err(LexError::InvalidEscape {
+                sequence,
+                span: self.span_from(start),
+            });
+        }
+        self.advance(); // Skip '{'
+        sequence.push('{');
 
-            if c == quote {
-                self.advance(); // Skip closing quote
-                found_closing_quote = true;
+        let mut digits = String::new();
+        while let Some(c) = self.peek_char(0) {
+            if c == '}' || c == '"' || c == '\'' || c.is_whitespace() {
                 break;
-            } else if c == '\\' {
-                self.advance(); // Skip backslash
-                if self.pos < self.source.len() {
-                    let escaped = self.source[self.pos];
-                    match escaped {
-                        'n' => string.push('\n'),
-                        't' => string.push('\t'),
-                        'r' => string.push('\r'),
-                        '\\' => string.push('\\'),
-                        '"' => string.push('"'),
-                        '\'' => string.push('\''),
-                        _ => string.push(escaped),
-                    }
-                    self.advance();
-                }
-            } else {
-                string.push(c);
-                self.advance();
             }
+            digits.push(c);
+            sequence.push(c);
+            self.advance();
+        }
+
+        let closed = self.peek_char(0) == Some('}');
+        if closed {
+            self.advance(); // Skip '}'
+            sequence.push('}');
         }
 
-        // Check if we reached the end without finding a closing quote
-        if !found_closing_quote {
-            return Err(LexError::UnterminatedString);
+        if !closed
+            || digits.is_empty()
+            || digits.len() > 6
+            || !digits.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(LexError::InvalidEscape {
+                sequence,
+                span: self.span_from(start),
+            });
         }
 
-        Ok(TokenKind::String(string))
+        let value = u32::from_str_radix(&digits, 16).unwrap_or(u32::MAX);
+        char::from_u32(value).ok_or(LexError::InvalidCodePoint {
+            value: digits,
+            span: self.span_from(start),
+        })
     }
 
     /// Read a line comment
-    fn read_line_comment(&mut self) -> Result<TokenKind, LexError> {
+    fn read_line_comment(&mut self) -> Result<TokenKind<'a>, LexError> {
         self.advance(); // Skip first '/'
         self.advance(); // Skip second '/'
+        let content_start = self.pos;
 
-        let mut comment = String::new();
-
-        while self.pos < self.source.len() {
-            let c = self.source[self.pos];
-            if c == '\n' {
-                break;
-            }
-            comment.push(c);
+        while self.peek_char(0).is_some_and(|c| c != '\n') {
             self.advance();
         }
 
-        Ok(TokenKind::Comment(comment))
+        Ok(TokenKind::Comment(self.slice(content_start..self.pos)))
     }
 
     /// Read a block comment
-    fn read_block_comment(&mut self) -> Result<TokenKind, LexError> {
+    fn read_block_comment(&mut self) -> Result<TokenKind<'a>, LexError> {
         self.advance(); // Skip '/'
         self.advance(); // Skip '*'
+        let content_start = self.pos;
 
-        let mut comment = String::new();
-
-        let mut found_closing_comment = false;
-
-        while self.pos < self.source.len() {
-            let c = self.source[self.pos];
-
+        while let Some(c) = self.peek_char(0) {
             if c == '*' && self.peek_char(1) == Some('/') {
+                let comment = self.slice(content_start..self.pos);
                 self.advance(); // Skip '*'
                 self.advance(); // Skip '/'
-                found_closing_comment = true;
-                break;
+                return Ok(TokenKind::Comment(comment));
             }
 
-            comment.push(c);
-            self.advance();
+            self.advance_tracking_lines();
         }
 
-        // Check if we reached the end without finding a closing comment
-        if !found_closing_comment {
-            return Err(LexError::UnterminatedComment);
-        }
-
-        Ok(TokenKind::Comment(comment))
+        // We reached the end without finding a closing comment
+        Err(LexError::UnterminatedComment {
+            span: self.error_span(),
+        })
     }
 
     /// Read an operator or symbol
-    fn read_operator(&mut self) -> Result<TokenKind, LexError> {
-        let c = self.source[self.pos];
+    fn read_operator(&mut self) -> Result<TokenKind<'a>, LexError> {
+        let c = self.peek_char(0).expect("operator at the current position");
+
+        if c == '.' && self.peek_char(1) == Some('.') && self.peek_char(2) == Some('.') {
+            self.advance();
+            self.advance();
+            self.advance();
+            return Ok(TokenKind::Ellipsis);
+        }
+
+        if c == '?' && self.peek_char(1) == Some('?') && self.peek_char(2) == Some('=') {
+            self.advance();
+            self.advance();
+            self.advance();
+            return Ok(TokenKind::NullishAssign);
+        }
+
+        if c == '*' && self.peek_char(1) == Some('*') && self.peek_char(2) == Some('=') {
+            self.advance();
+            self.advance();
+            self.advance();
+            return Ok(TokenKind::ExponentAssign);
+        }
 
         // Check for two-character operators
-        if self.pos + 1 < self.source.len() {
-            let next_c = self.source[self.pos + 1];
+        if let Some(next_c) = self.peek_char(1) {
             let two_char_op = format!("{}{}", c, next_c);
 
             match two_char_op.as_str() {
@@ -373,6 +773,26 @@ impl Lexer {
                     self.advance();
                     return Ok(TokenKind::MinusAssign);
                 }
+                "*=" => {
+                    self.advance();
+                    self.advance();
+                    return Ok(TokenKind::MultiplyAssign);
+                }
+                "/=" => {
+                    self.advance();
+                    self.advance();
+                    return Ok(TokenKind::DivideAssign);
+                }
+                "%=" => {
+                    self.advance();
+                    self.advance();
+                    return Ok(TokenKind::ModuloAssign);
+                }
+                "**" => {
+                    self.advance();
+                    self.advance();
+                    return Ok(TokenKind::Exponent);
+                }
                 "++" => {
                     self.advance();
                     self.advance();
@@ -388,6 +808,17 @@ impl Lexer {
                     self.advance();
                     return Ok(TokenKind::Arrow);
                 }
+                "=>" => {
+                    self.advance();
+                    self.advance();
+                    return Ok(TokenKind::FatArrow);
+                }
+                // `a?.5` is a conditional with a number, not optional chaining
+                "?." if !self.peek_char(2).is_some_and(|c| c.is_ascii_digit()) => {
+                    self.advance();
+                    self.advance();
+                    return Ok(TokenKind::QuestionDot);
+                }
                 // "&&" => {
                 //     self.advance();
                 //     self.advance();
@@ -398,16 +829,11 @@ impl Lexer {
                 //     self.advance();
                 //     return Ok(TokenKind::LogicalOr);
                 // }
-                // "=>" => {
-                //     self.advance();
-                //     self.advance();
-                //     return Ok(TokenKind::Arrow);
-                // }
-                // "??" => {
-                //     self.advance();
-                //     self.advance();
-                //     return Ok(TokenKind::NullishCoalescing);
-                // }
+                "??" => {
+                    self.advance();
+                    self.advance();
+                    return Ok(TokenKind::NullishCoalescing);
+                }
                 _ => {}
             }
         }
@@ -510,46 +936,205 @@ impl Lexer {
             //     self.advance();
             //     Ok(TokenKind::BitwiseXor)
             // }
-            _ => Err(LexError::UnexpectedCharacter(c)),
+            _ => {
+                self.advance();
+                Err(LexError::UnexpectedCharacter {
+                    character: c,
+                    span: self.error_span(),
+                })
+            }
         }
     }
 
     fn skip_whitespace(&mut self) {
-        while self.pos < self.source.len() {
-            let c = self.source[self.pos];
-            if c.is_whitespace() {
-                if c == '\n' {
-                    self.line += 1;
-                    self.column = 1;
-                } else {
-                    self.column += 1;
+        while self.peek_char(0).is_some_and(|c| c.is_whitespace()) {
+            self.advance_tracking_lines();
+        }
+    }
+
+    /// Read the whitespace, newlines and comments before a token onto `trivia`
+    fn read_leading_trivia(&mut self, trivia: &mut Vec<Trivia<'a>>) -> Result<(), LexError> {
+        while let Some(piece) = self.read_trivia_piece(false) {
+            trivia.push(piece?);
+        }
+        Ok(())
+    }
+
+    /// Read the whitespace and comments after a token up to the end of its line, the
+    /// newline leads the next token. A bad comment is left to be reported in its place
+    fn read_trailing_trivia(&mut self) -> Vec<Trivia<'a>> {
+        let mut trivia = Vec::new();
+        loop {
+            let (pos, line, column) = (self.pos, self.line, self.column);
+            match self.read_trivia_piece(true) {
+                Some(Ok(piece)) => trivia.push(piece),
+                Some(Err(_)) => {
+                    self.pos = pos;
+                    self.line = line;
+                    self.column = column;
+                    break;
                 }
-                self.advance();
-            } else {
-                break;
+                None => break,
             }
         }
+        trivia
+    }
+
+    /// Read one run of whitespace, newline or comment, `None` when a token comes next
+    fn read_trivia_piece(&mut self, trailing: bool) -> Option<Result<Trivia<'a>, LexError>> {
+        let c = self.peek_char(0)?;
+        let start_pos = self.pos;
+        self.token_start = self.current_position();
+
+        let kind = if c == '\n' {
+            if trailing {
+                return None;
+            }
+            self.advance_tracking_lines();
+            TriviaKind::Newline
+        } else if c.is_whitespace() {
+            while self
+                .peek_char(0)
+                .is_some_and(|c| c.is_whitespace() && c != '\n')
+            {
+                self.advance_tracking_lines();
+            }
+            TriviaKind::Whitespace
+        } else if c == '/' && self.peek_char(1) == Some('/') {
+            if let Err(error) = self.read_line_comment() {
+                return Some(Err(error));
+            }
+            TriviaKind::LineComment
+        } else if c == '/' && self.peek_char(1) == Some('*') {
+            if let Err(error) = self.read_block_comment() {
+                return Some(Err(error));
+            }
+            TriviaKind::BlockComment
+        } else {
+            return None;
+        };
+
+        Some(Ok(Trivia {
+            kind,
+            text: self.slice(start_pos..self.pos),
+            span: self.span_from(self.token_start),
+        }))
     }
 
     fn advance(&mut self) {
-        if self.pos < self.source.len() {
-            self.pos += 1;
+        if let Some(c) = self.peek_char(0) {
+            self.pos += c.len_utf8();
             self.column += 1;
         }
     }
 
-    fn peek_char(&self, offset: usize) -> Option<char> {
-        if self.pos + offset < self.source.len() {
-            Some(self.source[self.pos + offset])
+    /// Advance over a character that may be a line break, as found inside multi-line literals
+    fn advance_tracking_lines(&mut self) {
+        if self.peek_char(0) == Some('\n') {
+            self.pos += 1;
+            self.line += 1;
+            self.column = 1;
         } else {
-            None
+            self.advance();
+        }
+    }
+
+    /// Current position, including its byte offset
+    fn current_position(&self) -> Position {
+        Position::with_offset(self.line, self.column, self.base + self.pos)
+    }
+
+    /// Span from `start` up to the current position
+    fn span_from(&self, start: Position) -> Span {
+        Span::new(start, self.current_position()).with_file(self.file)
+    }
+
+    /// Span from the start of the current token up to the current position
+    fn error_span(&self) -> Span {
+        self.span_from(self.token_start)
+    }
+
+    /// Character `offset` characters ahead of the current one, reading more input if needed
+    fn peek_char(&mut self, offset: usize) -> Option<char> {
+        loop {
+            if let Some(c) = self.source[self.pos..].chars().nth(offset) {
+                return Some(c);
+            }
+            if !self.fill_buffer() {
+                return None;
+            }
         }
     }
 
-    fn update_positions(&mut self, _start_line: usize, _start_col: usize) {
-        // self.line = start_line;
-        // self.column = start_col;
-        // Position is already updated in next_token
+    /// Append the next line of the reader to the buffer, false once the input is exhausted
+    fn fill_buffer(&mut self) -> bool {
+        let Some(reader) = &mut self.reader else {
+            return false;
+        };
+
+        match reader.read_line(self.source.to_mut()) {
+            Ok(0) => {
+                self.reader = None;
+                false
+            }
+            Ok(_) => true,
+            Err(error) => {
+                self.read_error = Some(error);
+                self.reader = None;
+                false
+            }
+        }
+    }
+
+    /// Drop the text lexed so far from a reader's buffer, tokens already own their copy.
+    /// Waits until half the buffer was lexed so a long line is not shifted per token
+    fn discard_consumed(&mut self) {
+        if self.reader.is_some() && self.pos > 0 && self.pos * 2 >= self.source.len() {
+            self.source.to_mut().drain(..self.pos);
+            self.base += self.pos;
+            self.pos = 0;
+        }
+    }
+
+    /// Text of `range` in the source, borrowed unless it comes from a reader's buffer
+    fn slice(&self, range: Range<usize>) -> Cow<'a, str> {
+        match &self.source {
+            Cow::Borrowed(source) => Cow::Borrowed(&source[range]),
+            Cow::Owned(buffer) => Cow::Owned(buffer[range].to_string()),
+        }
+    }
+}
+
+impl fmt::Debug for Lexer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lexer")
+            .field("offset", &(self.base + self.pos))
+            .field("line", &self.line)
+            .field("column", &self.column)
+            .field("file", &self.file)
+            .field("streaming", &self.reader.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Yields every token up to, but not including, `Eof`.
+///
+/// A lexical error is yielded in place of the bad input and lexing carries on after it.
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Token<'a>, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.next_token() {
+            Ok(token) if token.kind == TokenKind::Eof => {
+                self.finished = true;
+                None
+            }
+            result => Some(result),
+        }
     }
 }
 
@@ -561,6 +1146,6 @@ mod tests {
     fn test_identifier() {
         let mut lexer = Lexer::new("hello world");
         let tokens = lexer.tokenize().unwrap();
-        assert_eq!(tokens[0].kind, TokenKind::Identifier("hello".to_string()));
+        assert_eq!(tokens[0].kind, TokenKind::Identifier("hello".into()));
     }
 }
@@ -1,11 +1,38 @@
-pub use crate::{error::LexError, lexer::Lexer, token::Token};
+pub use crate::{
+    error::LexError,
+    incremental::{TextEdit, TokenChange},
+    lexer::Lexer,
+    token::Token,
+};
 
 pub mod error;
+pub mod incremental;
 pub mod lexer;
 pub mod token;
 
 /// Tokenize source code into vec of tokens
-pub fn tokenize(source: &str) -> Result<Vec<Token>, LexError> {
+pub fn tokenize(source: &str) -> Result<Vec<Token<'_>>, LexError> {
     let mut lexer = Lexer::new(source);
     lexer.tokenize()
 }
+
+/// Tokenize source code, keeping whitespace and comments as trivia on the tokens
+pub fn tokenize_with_trivia(source: &str) -> Result<Vec<Token<'_>>, LexError> {
+    let mut lexer = Lexer::new(source).with_trivia();
+    lexer.tokenize()
+}
+
+/// Rebuild the source text from tokens produced with trivia
+pub fn tokens_to_source(tokens: &[Token]) -> String {
+    let mut source = String::new();
+    for token in tokens {
+        token.write_source(&mut source);
+    }
+    source
+}
+
+/// Tokenize source code, collecting every lexical error instead of stopping at the first one
+pub fn tokenize_with_errors(source: &str) -> (Vec<Token<'_>>, Vec<LexError>) {
+    let mut lexer = Lexer::new(source);
+    lexer.tokenize_with_errors()
+}
@@ -1,59 +1,43 @@
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Position {
-    pub line: usize,
-    pub column: usize,
-}
-
-impl Position {
-    pub fn new(line: usize, column: usize) -> Self {
-        Self { line, column }
-    }
-}
+use std::{borrow::Cow, fmt};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Span {
-    pub start: Position,
-    pub end: Position,
-}
+use serde::{Deserialize, Serialize};
 
-impl Span {
-    pub fn new(start: Position, end: Position) -> Self {
-        Self { start, end }
-    }
+use crate::error::LexError;
 
-    pub fn from_positions(
-        start_line: usize,
-        start_col: usize,
-        end_line: usize,
-        end_col: usize,
-    ) -> Self {
-        Self {
-            start: Position::new(start_line, start_col),
-            end: Position::new(end_line, end_col),
-        }
-    }
-}
+pub use lumi_span::{FileId, Position, Span};
 
-/// All supported token kinds
+/// All supported token kinds.
+///
+/// Text payloads borrow from the source where they can, escapes and normalized
+/// identifiers are the only ones that need an allocation.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum TokenKind {
+pub enum TokenKind<'a> {
     // Literals
-    Identifier(String),
-    Number(f64),
-    String(String),
+    Identifier(Cow<'a, str>),
+    Integer(i64),
+    Float(f64),
+    /// Arbitrary-precision integer written with an `n` suffix, kept as its decimal digits
+    BigInt(String),
+    String(Cow<'a, str>),
+    /// Leading text of an interpolated string, up to the first `${`
+    TemplateHead(Cow<'a, str>),
+    /// Text between two interpolations (`}` ... `${`)
+    TemplateMiddle(Cow<'a, str>),
+    /// Trailing text of an interpolated string, after the last `}`
+    TemplateTail(Cow<'a, str>),
     Boolean(bool),
     Null,
     Undefined,
 
     // Keywords
-    Keyword(String),
+    Keyword(Keyword),
 
     // Comments and whitespaces
-    Comment(String),
+    Comment(Cow<'a, str>),
     Whitespace,
     Eof,
+    /// Input the lexer could not tokenize, carrying the error message
+    Error(String),
 
     // Specifics
     LeftParen,
@@ -63,18 +47,31 @@ pub enum TokenKind {
     LeftBracket,
     RightBracket,
     Dot,
+    /// Spread `...`
+    Ellipsis,
     Semicolon,
     Comma,
     Colon,
     Question,
+    /// Optional chaining `?.`
+    QuestionDot,
     Exclamation,
 
     // Assignment
     Assign,
     PlusAssign,
     MinusAssign,
+    MultiplyAssign,
+    DivideAssign,
+    ModuloAssign,
+    /// Exponent assignment `**=`
+    ExponentAssign,
+    /// Null-coalescing assignment `??=`
+    NullishAssign,
     Arrow,
     // TODO: extend
+    /// Arrow function `=>`
+    FatArrow,
 
     // Comparison
     Equal,
@@ -88,6 +85,8 @@ pub enum TokenKind {
     // Logical operators
     LogicalAnd,
     LogicalOr,
+    /// Null-coalescing `??`
+    NullishCoalescing,
 
     // Increment/decrement operators
     Increment,
@@ -99,32 +98,205 @@ pub enum TokenKind {
     Star,
     Slash,
     Percent,
+    /// Exponent `**`
+    Exponent,
+}
+
+/// Words reserved by the grammar.
+///
+/// A word is only reserved once a construct uses it, until then it is a valid name.
+/// Words that only mean something in one position, like `to` and `step` in a for loop,
+/// `get`/`set` or type names, are lexed as identifiers and matched by the parser in context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Keyword {
+    Let,
+    Const,
+    Fn,
+    Return,
+    If,
+    Else,
+    For,
+    In,
+    While,
+    Do,
+    Loop,
+    Break,
+    Continue,
+    Print,
+    Class,
+    Extends,
+    Enum,
+    New,
+    This,
+    Super,
+    Try,
+    Catch,
+    Finally,
+    Throw,
+    Switch,
+    Case,
+    Default,
+    Import,
+    Export,
+}
+
+impl Keyword {
+    /// Look up the keyword spelled `word`, if it is reserved
+    pub fn from_word(word: &str) -> Option<Self> {
+        let keyword = match word {
+            "let" => Keyword::Let,
+            "const" => Keyword::Const,
+            "fn" => Keyword::Fn,
+            "return" => Keyword::Return,
+            "if" => Keyword::If,
+            "else" => Keyword::Else,
+            "for" => Keyword::For,
+            "in" => Keyword::In,
+            "while" => Keyword::While,
+            "do" => Keyword::Do,
+            "loop" => Keyword::Loop,
+            "break" => Keyword::Break,
+            "continue" => Keyword::Continue,
+            "print" => Keyword::Print,
+            "class" => Keyword::Class,
+            "extends" => Keyword::Extends,
+            "enum" => Keyword::Enum,
+            "new" => Keyword::New,
+            "this" => Keyword::This,
+            "super" => Keyword::Super,
+            "try" => Keyword::Try,
+            "catch" => Keyword::Catch,
+            "finally" => Keyword::Finally,
+            "throw" => Keyword::Throw,
+            "switch" => Keyword::Switch,
+            "case" => Keyword::Case,
+            "default" => Keyword::Default,
+            "import" => Keyword::Import,
+            "export" => Keyword::Export,
+            _ => return None,
+        };
+        Some(keyword)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Keyword::Let => "let",
+            Keyword::Const => "const",
+            Keyword::Fn => "fn",
+            Keyword::Return => "return",
+            Keyword::If => "if",
+            Keyword::Else => "else",
+            Keyword::For => "for",
+            Keyword::In => "in",
+            Keyword::While => "while",
+            Keyword::Do => "do",
+            Keyword::Loop => "loop",
+            Keyword::Break => "break",
+            Keyword::Continue => "continue",
+            Keyword::Print => "print",
+            Keyword::Class => "class",
+            Keyword::Extends => "extends",
+            Keyword::Enum => "enum",
+            Keyword::New => "new",
+            Keyword::This => "this",
+            Keyword::Super => "super",
+            Keyword::Try => "try",
+            Keyword::Catch => "catch",
+            Keyword::Finally => "finally",
+            Keyword::Throw => "throw",
+            Keyword::Switch => "switch",
+            Keyword::Case => "case",
+            Keyword::Default => "default",
+            Keyword::Import => "import",
+            Keyword::Export => "export",
+        }
+    }
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Kinds of source text that carry no meaning for the parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriviaKind {
+    /// Spaces, tabs and carriage returns
+    Whitespace,
+    Newline,
+    LineComment,
+    BlockComment,
+}
+
+/// A piece of trivia with its exact source text, comment delimiters included
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trivia<'a> {
+    pub kind: TriviaKind,
+    pub text: Cow<'a, str>,
+    pub span: Span,
 }
 
 /// A token with position information
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Token {
-    pub kind: TokenKind,
+pub struct Token<'a> {
+    pub kind: TokenKind<'a>,
     pub span: Span,
+    /// Trivia between the previous token and this one, only kept by a lexer built `with_trivia`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub leading_trivia: Vec<Trivia<'a>>,
+    /// Trivia after this token up to the end of its line, only kept by a lexer built `with_trivia`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trailing_trivia: Vec<Trivia<'a>>,
+    /// Exact source text of the token, only kept by a lexer built `with_trivia`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lexeme: Option<Cow<'a, str>>,
 }
 
-impl Token {
-    pub fn new(kind: TokenKind, span: Span) -> Self {
-        Self { kind, span }
+impl<'a> Token<'a> {
+    pub fn new(kind: TokenKind<'a>, span: Span) -> Self {
+        Self {
+            kind,
+            span,
+            leading_trivia: Vec::new(),
+            trailing_trivia: Vec::new(),
+            lexeme: None,
+        }
     }
 
     pub fn with_positions(
-        kind: TokenKind,
+        kind: TokenKind<'a>,
         start_line: usize,
         start_col: usize,
         end_line: usize,
         end_col: usize,
     ) -> Self {
-        Self {
+        Self::new(
             kind,
-            span: Span::from_positions(start_line, start_col, end_line, end_col),
+            Span::from_positions(start_line, start_col, end_line, end_col),
+        )
+    }
+
+    /// Token standing in for input that failed to lex
+    pub fn error(error: &LexError) -> Self {
+        Self::new(TokenKind::Error(error.to_string()), error.span().clone())
+    }
+
+    /// Append the token's source text, surrounded by its trivia, to `out`.
+    ///
+    /// Only tokens from a lexer built `with_trivia` reproduce the original source.
+    pub fn write_source(&self, out: &mut String) {
+        for trivia in &self.leading_trivia {
+            out.push_str(&trivia.text);
+        }
+        if let Some(lexeme) = &self.lexeme {
+            out.push_str(lexeme);
+        }
+        for trivia in &self.trailing_trivia {
+            out.push_str(&trivia.text);
         }
     }
+
     pub fn start(&self) -> Position {
         self.span.start
     }
@@ -144,8 +316,13 @@ impl Token {
     pub fn is_literal(&self) -> bool {
         matches!(
             self.kind,
-            TokenKind::Number(_)
+            TokenKind::Integer(_)
+                | TokenKind::Float(_)
+                | TokenKind::BigInt(_)
                 | TokenKind::String(_)
+                | TokenKind::TemplateHead(_)
+                | TokenKind::TemplateMiddle(_)
+                | TokenKind::TemplateTail(_)
                 | TokenKind::Boolean(_)
                 | TokenKind::Null
                 | TokenKind::Undefined
@@ -1,6 +1,6 @@
 use std::time::Instant;
 
-use lumi_lexer::tokenize;
+use lumi_lexer::{Lexer, TextEdit, tokenize};
 
 #[test]
 fn test_lexer_performance_simple() {
@@ -69,6 +69,41 @@ fn test_lexer_performance_large_source() {
     assert!(duration.as_millis() < 1000);
 }
 
+#[test]
+fn test_incremental_relex_performance_large_source() {
+    let mut source = String::new();
+    for i in 0..5000 {
+        source.push_str(&format!("let x{} -> {} + {};\n", i, i, i));
+    }
+
+    let start = Instant::now();
+    let mut tokens = tokenize(&source).unwrap();
+    let full_duration = start.elapsed();
+
+    // Change one number in the middle of the file
+    let offset = source.find("let x2500 -> 2500").unwrap() + "let x2500 -> ".len();
+    let edit = TextEdit::new(offset..offset + 4, "42");
+    let edited = edit.apply(&source);
+
+    let start = Instant::now();
+    let change = Lexer::relex(&edited, &mut tokens, &edit, false);
+    let incremental_duration = start.elapsed();
+
+    println!(
+        "Full lexing took: {:?}, incremental re-lex took: {:?} for {} tokens",
+        full_duration,
+        incremental_duration,
+        tokens.len()
+    );
+
+    // Only the edited token is re-lexed, and the result matches a full re-tokenize
+    assert!(change.inserted.len() <= 2);
+    assert_eq!(tokens, tokenize(&edited).unwrap());
+
+    // Re-lexing a small region should be much faster than lexing the whole file
+    assert!(incremental_duration < full_duration);
+}
+
 #[test]
 fn test_lexer_memory_usage() {
     let source = "let x: int -> 42;";
@@ -85,3 +120,22 @@ fn test_lexer_memory_usage() {
     // Should be reasonable memory usage (less than 1MB for small source)
     assert!(estimated_memory < 1_000_000);
 }
+
+#[test]
+fn test_reader_performance_long_line() {
+    // One line means one buffer fill, so tokens must not shift it one by one
+    let source = "x + ".repeat(100_000) + "x;";
+
+    let start = Instant::now();
+    let tokens = Lexer::from_reader(source.as_bytes()).tokenize().unwrap();
+    let duration = start.elapsed();
+
+    println!(
+        "Long line streaming took: {:?} for {} tokens",
+        duration,
+        tokens.len()
+    );
+
+    assert_eq!(tokens, tokenize(&source).unwrap());
+    assert!(duration.as_millis() < 1000);
+}
@@ -1,4 +1,8 @@
-use lumi_lexer::{error::LexError, tokenize};
+use lumi_lexer::{
+    error::LexError,
+    token::{Keyword, TokenKind},
+    tokenize, tokenize_with_errors,
+};
 
 #[test]
 fn test_unterminated_string() {
@@ -7,7 +11,7 @@ fn test_unterminated_string() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        LexError::UnterminatedString => {}
+        LexError::UnterminatedString { .. } => {}
         _ => panic!("Expected UnterminatedString error"),
     }
 }
@@ -19,7 +23,190 @@ fn test_unterminated_comment() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        LexError::UnterminatedComment => {}
+        LexError::UnterminatedComment { .. } => {}
         _ => panic!("Expected unterminatedComment error"),
     }
 }
+
+#[test]
+fn test_unterminated_interpolation() {
+    let source = "\"total: ${x + 1";
+    let result = tokenize(source);
+
+    assert!(result.is_err());
+    match result.unwrap_err() {
+        LexError::UnterminatedString { .. } => {}
+        _ => panic!("Expected UnterminatedString error"),
+    }
+}
+
+#[test]
+fn test_invalid_escape() {
+    let source = "let s = \"bad \\q escape\";";
+    let result = tokenize(source);
+
+    match result.unwrap_err() {
+        LexError::InvalidEscape { sequence, span } => {
+            assert_eq!(sequence, "\\q");
+            assert_eq!(span.start.line, 1);
+            assert_eq!(span.end.column, span.start.column + 2);
+        }
+        e => panic!("Expected InvalidEscape error, got {:?}", e),
+    }
+}
+
+#[test]
+fn test_invalid_hex_escape() {
+    let source = "\"\\xZ1\"";
+    let result = tokenize(source);
+
+    match result.unwrap_err() {
+        LexError::InvalidEscape { sequence, .. } => assert_eq!(sequence, "\\xZ1"),
+        e => panic!("Expected InvalidEscape error, got {:?}", e),
+    }
+}
+
+#[test]
+fn test_invalid_code_point() {
+    let source = "\"\\u{D800}\"";
+    let result = tokenize(source);
+
+    match result.unwrap_err() {
+        LexError::InvalidCodePoint { value, span } => {
+            assert_eq!(value, "D800");
+            assert_eq!(span.start.column, 2);
+        }
+        e => panic!("Expected InvalidCodePoint error, got {:?}", e),
+    }
+}
+
+#[test]
+fn test_unterminated_raw_string() {
+    let source = "r\"no end";
+    let result = tokenize(source);
+
+    assert!(matches!(
+        result.unwrap_err(),
+        LexError::UnterminatedString { .. }
+    ));
+}
+
+#[test]
+fn test_unexpected_character_span() {
+    let source = "let x = 1;\nlet y = @;";
+    let result = tokenize(source);
+
+    match result.unwrap_err() {
+        LexError::UnexpectedCharacter { character, span } => {
+            assert_eq!(character, '@');
+            assert_eq!((span.start.line, span.start.column), (2, 9));
+            assert_eq!((span.end.line, span.end.column), (2, 10));
+        }
+        e => panic!("Expected UnexpectedCharacter error, got {:?}", e),
+    }
+
+    let source = "let x -> 1e+;";
+    let error = tokenize(source).unwrap_err();
+    assert_eq!(error.span().start.column, 10);
+}
+
+#[test]
+fn test_unterminated_interpolation_span_starts_at_string() {
+    let source = "x = \"a ${b";
+    let (tokens, errors) = tokenize_with_errors(source);
+
+    assert!(matches!(errors[0], LexError::UnterminatedString { .. }));
+    assert_eq!(tokens[2].kind, TokenKind::TemplateHead("a ".into()));
+    assert_eq!(errors[0].span().start, tokens[2].span.start);
+}
+
+#[test]
+fn test_tokenize_with_errors_collects_all_errors() {
+    let source = "let a = @;\nlet b = \"bad \\q\";\nlet c = 3;";
+    let (tokens, errors) = tokenize_with_errors(source);
+
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        errors[0],
+        LexError::UnexpectedCharacter { character: '@', .. }
+    ));
+    assert!(matches!(errors[1], LexError::InvalidEscape { .. }));
+
+    let error_tokens: Vec<_> = tokens
+        .iter()
+        .filter(|t| matches!(t.kind, TokenKind::Error(_)))
+        .collect();
+    assert_eq!(error_tokens.len(), 2);
+    assert_eq!(&error_tokens[0].span, errors[0].span());
+
+    // Lexing continued after both errors
+    let tail: Vec<&TokenKind> = tokens.iter().rev().take(6).map(|t| &t.kind).collect();
+    assert_eq!(
+        tail,
+        vec![
+            &TokenKind::Eof,
+            &TokenKind::Semicolon,
+            &TokenKind::Integer(3),
+            &TokenKind::Assign,
+            &TokenKind::Identifier("c".into()),
+            &TokenKind::Keyword(Keyword::Let),
+        ]
+    );
+}
+
+#[test]
+fn test_tokenize_with_errors_without_errors() {
+    let source = "let a = 1;";
+    let (tokens, errors) = tokenize_with_errors(source);
+
+    assert!(errors.is_empty());
+    assert_eq!(tokens, tokenize(source).unwrap());
+}
+
+#[test]
+fn test_invalid_number_literals() {
+    let invalid = [
+        "1e+", "1..2", "1.2.3", "1_", "1__0", "0x", "0x_1", "0b102", "12px", "1.5n", "1_.5",
+    ];
+
+    for source in invalid {
+        match tokenize(source) {
+            Err(LexError::InvalidNumber { value, span }) => {
+                assert_eq!(value, source);
+                assert_eq!(span.range(), 0..source.len());
+            }
+            other => panic!("Expected invalid number for {:?}, got {:?}", source, other),
+        }
+    }
+}
+
+#[test]
+fn test_integer_overflow() {
+    let source = "let x -> 9223372036854775808;";
+    let err = tokenize(source).unwrap_err();
+
+    match err {
+        LexError::IntegerOverflow { value, span } => {
+            assert_eq!(value, "9223372036854775808");
+            assert_eq!(span.range(), 9..28);
+        }
+        _ => panic!("Expected IntegerOverflow error"),
+    }
+    assert!(tokenize("9223372036854775807").is_ok());
+}
+
+#[test]
+fn test_non_identifier_unicode_characters() {
+    let (tokens, errors) = tokenize_with_errors("a😀 b");
+
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        LexError::UnexpectedCharacter {
+            character: '😀',
+            ..
+        }
+    ));
+    assert_eq!(tokens[0].kind, TokenKind::Identifier("a".into()));
+    assert_eq!(tokens[2].kind, TokenKind::Identifier("b".into()));
+}
@@ -0,0 +1,172 @@
+use lumi_lexer::{Lexer, TextEdit, Token, token::TokenKind, tokenize};
+
+const SOURCE: &str = "fn add(a, b) {\n    a + b; // sum\n}\nlet s -> \"total: ${add(1, 2)} of ${ {} }\";\n/* block\n comment */ print s;\nlet é -> 0xFF;\n";
+
+/// Apply an edit incrementally and check the result against a full re-tokenize
+fn check_edit(source: &str, edit: TextEdit, preserve_trivia: bool) {
+    let mut tokens = lex(source, preserve_trivia);
+    let edited = edit.apply(source);
+
+    let change = Lexer::relex(&edited, &mut tokens, &edit, preserve_trivia);
+
+    assert_eq!(
+        tokens,
+        lex(&edited, preserve_trivia),
+        "edit {:?} on {:?} (change {:?})",
+        edit,
+        source,
+        change
+    );
+}
+
+fn lex(source: &str, preserve_trivia: bool) -> Vec<Token<'_>> {
+    let mut lexer = Lexer::new(source);
+    if preserve_trivia {
+        lexer = lexer.with_trivia();
+    }
+    lexer.tokenize_with_errors().0
+}
+
+#[test]
+fn test_relex_replaces_only_changed_tokens() {
+    let source = "let a -> 1;\nlet b -> 2;\nlet c -> 3;";
+    let mut tokens = tokenize(source).unwrap();
+    let edit = TextEdit::new(21..22, "42");
+    let edited = edit.apply(source);
+
+    let change = Lexer::relex(&edited, &mut tokens, &edit, false);
+
+    // The arrow before the number is lexed again, in case the edit changed it
+    assert_eq!(change.removed, 7..9);
+    assert_eq!(change.inserted, 7..9);
+    assert!(change.errors.is_empty());
+    assert_eq!(tokens[8].kind, TokenKind::Integer(42));
+    assert_eq!(tokens, tokenize(&edited).unwrap());
+}
+
+#[test]
+fn test_relex_shifts_following_lines() {
+    let source = "let a -> 1;\nlet b -> 2;";
+    let mut tokens = tokenize(source).unwrap();
+    let edit = TextEdit::insert(11, "\nprint a;");
+    let edited = edit.apply(source);
+
+    let change = Lexer::relex(&edited, &mut tokens, &edit, false);
+
+    assert_eq!(change.inserted.len(), change.removed.len() + 3);
+    let b = tokens
+        .iter()
+        .find(|t| t.kind == TokenKind::Identifier("b".into()))
+        .unwrap();
+    assert_eq!(b.span.start.line, 3);
+    assert_eq!(&edited[b.span.range()], "b");
+}
+
+#[test]
+fn test_relex_merges_tokens_at_edit_boundary() {
+    check_edit("a < b", TextEdit::insert(3, "="), false);
+    check_edit("ab c", TextEdit::delete(2..3), false);
+    check_edit("x / y", TextEdit::insert(3, "/"), false);
+    check_edit("r 'raw'", TextEdit::delete(1..2), false);
+}
+
+#[test]
+fn test_relex_inside_interpolation() {
+    check_edit(SOURCE, TextEdit::new(59..60, "10"), false);
+    check_edit(SOURCE, TextEdit::insert(55, "}"), false);
+    check_edit(SOURCE, TextEdit::delete(47..49), false);
+}
+
+#[test]
+fn test_relex_reports_new_errors() {
+    let source = "let a -> 1;\nlet b -> 2;";
+    let mut tokens = tokenize(source).unwrap();
+    let edit = TextEdit::insert(21, "@");
+    let edited = edit.apply(source);
+
+    let change = Lexer::relex(&edited, &mut tokens, &edit, false);
+
+    assert_eq!(change.errors.len(), 1);
+    assert_eq!(tokens, lex(&edited, false));
+}
+
+#[test]
+fn test_relex_matches_full_tokenize_for_every_edit() {
+    let replacements = [
+        "", "x", "1", " ", "\n", "\"", "${", "}", "//", "/*", "*/", "é", "==", "\\",
+    ];
+
+    for (i, replacement) in replacements.iter().enumerate() {
+        let boundaries: Vec<usize> = (0..=SOURCE.len())
+            .filter(|o| SOURCE.is_char_boundary(*o))
+            .collect();
+        for (j, &start) in boundaries.iter().enumerate() {
+            // Vary the length of the replaced range a little
+            let end = boundaries[(j + (i + j) % 3).min(boundaries.len() - 1)];
+            let edit = TextEdit::new(start..end, replacement);
+            check_edit(SOURCE, edit.clone(), false);
+            check_edit(SOURCE, edit, true);
+        }
+    }
+}
+
+#[test]
+fn test_relex_token_before_edit_sees_new_lookahead() {
+    check_edit("f(1.é)", TextEdit::new(4..6, "1"), false);
+    check_edit("f(1.é)", TextEdit::new(4..6, "1"), true);
+    check_edit("x -> 1 .5", TextEdit::delete(6..7), false);
+}
+
+#[test]
+fn test_relex_keeps_trivia_after_leading_error() {
+    check_edit(
+        "@ let a -> 1; // one\nlet b -> 2;",
+        TextEdit::new(11..12, "3"),
+        true,
+    );
+}
+
+#[test]
+fn test_relex_moves_error_positions() {
+    let source = "let a -> 1;\nlet b -> @;";
+    let mut tokens = lex(source, false);
+    let edit = TextEdit::insert(0, "\n\n");
+    let edited = edit.apply(source);
+
+    Lexer::relex(&edited, &mut tokens, &edit, false);
+
+    assert_eq!(tokens, lex(&edited, false));
+    assert!(tokens.iter().any(
+        |t| matches!(&t.kind, TokenKind::Error(message) if message.contains("at line 4, column 10"))
+    ));
+}
+
+#[test]
+fn test_relex_matches_full_tokenize_for_random_edits() {
+    let pieces = [
+        "let", " ", "\n", "x", "é", "1", "0x1F", ".", "5", "e", "+", "->", "=", "<", "/", "//",
+        "/*", "*/", "\"", "'", "${", "}", "{", "r", "_", "@", "\\", ";", "\t",
+    ];
+    // Xorshift, so failures can be reproduced
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    let mut next = |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % bound as u64) as usize
+    };
+
+    for _ in 0..2000 {
+        let source: String = (0..next(24)).map(|_| pieces[next(pieces.len())]).collect();
+        let boundaries: Vec<usize> = (0..=source.len())
+            .filter(|o| source.is_char_boundary(*o))
+            .collect();
+        let start = next(boundaries.len());
+        let end = (start + next(4)).min(boundaries.len() - 1);
+        let replacement: String = (0..next(3)).map(|_| pieces[next(pieces.len())]).collect();
+        let edit = TextEdit::new(boundaries[start]..boundaries[end], &replacement);
+
+        check_edit(&source, edit.clone(), false);
+        check_edit(&source, edit, true);
+    }
+}
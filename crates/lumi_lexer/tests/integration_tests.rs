@@ -1,4 +1,8 @@
-use lumi_lexer::{token::TokenKind, tokenize};
+use lumi_lexer::{
+    token::{Keyword, TokenKind},
+    tokenize,
+};
+use lumi_span::{FileId, SourceFile};
 
 #[test]
 fn test_simple_program() {
@@ -13,13 +17,13 @@ fn test_simple_program() {
     // Should contain the expected tokens
     let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
 
-    assert!(token_kinds.contains(&&TokenKind::Keyword("let".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Keyword("str".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Identifier("x".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Identifier("y".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Identifier("z".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Number(42.0)));
-    assert!(token_kinds.contains(&&TokenKind::String("hello".to_string())));
+    assert!(token_kinds.contains(&&TokenKind::Keyword(Keyword::Let)));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("str".into())));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("x".into())));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("y".into())));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("z".into())));
+    assert!(token_kinds.contains(&&TokenKind::Integer(42)));
+    assert!(token_kinds.contains(&&TokenKind::String("hello".into())));
     assert!(token_kinds.contains(&&TokenKind::Plus));
     assert!(token_kinds.contains(&&TokenKind::Arrow));
     assert!(token_kinds.contains(&&TokenKind::Semicolon));
@@ -36,15 +40,15 @@ fn test_function_declaration() {
     let tokens = tokenize(source).unwrap();
     let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
 
-    assert!(token_kinds.contains(&&TokenKind::Keyword("fn".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Identifier("add".to_string())));
+    assert!(token_kinds.contains(&&TokenKind::Keyword(Keyword::Fn)));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("add".into())));
     assert!(token_kinds.contains(&&TokenKind::LeftParen));
-    assert!(token_kinds.contains(&&TokenKind::Identifier("a".to_string())));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("a".into())));
     assert!(token_kinds.contains(&&TokenKind::Comma));
-    assert!(token_kinds.contains(&&TokenKind::Identifier("b".to_string())));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("b".into())));
     assert!(token_kinds.contains(&&TokenKind::RightParen));
     assert!(token_kinds.contains(&&TokenKind::LeftBrace));
-    assert!(token_kinds.contains(&&TokenKind::Keyword("return".to_string())));
+    assert!(token_kinds.contains(&&TokenKind::Keyword(Keyword::Return)));
     assert!(token_kinds.contains(&&TokenKind::Plus));
     assert!(token_kinds.contains(&&TokenKind::RightBrace));
 }
@@ -61,14 +65,14 @@ fn test_if_statement() {
     let tokens = tokenize(source).unwrap();
     let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
 
-    assert!(token_kinds.contains(&&TokenKind::Keyword("if".to_string())));
+    assert!(token_kinds.contains(&&TokenKind::Keyword(Keyword::If)));
     assert!(token_kinds.contains(&&TokenKind::LeftParen));
-    assert!(token_kinds.contains(&&TokenKind::Identifier("x".to_string())));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("x".into())));
     assert!(token_kinds.contains(&&TokenKind::GreaterThan));
-    assert!(token_kinds.contains(&&TokenKind::Number(0.0)));
+    assert!(token_kinds.contains(&&TokenKind::Integer(0)));
     assert!(token_kinds.contains(&&TokenKind::RightParen));
     assert!(token_kinds.contains(&&TokenKind::LeftBrace));
-    assert!(token_kinds.contains(&&TokenKind::Keyword("else".to_string())));
+    assert!(token_kinds.contains(&&TokenKind::Keyword(Keyword::Else)));
     assert!(token_kinds.contains(&&TokenKind::RightBrace));
 }
 
@@ -83,14 +87,14 @@ fn test_for_loop() {
     let tokens = tokenize(source).unwrap();
     let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
 
-    assert!(token_kinds.contains(&&TokenKind::Keyword("for".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Keyword("to".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Keyword("step".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Identifier("i".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Number(1.0)));
+    assert!(token_kinds.contains(&&TokenKind::Keyword(Keyword::For)));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("to".into())));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("step".into())));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("i".into())));
+    assert!(token_kinds.contains(&&TokenKind::Integer(1)));
     assert!(token_kinds.contains(&&TokenKind::Semicolon));
     assert!(token_kinds.contains(&&TokenKind::Arrow));
-    assert!(token_kinds.contains(&&TokenKind::Number(5.0)));
+    assert!(token_kinds.contains(&&TokenKind::Integer(5)));
     assert!(token_kinds.contains(&&TokenKind::Semicolon));
     assert!(token_kinds.contains(&&TokenKind::LeftBrace));
     assert!(token_kinds.contains(&&TokenKind::RightBrace));
@@ -105,19 +109,31 @@ fn test_array_literal() {
     let tokens = tokenize(source).unwrap();
     let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
 
-    assert!(token_kinds.contains(&&TokenKind::Keyword("let".to_string())));
-    assert!(token_kinds.contains(&&TokenKind::Identifier("arr".to_string())));
+    assert!(token_kinds.contains(&&TokenKind::Keyword(Keyword::Let)));
+    assert!(token_kinds.contains(&&TokenKind::Identifier("arr".into())));
     assert!(token_kinds.contains(&&TokenKind::Arrow));
     assert!(token_kinds.contains(&&TokenKind::LeftBracket));
-    assert!(token_kinds.contains(&&TokenKind::Number(1.0)));
+    assert!(token_kinds.contains(&&TokenKind::Integer(1)));
     assert!(token_kinds.contains(&&TokenKind::Comma));
-    assert!(token_kinds.contains(&&TokenKind::Number(2.0)));
+    assert!(token_kinds.contains(&&TokenKind::Integer(2)));
     assert!(token_kinds.contains(&&TokenKind::Comma));
-    assert!(token_kinds.contains(&&TokenKind::Number(3.0)));
+    assert!(token_kinds.contains(&&TokenKind::Integer(3)));
     assert!(token_kinds.contains(&&TokenKind::Comma));
-    assert!(token_kinds.contains(&&TokenKind::String("hello".to_string())));
+    assert!(token_kinds.contains(&&TokenKind::String("hello".into())));
     assert!(token_kinds.contains(&&TokenKind::Comma));
     assert!(token_kinds.contains(&&TokenKind::Boolean(true)));
     assert!(token_kinds.contains(&&TokenKind::RightBracket));
     assert!(token_kinds.contains(&&TokenKind::Semicolon));
 }
+
+#[test]
+fn test_token_positions_match_source_file() {
+    let source =
+        "let café -> 1e3;\n\t/* two\n lines */ print \"\"\"a\nb\"\"\";\r\n  x -> \"é${y}\";\n";
+    let file = SourceFile::new(FileId::default(), "main.lumi", source);
+
+    for token in tokenize(source).unwrap() {
+        assert_eq!(file.position(token.span.start.offset), token.span.start);
+        assert_eq!(file.position(token.span.end.offset), token.span.end);
+    }
+}
@@ -1,4 +1,8 @@
-use lumi_lexer::{Lexer, token::TokenKind, tokenize};
+use lumi_lexer::{
+    Lexer,
+    token::{FileId, Keyword, TokenKind},
+    tokenize,
+};
 
 #[test]
 fn test_lexer_creation() {
@@ -17,8 +21,8 @@ fn test_identifier_tokenization() {
     let tokens = tokenize(source).unwrap();
 
     assert_eq!(tokens.len(), 3); // identifier + identifier + EOF
-    assert_eq!(tokens[0].kind, TokenKind::Identifier("hello".to_string()));
-    assert_eq!(tokens[1].kind, TokenKind::Identifier("world".to_string()));
+    assert_eq!(tokens[0].kind, TokenKind::Identifier("hello".into()));
+    assert_eq!(tokens[1].kind, TokenKind::Identifier("world".into()));
     assert_eq!(tokens[2].kind, TokenKind::Eof);
 }
 
@@ -28,7 +32,7 @@ fn test_string_tokenization() {
     let tokens = tokenize(source).unwrap();
 
     assert_eq!(tokens.len(), 2); // string + EOF
-    assert_eq!(tokens[0].kind, TokenKind::String("hello world".to_string()));
+    assert_eq!(tokens[0].kind, TokenKind::String("hello world".into()));
     assert_eq!(tokens[1].kind, TokenKind::Eof);
 }
 
@@ -38,7 +42,7 @@ fn test_number_tokenization() {
     let tokens = tokenize(source).unwrap();
 
     assert_eq!(tokens.len(), 2); // number + EOF
-    assert_eq!(tokens[0].kind, TokenKind::Number(42.0));
+    assert_eq!(tokens[0].kind, TokenKind::Integer(42));
     assert_eq!(tokens[1].kind, TokenKind::Eof);
 }
 
@@ -64,13 +68,7 @@ fn test_keyword_tokenization() {
         assert_eq!(tokens.len(), 2); // keyword + EOF
 
         match &tokens[0].kind {
-            TokenKind::Keyword(k) => {
-                if k == "this" || k == "super" {
-                    assert_eq!(k, keyword);
-                } else {
-                    assert_eq!(k, keyword);
-                }
-            }
+            TokenKind::Keyword(k) => assert_eq!(k.as_str(), keyword),
             TokenKind::Boolean(true) => assert_eq!(keyword, "true"),
             TokenKind::Boolean(false) => assert_eq!(keyword, "false"),
             TokenKind::Null => assert_eq!(keyword, "null"),
@@ -111,7 +109,7 @@ fn test_comment_tokenization() {
     assert_eq!(tokens.len(), 2); // comment + EOF
     assert_eq!(
         tokens[0].kind,
-        TokenKind::Comment(" this is a comment".to_string())
+        TokenKind::Comment(" this is a comment".into())
     );
 
     // Block comment
@@ -121,7 +119,7 @@ fn test_comment_tokenization() {
     assert_eq!(tokens.len(), 2); // comment + EOF
     assert_eq!(
         tokens[0].kind,
-        TokenKind::Comment(" this is a block comment ".to_string())
+        TokenKind::Comment(" this is a block comment ".into())
     );
 }
 
@@ -131,12 +129,12 @@ fn test_variable_declaration() {
     let tokens = tokenize(source).unwrap();
 
     assert_eq!(tokens.len(), 8); // let + identifier + colon + identifier + assign + number + semicolon + EOF
-    assert_eq!(tokens[0].kind, TokenKind::Keyword("let".to_string()));
-    assert_eq!(tokens[1].kind, TokenKind::Identifier("x".to_string()));
+    assert_eq!(tokens[0].kind, TokenKind::Keyword(Keyword::Let));
+    assert_eq!(tokens[1].kind, TokenKind::Identifier("x".into()));
     assert_eq!(tokens[2].kind, TokenKind::Colon);
-    assert_eq!(tokens[3].kind, TokenKind::Keyword("int".to_string()));
+    assert_eq!(tokens[3].kind, TokenKind::Identifier("int".into()));
     assert_eq!(tokens[4].kind, TokenKind::Arrow);
-    assert_eq!(tokens[5].kind, TokenKind::Number(42.0));
+    assert_eq!(tokens[5].kind, TokenKind::Integer(42));
 }
 
 #[test]
@@ -145,15 +143,15 @@ fn test_plus_assign() {
     let tokens = tokenize(source).unwrap();
 
     assert_eq!(tokens.len(), 12);
-    assert_eq!(tokens[0].kind, TokenKind::Keyword("let".to_string()));
-    assert_eq!(tokens[1].kind, TokenKind::Identifier("x".to_string()));
+    assert_eq!(tokens[0].kind, TokenKind::Keyword(Keyword::Let));
+    assert_eq!(tokens[1].kind, TokenKind::Identifier("x".into()));
     assert_eq!(tokens[2].kind, TokenKind::Colon);
-    assert_eq!(tokens[3].kind, TokenKind::Keyword("int".to_string()));
+    assert_eq!(tokens[3].kind, TokenKind::Identifier("int".into()));
     assert_eq!(tokens[4].kind, TokenKind::Arrow);
-    assert_eq!(tokens[5].kind, TokenKind::Number(42.0));
-    assert_eq!(tokens[7].kind, TokenKind::Identifier("x".to_string()));
+    assert_eq!(tokens[5].kind, TokenKind::Integer(42));
+    assert_eq!(tokens[7].kind, TokenKind::Identifier("x".into()));
     assert_eq!(tokens[8].kind, TokenKind::PlusAssign);
-    assert_eq!(tokens[9].kind, TokenKind::Number(1.0));
+    assert_eq!(tokens[9].kind, TokenKind::Integer(1));
 }
 
 #[test]
@@ -162,13 +160,348 @@ fn test_minus_assign() {
     let tokens = tokenize(source).unwrap();
 
     assert_eq!(tokens.len(), 12);
-    assert_eq!(tokens[0].kind, TokenKind::Keyword("let".to_string()));
-    assert_eq!(tokens[1].kind, TokenKind::Identifier("x".to_string()));
+    assert_eq!(tokens[0].kind, TokenKind::Keyword(Keyword::Let));
+    assert_eq!(tokens[1].kind, TokenKind::Identifier("x".into()));
     assert_eq!(tokens[2].kind, TokenKind::Colon);
-    assert_eq!(tokens[3].kind, TokenKind::Keyword("int".to_string()));
+    assert_eq!(tokens[3].kind, TokenKind::Identifier("int".into()));
     assert_eq!(tokens[4].kind, TokenKind::Arrow);
-    assert_eq!(tokens[5].kind, TokenKind::Number(42.0));
-    assert_eq!(tokens[7].kind, TokenKind::Identifier("x".to_string()));
+    assert_eq!(tokens[5].kind, TokenKind::Integer(42));
+    assert_eq!(tokens[7].kind, TokenKind::Identifier("x".into()));
     assert_eq!(tokens[8].kind, TokenKind::MinusAssign);
-    assert_eq!(tokens[9].kind, TokenKind::Number(1.0));
+    assert_eq!(tokens[9].kind, TokenKind::Integer(1));
+}
+
+#[test]
+fn test_interpolated_string_tokenization() {
+    let source = r#""count: ${x + 1} items, ${ {} } \${literal}""#;
+    let tokens = tokenize(source).unwrap();
+    let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        token_kinds,
+        vec![
+            &TokenKind::TemplateHead("count: ".into()),
+            &TokenKind::Identifier("x".into()),
+            &TokenKind::Plus,
+            &TokenKind::Integer(1),
+            &TokenKind::TemplateMiddle(" items, ".into()),
+            &TokenKind::LeftBrace,
+            &TokenKind::RightBrace,
+            &TokenKind::TemplateTail(" ${literal}".into()),
+            &TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_string_escape_sequences() {
+    let source = "\"a\\tb\\0\\x41\\u{1F600}\\u{e9}\"";
+    let tokens = tokenize(source).unwrap();
+
+    assert_eq!(
+        tokens[0].kind,
+        TokenKind::String("a\tb\0A\u{1F600}\u{e9}".into())
+    );
+}
+
+#[test]
+fn test_string_line_continuation() {
+    let source = "\"hello \\\n      world\"";
+    let tokens = tokenize(source).unwrap();
+
+    assert_eq!(tokens[0].kind, TokenKind::String("hello world".into()));
+}
+
+#[test]
+fn test_raw_string() {
+    let source = "r\"C:\\path\\n ${x}\nnext line\" 1";
+    let tokens = tokenize(source).unwrap();
+
+    assert_eq!(
+        tokens[0].kind,
+        TokenKind::String("C:\\path\\n ${x}\nnext line".into())
+    );
+    assert_eq!(tokens[1].kind, TokenKind::Integer(1));
+    assert_eq!(tokens[1].span.start.line, 2);
+}
+
+#[test]
+fn test_triple_quoted_string() {
+    let source = "\"\"\"say \"hi\"\n${name}!\"\"\"";
+    let tokens = tokenize(source).unwrap();
+    let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        token_kinds,
+        vec![
+            &TokenKind::TemplateHead("say \"hi\"\n".into()),
+            &TokenKind::Identifier("name".into()),
+            &TokenKind::TemplateTail("!".into()),
+            &TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_token_byte_offsets() {
+    let source = "let é -> \"ü\";\nx";
+    let tokens = tokenize(source).unwrap();
+
+    for token in &tokens[..tokens.len() - 1] {
+        let text = &source[token.span.range()];
+        match &token.kind {
+            TokenKind::Identifier(name) => assert_eq!(text, name),
+            TokenKind::String(s) => assert_eq!(text, format!("\"{}\"", s)),
+            _ => assert!(!text.is_empty()),
+        }
+    }
+    assert_eq!(tokens[5].span.start.offset, source.len() - 1);
+    assert_eq!(tokens[6].span.start.offset, source.len());
+}
+
+#[test]
+fn test_spans_carry_file_id() {
+    let mut lexer = Lexer::new("let x;").with_file(FileId(3));
+    let tokens = lexer.tokenize().unwrap();
+
+    assert!(tokens.iter().all(|t| t.span.file == FileId(3)));
+}
+
+#[test]
+fn test_integer_and_float_tokenization() {
+    let source = "42 4.2 1e3 2.5E-2 0 007";
+    let tokens = tokenize(source).unwrap();
+    let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        token_kinds,
+        vec![
+            &TokenKind::Integer(42),
+            &TokenKind::Float(4.2),
+            &TokenKind::Float(1000.0),
+            &TokenKind::Float(0.025),
+            &TokenKind::Integer(0),
+            &TokenKind::Integer(7),
+            &TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_number_separators_and_radix() {
+    let source = "1_000_000 0xFF_FF 0b1010 0o17 3_000.000_5";
+    let tokens = tokenize(source).unwrap();
+    let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        token_kinds,
+        vec![
+            &TokenKind::Integer(1_000_000),
+            &TokenKind::Integer(0xFFFF),
+            &TokenKind::Integer(10),
+            &TokenKind::Integer(15),
+            &TokenKind::Float(3000.0005),
+            &TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_big_integer_tokenization() {
+    let source = "123n 9007199254740993n 0xFFFF_FFFF_FFFF_FFFF_FFFFn";
+    let tokens = tokenize(source).unwrap();
+
+    assert_eq!(tokens[0].kind, TokenKind::BigInt("123".to_string()));
+    assert_eq!(
+        tokens[1].kind,
+        TokenKind::BigInt("9007199254740993".to_string())
+    );
+    assert_eq!(
+        tokens[2].kind,
+        TokenKind::BigInt("1208925819614629174706175".to_string())
+    );
+}
+
+#[test]
+fn test_number_followed_by_member_access() {
+    let tokens = tokenize("1.foo").unwrap();
+    let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        token_kinds,
+        vec![
+            &TokenKind::Integer(1),
+            &TokenKind::Dot,
+            &TokenKind::Identifier("foo".into()),
+            &TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_optional_chaining_operator() {
+    let tokens = tokenize("a?.b c?.5").unwrap();
+    let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        token_kinds,
+        vec![
+            &TokenKind::Identifier("a".into()),
+            &TokenKind::QuestionDot,
+            &TokenKind::Identifier("b".into()),
+            &TokenKind::Identifier("c".into()),
+            &TokenKind::Question,
+            &TokenKind::Dot,
+            &TokenKind::Integer(5),
+            &TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_spread_operator() {
+    let tokens = tokenize("[...rest]").unwrap();
+    let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        token_kinds,
+        vec![
+            &TokenKind::LeftBracket,
+            &TokenKind::Ellipsis,
+            &TokenKind::Identifier("rest".into()),
+            &TokenKind::RightBracket,
+            &TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_fat_arrow() {
+    let tokens = tokenize("(a) => a == 1").unwrap();
+    let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        token_kinds,
+        vec![
+            &TokenKind::LeftParen,
+            &TokenKind::Identifier("a".into()),
+            &TokenKind::RightParen,
+            &TokenKind::FatArrow,
+            &TokenKind::Identifier("a".into()),
+            &TokenKind::Equal,
+            &TokenKind::Integer(1),
+            &TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_unicode_identifiers() {
+    let tokens = tokenize("_private $jq π café 変数 x1_$").unwrap();
+    let names: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        names,
+        vec![
+            &TokenKind::Identifier("_private".into()),
+            &TokenKind::Identifier("$jq".into()),
+            &TokenKind::Identifier("π".into()),
+            &TokenKind::Identifier("café".into()),
+            &TokenKind::Identifier("変数".into()),
+            &TokenKind::Identifier("x1_$".into()),
+            &TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_identifiers_are_nfc_normalized() {
+    // "e" followed by a combining acute accent is the decomposed form of "é"
+    let composed = tokenize("caf\u{e9}").unwrap();
+    let decomposed = tokenize("cafe\u{301}").unwrap();
+
+    assert_eq!(composed[0].kind, decomposed[0].kind);
+    assert_eq!(
+        decomposed[0].kind,
+        TokenKind::Identifier("caf\u{e9}".into())
+    );
+}
+
+#[test]
+fn test_contextual_keywords_are_identifiers() {
+    for word in [
+        "to",
+        "step",
+        "get",
+        "set",
+        "of",
+        "int",
+        "str",
+        "goto",
+        "synchronized",
+    ] {
+        let tokens = tokenize(word).unwrap();
+        assert_eq!(tokens[0].kind, TokenKind::Identifier(word.into()));
+    }
+}
+
+#[test]
+fn test_keyword_lookup_round_trips() {
+    for word in ["let", "const", "fn", "return", "print", "enum"] {
+        let keyword = Keyword::from_word(word).unwrap();
+        assert_eq!(keyword.to_string(), word);
+    }
+    assert_eq!(Keyword::from_word("step"), None);
+}
+
+#[test]
+fn test_conditional_and_nullish_operators() {
+    let tokens = tokenize("a ? b : c ?? d; e ??= f; g?.h").unwrap();
+    let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        token_kinds,
+        vec![
+            &TokenKind::Identifier("a".into()),
+            &TokenKind::Question,
+            &TokenKind::Identifier("b".into()),
+            &TokenKind::Colon,
+            &TokenKind::Identifier("c".into()),
+            &TokenKind::NullishCoalescing,
+            &TokenKind::Identifier("d".into()),
+            &TokenKind::Semicolon,
+            &TokenKind::Identifier("e".into()),
+            &TokenKind::NullishAssign,
+            &TokenKind::Identifier("f".into()),
+            &TokenKind::Semicolon,
+            &TokenKind::Identifier("g".into()),
+            &TokenKind::QuestionDot,
+            &TokenKind::Identifier("h".into()),
+            &TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_compound_assignment_and_exponent_operators() {
+    let tokens = tokenize("a *= b /= c %= d **= e ** f").unwrap();
+    let token_kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+    assert_eq!(
+        token_kinds,
+        vec![
+            &TokenKind::Identifier("a".into()),
+            &TokenKind::MultiplyAssign,
+            &TokenKind::Identifier("b".into()),
+            &TokenKind::DivideAssign,
+            &TokenKind::Identifier("c".into()),
+            &TokenKind::ModuloAssign,
+            &TokenKind::Identifier("d".into()),
+            &TokenKind::ExponentAssign,
+            &TokenKind::Identifier("e".into()),
+            &TokenKind::Exponent,
+            &TokenKind::Identifier("f".into()),
+            &TokenKind::Eof,
+        ]
+    );
 }
@@ -1,4 +1,7 @@
 mod benchmark_tests;
 mod error_tests;
+mod incremental_tests;
 mod integration_tests;
 mod lexer_tests;
+mod stream_tests;
+mod trivia_tests;
@@ -0,0 +1,87 @@
+use std::{borrow::Cow, io::BufReader};
+
+use lumi_lexer::{Lexer, error::LexError, token::TokenKind, tokenize};
+
+#[test]
+fn test_tokens_borrow_from_source() {
+    let tokens = tokenize("name \"plain\" \"esc\\n\" // note").unwrap();
+
+    assert!(matches!(
+        tokens[0].kind,
+        TokenKind::Identifier(Cow::Borrowed("name"))
+    ));
+    assert!(matches!(
+        tokens[1].kind,
+        TokenKind::String(Cow::Borrowed("plain"))
+    ));
+    // Escapes change the text, so only those strings are copied
+    assert!(matches!(&tokens[2].kind, TokenKind::String(Cow::Owned(s)) if s == "esc\n"));
+    assert!(matches!(
+        tokens[3].kind,
+        TokenKind::Comment(Cow::Borrowed(" note"))
+    ));
+}
+
+#[test]
+fn test_lexer_iterator_stops_before_eof() {
+    let kinds: Vec<TokenKind> = Lexer::new("x + 1")
+        .map(|token| token.unwrap().kind)
+        .collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Identifier("x".into()),
+            TokenKind::Plus,
+            TokenKind::Integer(1),
+        ]
+    );
+}
+
+#[test]
+fn test_lexer_iterator_continues_after_errors() {
+    let results: Vec<_> = Lexer::new("a @ b").collect();
+
+    assert_eq!(results.len(), 3);
+    assert!(matches!(
+        results[1],
+        Err(LexError::UnexpectedCharacter { character: '@', .. })
+    ));
+    assert_eq!(
+        results[2].as_ref().unwrap().kind,
+        TokenKind::Identifier("b".into())
+    );
+}
+
+#[test]
+fn test_reader_matches_string_lexer() {
+    let source =
+        "let s -> \"\"\"multi\nline\"\"\";\n/* block\ncomment */ print \"${a\n}\";\nlet π -> 3.14;";
+    // A tiny buffer makes the lexer refill in the middle of tokens
+    let reader = BufReader::with_capacity(2, source.as_bytes());
+
+    let streamed = Lexer::from_reader(reader).tokenize().unwrap();
+    let borrowed = tokenize(source).unwrap();
+
+    assert_eq!(streamed, borrowed);
+}
+
+#[test]
+fn test_reader_with_trivia_reproduces_source() {
+    let source = "// header\nlet x -> 1; /* a\nb */\n  y\n";
+    let reader = BufReader::new(source.as_bytes());
+
+    let tokens = Lexer::from_reader(reader).with_trivia().tokenize().unwrap();
+
+    assert_eq!(lumi_lexer::tokens_to_source(&tokens), source);
+    assert_eq!(tokens.last().unwrap().span.start.offset, source.len());
+}
+
+#[test]
+fn test_reader_error_is_reported() {
+    let bytes: &[u8] = b"let x;\n\xFF\xFE\n";
+    let results: Vec<_> = Lexer::from_reader(bytes).collect();
+
+    assert!(results[..3].iter().all(|r| r.is_ok()));
+    assert!(matches!(results.last(), Some(Err(LexError::Io { .. }))));
+}
@@ -0,0 +1,91 @@
+use lumi_lexer::{
+    Lexer,
+    token::{Keyword, TokenKind, TriviaKind},
+    tokenize, tokenize_with_trivia, tokens_to_source,
+};
+
+#[test]
+fn test_round_trip_reproduces_source() {
+    let source =
+        "// header\nlet x -> 1; /* inline */\n\n  fn add(a, b) {\r\n\treturn a + b; // sum\n}\n  ";
+    let tokens = tokenize_with_trivia(source).unwrap();
+
+    assert_eq!(tokens_to_source(&tokens), source);
+}
+
+#[test]
+fn test_round_trip_keeps_raw_literals() {
+    let source = "let s -> \"a\\tb ${x} \\u{41}\";\nlet r -> r\"raw\\n\"; let h -> 0xFF;";
+    let tokens = tokenize_with_trivia(source).unwrap();
+
+    assert_eq!(tokens_to_source(&tokens), source);
+}
+
+#[test]
+fn test_round_trip_keeps_lexical_errors() {
+    let source = "let x -> 1;\n  @ // bad\nlet s -> \"a \\q\"; x /* never closed";
+    let mut lexer = Lexer::new(source).with_trivia();
+    let (tokens, errors) = lexer.tokenize_with_errors();
+
+    assert_eq!(errors.len(), 3);
+    assert_eq!(tokens_to_source(&tokens), source);
+    let bad = tokens
+        .iter()
+        .find(|t| matches!(t.kind, TokenKind::Error(_)))
+        .unwrap();
+    assert_eq!(bad.lexeme.as_deref(), Some("@"));
+    let leading: Vec<TriviaKind> = bad.leading_trivia.iter().map(|t| t.kind).collect();
+    assert_eq!(leading, vec![TriviaKind::Newline, TriviaKind::Whitespace]);
+}
+
+#[test]
+fn test_leading_and_trailing_trivia() {
+    let source = "// doc\nlet x; // note\ny";
+    let tokens = tokenize_with_trivia(source).unwrap();
+
+    let let_token = &tokens[0];
+    assert_eq!(let_token.kind, TokenKind::Keyword(Keyword::Let));
+    let leading: Vec<TriviaKind> = let_token.leading_trivia.iter().map(|t| t.kind).collect();
+    assert_eq!(leading, vec![TriviaKind::LineComment, TriviaKind::Newline]);
+    assert_eq!(let_token.leading_trivia[0].text, "// doc");
+
+    // Trailing trivia stops at the end of the line
+    let semicolon = &tokens[2];
+    assert_eq!(semicolon.kind, TokenKind::Semicolon);
+    let trailing: Vec<TriviaKind> = semicolon.trailing_trivia.iter().map(|t| t.kind).collect();
+    assert_eq!(
+        trailing,
+        vec![TriviaKind::Whitespace, TriviaKind::LineComment]
+    );
+
+    let y = &tokens[3];
+    assert_eq!(y.leading_trivia[0].kind, TriviaKind::Newline);
+    assert_eq!(y.lexeme.as_deref(), Some("y"));
+}
+
+#[test]
+fn test_trivia_mode_keeps_token_positions() {
+    let source = "let a -> 1;\n/* two\nlines */ print a;";
+    let plain = tokenize(source).unwrap();
+    let with_trivia = tokenize_with_trivia(source).unwrap();
+
+    let plain: Vec<_> = plain
+        .iter()
+        .filter(|t| !matches!(t.kind, TokenKind::Comment(_)))
+        .map(|t| (&t.kind, &t.span))
+        .collect();
+    let with_trivia: Vec<_> = with_trivia.iter().map(|t| (&t.kind, &t.span)).collect();
+
+    assert_eq!(plain, with_trivia);
+}
+
+#[test]
+fn test_default_mode_has_no_trivia() {
+    let mut lexer = Lexer::new("let x; // note");
+    let tokens = lexer.tokenize().unwrap();
+
+    assert!(tokens.iter().all(|t| t.leading_trivia.is_empty()
+        && t.trailing_trivia.is_empty()
+        && t.lexeme.is_none()));
+    assert_eq!(tokens[3].kind, TokenKind::Comment(" note".into()));
+}
@@ -1,4 +1,4 @@
-use lumi_ast::Position;
+use lumi_ast::{Position, Span};
 use lumi_lexer::Token;
 use thiserror::Error;
 
@@ -8,45 +8,61 @@ pub type ParseResult<T> = Result<T, ParserError>;
 /// Errors that can occur during parsing
 #[derive(Debug, Error, Clone, PartialEq)]
 pub enum ParserError {
-    #[error("Unexpected token: {token} at position {position}")]
+    #[error("Unexpected token: {token} at position {}", span.start)]
     UnexpectedToken {
         token: String,
-        position: Position,
+        span: Span,
         expected: Option<String>,
     },
-    #[error("Invalid syntax at position {position}")]
+    #[error("Invalid syntax at position {}", span.start)]
     UnexpectedEndOfFile {
-        position: Position,
+        span: Span,
         expected: Option<String>,
     },
-    #[error("Invalid syntax: {message} at position {position}")]
-    InvalidSyntax { message: String, position: Position },
+    #[error("Invalid syntax: {message} at position {}", span.start)]
+    InvalidSyntax { message: String, span: Span },
+    #[error("Lexical error: {message}")]
+    Lexical { message: String, span: Span },
 }
 
 impl ParserError {
     pub fn unexpected_token(token: &Token, expected: Option<&str>) -> Self {
-        let position = Position {
-            line: token.start().line,
-            column: token.start().column,
-        };
         ParserError::UnexpectedToken {
             token: format!("{:?}", token.kind),
-            position,
+            span: token.span.clone(),
             expected: expected.map(|s| s.to_string()),
         }
     }
 
     pub fn unexpected_end_of_file(expected: Option<&str>) -> Self {
         ParserError::UnexpectedEndOfFile {
-            position: Position::new(1, 1), // Assuming default position for EOF
+            // Assuming default position for EOF
+            span: Span::new(Position::default(), Position::default()),
             expected: expected.map(|s| s.to_string()),
         }
     }
 
-    pub fn invalid_syntax(message: &str, position: Position) -> Self {
+    pub fn lexical(message: &str, token: &Token) -> Self {
+        ParserError::Lexical {
+            message: message.to_string(),
+            span: token.span.clone(),
+        }
+    }
+
+    pub fn invalid_syntax(message: &str, span: Span) -> Self {
         ParserError::InvalidSyntax {
             message: message.to_string(),
-            position,
+            span,
+        }
+    }
+
+    /// The source span the error points at
+    pub fn span(&self) -> &Span {
+        match self {
+            ParserError::UnexpectedToken { span, .. }
+            | ParserError::UnexpectedEndOfFile { span, .. }
+            | ParserError::InvalidSyntax { span, .. }
+            | ParserError::Lexical { span, .. } => span,
         }
     }
 }
@@ -4,4 +4,4 @@ mod recovery;
 
 pub use error::{ParseResult, ParserError};
 pub use parser::Parser;
-pub use recovery::ParsingContext;
+pub use recovery::{ErrorRecovery, ParsingContext, RecoveryContext, RecoveryStrategy};
@@ -1,43 +1,57 @@
+use std::collections::VecDeque;
+
 use lumi_ast::{
-    AssignmentExpression, BinaryExpression, BlockStatement, CallExpression, ExpressionStatement,
-    ForStatement, FunctionDeclaration, IfStatement, LogicalExpression, Node, Position,
-    PrintStatement, Program, Span, UnaryExpression, VariableDeclaration, VariableDeclarator,
+    ArrayLiteral, ArrayPattern, AssignmentExpression, BinaryExpression, BlockStatement,
+    BreakStatement, CallExpression, CatchClause, ClassDeclaration, ClassMember, ClassMemberKind,
+    ConditionalExpression, ContinueStatement, DoWhileStatement, EnumDeclaration, EnumField,
+    EnumVariant, ExpressionStatement, FileId, ForEachStatement, ForStatement, FunctionDeclaration,
+    FunctionExpression, IfStatement, InterpolatedString, LabeledStatement, LogicalExpression,
+    LoopStatement, MatchArm, MatchExpression, MemberExpression, NamedArgument, NewExpression, Node,
+    ObjectLiteral, ObjectPattern, ObjectProperty, Parameter, Pattern, Position, PrintStatement,
+    Program, PropertyKey, PropertyPattern, ReturnStatement, Span, SpreadElement, SwitchCase,
+    SwitchStatement, ThrowStatement, TryStatement, UnaryExpression, VariableDeclaration,
+    VariableDeclarator, VariantPattern, WhileStatement,
+};
+use lumi_lexer::{
+    token::{Keyword, TokenKind},
+    Lexer, Token,
 };
-use lumi_lexer::{token::TokenKind, Lexer, Token};
 
 use crate::{
     error::{ParseResult, ParserError},
     recovery::{ErrorRecovery, ParsingContext, RecoveryContext, RecoveryStrategy},
 };
 
-/// Main parser struct that holds the source code, lexer, and parsing state
-pub struct Parser {
-    _source: String,
+/// Main parser struct that holds the lexer over the source code and the parsing state
+pub struct Parser<'a> {
     _strict_mode: bool,
-    lexer: Lexer,
-    current: Option<Token>,
-    previous: Option<Token>,
+    lexer: Lexer<'a>,
+    file: FileId,
+    current: Option<Token<'a>>,
+    previous: Option<Token<'a>>,
+    /// Tokens after `current`, only read when the grammar needs more lookahead
+    lookahead: VecDeque<Token<'a>>,
     error_recovery: ErrorRecovery,
     context: ParsingContext,
 }
 
-impl Parser {
-    pub fn new(source: &str) -> Self {
-        let mut lexer = Lexer::new(source);
-        let current = match lexer.next_token() {
-            Ok(token) => Some(token),
-            Err(err) => {
-                eprintln!("Lexer error: {}", err);
-                None
-            }
-        };
+impl<'a> Parser<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self::with_file(source, FileId::default())
+    }
+
+    /// Create a parser whose spans point into `file` of a source map
+    pub fn with_file(source: &'a str, file: FileId) -> Self {
+        let mut lexer = Lexer::new(source).with_file(file);
+        let current = Some(lexer.next_token().unwrap_or_else(|err| Token::error(&err)));
 
         Self {
-            _source: source.to_string(),
             _strict_mode: false,
             lexer,
+            file,
             current,
             previous: None,
+            lookahead: VecDeque::new(),
             error_recovery: ErrorRecovery::default(),
             context: ParsingContext::TopLevel,
         }
@@ -48,6 +62,11 @@ impl Parser {
         self.parse_program()
     }
 
+    /// Errors the parser recovered from, including lexical errors
+    pub fn errors(&self) -> &[ParserError] {
+        self.error_recovery.errors()
+    }
+
     /// Parse a program node
     fn parse_program(&mut self) -> ParseResult<Node> {
         let mut body = Vec::new();
@@ -67,6 +86,9 @@ impl Parser {
                 Ok(stmt) => body.push(stmt),
                 Err(error) => {
                     if !self.try_recover_from_error(error.clone()) {
+                        if self.error_recovery.has_errors() {
+                            break; // Error limit reached, the recorded errors are kept in `errors()`
+                        }
                         return Err(error);
                     }
                     if self.is_eof() {
@@ -90,15 +112,30 @@ impl Parser {
         let old_context = self.context.clone();
         self.context = ParsingContext::Statement;
 
+        let is_label = self.check_idenfitier() && self.peek_check(TokenKind::Colon);
+
         let result = if let Some(token) = &self.current {
             match &token.kind {
-                TokenKind::Keyword(kw) => match kw.as_str() {
-                    "let" | "const" => self.parse_variable_declaration(),
-                    "if" => self.parse_if_statement(),
-                    "print" => self.parse_print_statement(),
-                    "fn" => self.parse_function_statement(),
-                    "for" => self.parse_for_statement(),
-                    // "while" => self.parse_while_loop(),
+                TokenKind::Keyword(kw) => match kw {
+                    Keyword::Let | Keyword::Const => self.parse_variable_declaration(),
+                    Keyword::If => self.parse_if_statement(),
+                    Keyword::Print => self.parse_print_statement(),
+                    Keyword::Fn => self.parse_function_statement(),
+                    Keyword::For => self.parse_for_statement(),
+                    Keyword::While => self.parse_while_statement(),
+                    Keyword::Do => self.parse_do_while_statement(),
+                    Keyword::Loop => self.parse_loop_statement(),
+                    Keyword::Break => self.parse_break_statement(),
+                    Keyword::Continue => self.parse_continue_statement(),
+                    Keyword::Return => self.parse_return_statement(),
+                    Keyword::Class => self.parse_class_declaration(),
+                    Keyword::Enum => self.parse_enum_declaration(),
+                    Keyword::Throw => self.parse_throw_statement(),
+                    Keyword::Try => self.parse_try_statement(),
+                    Keyword::Switch => self.parse_switch_statement(),
+                    Keyword::New | Keyword::This | Keyword::Super => {
+                        self.parse_expression_statement()
+                    }
                     // _ => self.parse_expression_statement(),
                     _ => {
                         // NOTE: temporary placeholder
@@ -107,6 +144,7 @@ impl Parser {
                     }
                 },
                 TokenKind::LeftBrace => self.parse_block_statement(),
+                _ if is_label => self.parse_labeled_statement(),
                 _ => self.parse_expression_statement(),
             }
         } else {
@@ -153,12 +191,36 @@ impl Parser {
         // for i in 1 to 10 step 5 { // the step keyword is optional, default is 1
         // }
         self.advance();
-        let id = Box::new(self.parse_identifier()?);
-        self.expect(TokenKind::Keyword("in".to_string()))?;
+        let id = Box::new(self.parse_binding_target()?);
+        self.expect(TokenKind::Keyword(Keyword::In))?;
         let start = Box::new(self.parse_expression()?);
-        self.expect(TokenKind::Keyword("to".to_string()))?;
+
+        // Without `to` the loop runs over the elements of an array: for [k, v] in pairs { }
+        if !self.check_contextual("to") {
+            if !self.check(TokenKind::LeftBrace) {
+                return Err(ParserError::invalid_syntax(
+                    "Expected 'to' or a block after the start of a for loop",
+                    self.create_span_from_tokens(),
+                ));
+            }
+            let body = Box::new(self.parse_statement()?);
+            let span = self.create_span_from_tokens();
+            return Ok(Node::ForEachStatement(ForEachStatement {
+                iterator: id,
+                iterable: start,
+                body,
+                span: Some(span),
+            }));
+        }
+        if id.as_identifier().is_none() {
+            return Err(ParserError::invalid_syntax(
+                "The iterator of a range loop must be a name",
+                self.create_span_from_tokens(),
+            ));
+        }
+        self.expect_contextual("to")?;
         let end = Box::new(self.parse_expression()?);
-        let step = if self.check(TokenKind::Keyword("step".to_string())) {
+        let step = if self.check_contextual("step") {
             self.advance(); // consume 'step'
             Some(Box::new(self.parse_expression()?))
         } else {
@@ -177,6 +239,287 @@ impl Parser {
         }))
     }
 
+    /// Example while loop: while (x < 10) { }
+    fn parse_while_statement(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'while'
+        self.expect(TokenKind::LeftParen)?;
+        let condition = Box::new(self.parse_expression()?);
+        self.expect(TokenKind::RightParen)?;
+        let body = Box::new(self.parse_statement()?);
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::WhileStatement(WhileStatement {
+            condition,
+            body,
+            span: Some(span),
+        }))
+    }
+
+    /// Example do-while loop: do { } while (x < 10);
+    fn parse_do_while_statement(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'do'
+        let body = Box::new(self.parse_statement()?);
+        self.expect(TokenKind::Keyword(Keyword::While))?;
+        self.expect(TokenKind::LeftParen)?;
+        let condition = Box::new(self.parse_expression()?);
+        self.expect(TokenKind::RightParen)?;
+        self.skip_statement_terminator();
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::DoWhileStatement(DoWhileStatement {
+            body,
+            condition,
+            span: Some(span),
+        }))
+    }
+
+    /// Example infinite loop: loop { if (done) { break; } }
+    fn parse_loop_statement(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'loop'
+        let body = Box::new(self.parse_statement()?);
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::LoopStatement(LoopStatement {
+            body,
+            span: Some(span),
+        }))
+    }
+
+    /// Example: return; or return x + 1;
+    fn parse_return_statement(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'return'
+
+        let argument = if self.check(TokenKind::Semicolon)
+            || self.check(TokenKind::RightBrace)
+            || self.is_eof()
+        {
+            None
+        } else {
+            Some(Box::new(self.parse_expression()?))
+        };
+        self.skip_statement_terminator();
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::ReturnStatement(ReturnStatement {
+            argument,
+            span: Some(span),
+        }))
+    }
+
+    /// Example: throw "not found";
+    fn parse_throw_statement(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'throw'
+
+        if self.check(TokenKind::Semicolon) || self.check(TokenKind::RightBrace) || self.is_eof() {
+            return Err(ParserError::invalid_syntax(
+                "Expected an expression after 'throw'",
+                self.create_span_from_tokens(),
+            ));
+        }
+        let argument = Box::new(self.parse_expression()?);
+        self.skip_statement_terminator();
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::ThrowStatement(ThrowStatement {
+            argument,
+            span: Some(span),
+        }))
+    }
+
+    /// Example: try { ... } catch (e) { ... } finally { ... }
+    fn parse_try_statement(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'try'
+        let block = Box::new(self.parse_required_block("try")?);
+
+        let handler = if self.check(TokenKind::Keyword(Keyword::Catch)) {
+            let catch_pos = self.current_position();
+            self.advance(); // consume 'catch'
+            let param = if self.check(TokenKind::LeftParen) {
+                self.advance(); // consume '('
+                let param = self.parse_identifier()?;
+                self.expect(TokenKind::RightParen)?;
+                Some(Box::new(param))
+            } else {
+                None
+            };
+            let body = Box::new(self.parse_required_block("catch")?);
+            Some(CatchClause {
+                param,
+                body,
+                span: Some(self.create_span(catch_pos, self.previous_position())),
+            })
+        } else {
+            None
+        };
+
+        let finalizer = if self.check(TokenKind::Keyword(Keyword::Finally)) {
+            self.advance(); // consume 'finally'
+            Some(Box::new(self.parse_required_block("finally")?))
+        } else {
+            None
+        };
+
+        if handler.is_none() && finalizer.is_none() {
+            return Err(ParserError::invalid_syntax(
+                "Expected 'catch' or 'finally' after a try block",
+                self.create_span_from_tokens(),
+            ));
+        }
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::TryStatement(TryStatement {
+            block,
+            handler,
+            finalizer,
+            span: Some(span),
+        }))
+    }
+
+    /// Example: switch (x) { case 1, 2: ... default: ... }
+    fn parse_switch_statement(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'switch'
+        self.expect(TokenKind::LeftParen)?;
+        let discriminant = Box::new(self.parse_expression()?);
+        self.expect(TokenKind::RightParen)?;
+        self.expect(TokenKind::LeftBrace)?;
+
+        let mut cases = Vec::new();
+        let mut has_default = false;
+        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+            let case_pos = self.current_position();
+            let mut tests = Vec::new();
+            if self.check(TokenKind::Keyword(Keyword::Case)) {
+                self.advance(); // consume 'case'
+                tests.push(self.parse_expression()?);
+                while self.check(TokenKind::Comma) {
+                    self.advance(); // consume ','
+                    tests.push(self.parse_expression()?);
+                }
+            } else if self.check(TokenKind::Keyword(Keyword::Default)) {
+                if has_default {
+                    return Err(ParserError::invalid_syntax(
+                        "A switch statement can only have one default case",
+                        self.create_span_from_tokens(),
+                    ));
+                }
+                has_default = true;
+                self.advance(); // consume 'default'
+            } else {
+                return Err(ParserError::invalid_syntax(
+                    "Expected 'case' or 'default' in a switch statement",
+                    self.create_span_from_tokens(),
+                ));
+            }
+            self.expect(TokenKind::Colon)?;
+
+            // A case runs until the next one, there is no fallthrough
+            let mut body = Vec::new();
+            while !self.check(TokenKind::Keyword(Keyword::Case))
+                && !self.check(TokenKind::Keyword(Keyword::Default))
+                && !self.check(TokenKind::RightBrace)
+                && !self.is_eof()
+            {
+                body.push(self.parse_statement()?);
+            }
+            cases.push(SwitchCase {
+                tests,
+                body,
+                span: Some(self.create_span(case_pos, self.previous_position())),
+            });
+        }
+        self.expect(TokenKind::RightBrace)?;
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::SwitchStatement(SwitchStatement {
+            discriminant,
+            cases,
+            span: Some(span),
+        }))
+    }
+
+    /// The braces are required after `try`, `catch` and `finally`
+    fn parse_required_block(&mut self, keyword: &str) -> ParseResult<Node> {
+        if !self.check(TokenKind::LeftBrace) {
+            return Err(ParserError::invalid_syntax(
+                &format!("Expected '{{' after '{}'", keyword),
+                self.create_span_from_tokens(),
+            ));
+        }
+        self.parse_block_statement()
+    }
+
+    /// Example: break; or break outer;
+    fn parse_break_statement(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'break'
+        let label = self.parse_jump_label()?;
+        self.skip_statement_terminator();
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::BreakStatement(BreakStatement {
+            label,
+            span: Some(span),
+        }))
+    }
+
+    /// Example: continue; or continue outer;
+    fn parse_continue_statement(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'continue'
+        let label = self.parse_jump_label()?;
+        self.skip_statement_terminator();
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::ContinueStatement(ContinueStatement {
+            label,
+            span: Some(span),
+        }))
+    }
+
+    /// Optional loop label after `break` or `continue`
+    fn parse_jump_label(&mut self) -> ParseResult<Option<String>> {
+        if self.check_idenfitier() {
+            Ok(Some(self.parse_identifier()?.name()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Example labeled loop: outer: for i in 0 to 10 { }
+    fn parse_labeled_statement(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        let label = self.parse_identifier()?.name();
+        self.expect(TokenKind::Colon)?;
+
+        let is_loop = self.check(TokenKind::Keyword(Keyword::While))
+            || self.check(TokenKind::Keyword(Keyword::Do))
+            || self.check(TokenKind::Keyword(Keyword::Loop))
+            || self.check(TokenKind::Keyword(Keyword::For));
+        if !is_loop {
+            let span = self.create_span_from_tokens();
+            return Err(ParserError::invalid_syntax(
+                &format!("Label '{}' must be followed by a loop", label),
+                span,
+            ));
+        }
+
+        let body = Box::new(self.parse_statement()?);
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::LabeledStatement(LabeledStatement {
+            label,
+            body,
+            span: Some(span),
+        }))
+    }
+
     /// Parse function statement
     fn parse_function_statement(&mut self) -> ParseResult<Node> {
         self.advance(); // consume 'fn'
@@ -190,6 +533,7 @@ impl Parser {
         self.expect(TokenKind::LeftParen)?;
         let params = self.parse_parameters()?;
         self.expect(TokenKind::RightParen)?;
+        let return_type = self.try_parse_identifier_type()?.map(Box::new);
 
         let body = Box::new(self.parse_function_body()?);
 
@@ -197,17 +541,340 @@ impl Parser {
         Ok(Node::FunctionDeclaration(FunctionDeclaration {
             id,
             params,
+            return_type,
             body,
             is_async: false,
             span: Some(span),
         }))
     }
 
-    fn parse_parameters(&mut self) -> ParseResult<Vec<Node>> {
+    /// Example function expression: fn (a, b) { return a + b; }
+    fn parse_function_expression(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'fn'
+
+        let id = if self.check_idenfitier() {
+            Some(Box::new(self.parse_identifier()?))
+        } else {
+            None
+        };
+
+        self.expect(TokenKind::LeftParen)?;
+        let params = self.parse_parameters()?;
+        self.expect(TokenKind::RightParen)?;
+        let return_type = self.try_parse_identifier_type()?.map(Box::new);
+
+        let body = Box::new(self.parse_function_body()?);
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::FunctionExpression(FunctionExpression {
+            id,
+            params,
+            return_type,
+            body,
+            is_arrow: false,
+            span: Some(span),
+        }))
+    }
+
+    /// Example arrow functions: x => x * 2, (a, b) => { return a + b; }
+    fn parse_arrow_function(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+
+        let params = if self.check(TokenKind::LeftParen) {
+            self.advance(); // consume '('
+            let params = self.parse_parameters()?;
+            self.expect(TokenKind::RightParen)?;
+            params
+        } else {
+            vec![self.parse_parameter()?]
+        };
+        let return_type = self.try_parse_identifier_type()?.map(Box::new);
+
+        self.expect(TokenKind::FatArrow)?;
+
+        let body = if self.check(TokenKind::LeftBrace) {
+            self.parse_function_body()?
+        } else {
+            self.parse_assignment_expression()?
+        };
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::FunctionExpression(FunctionExpression {
+            id: None,
+            params,
+            return_type,
+            body: Box::new(body),
+            is_arrow: true,
+            span: Some(span),
+        }))
+    }
+
+    /// Example class: class Dog extends Animal { legs -> 4; constructor(name) { ... } }
+    fn parse_class_declaration(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'class'
+
+        let id = Box::new(self.parse_identifier()?);
+        let superclass = if self.check(TokenKind::Keyword(Keyword::Extends)) {
+            self.advance(); // consume 'extends'
+            Some(Box::new(self.parse_postfix_expression()?))
+        } else {
+            None
+        };
+
+        self.expect(TokenKind::LeftBrace)?;
+        let old_context = self.context.clone();
+        self.context = ParsingContext::Class;
+
+        let mut members: Vec<ClassMember> = Vec::new();
+        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+            if self.check(TokenKind::Semicolon) {
+                self.advance();
+                continue;
+            }
+            let member = match self.parse_class_member() {
+                Ok(member) => member,
+                Err(error) => {
+                    self.context = old_context;
+                    return Err(error);
+                }
+            };
+            if member.kind == ClassMemberKind::Constructor
+                && members
+                    .iter()
+                    .any(|m| m.kind == ClassMemberKind::Constructor)
+            {
+                self.context = old_context;
+                return Err(ParserError::invalid_syntax(
+                    "A class can only have one constructor",
+                    self.create_span_from_tokens(),
+                ));
+            }
+            members.push(member);
+        }
+
+        self.context = old_context;
+        self.expect(TokenKind::RightBrace)?;
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::ClassDeclaration(ClassDeclaration {
+            id,
+            superclass,
+            members,
+            span: Some(span),
+        }))
+    }
+
+    /// Example: enum Shape { Circle(r: float), Rect(w: float, h: float), Empty }
+    fn parse_enum_declaration(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'enum'
+
+        let id = Box::new(self.parse_identifier()?);
+        self.expect(TokenKind::LeftBrace)?;
+
+        let mut variants: Vec<EnumVariant> = Vec::new();
+        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+            let variant_pos = self.current_position();
+            let name = self.parse_identifier()?.name();
+            if variants.iter().any(|variant| variant.name == name) {
+                return Err(ParserError::invalid_syntax(
+                    &format!("Duplicate enum variant '{}'", name),
+                    self.create_span(variant_pos, self.previous_position()),
+                ));
+            }
+
+            let mut fields = Vec::new();
+            if self.check(TokenKind::LeftParen) {
+                self.advance(); // consume '('
+                while !self.check(TokenKind::RightParen) && !self.is_eof() {
+                    let name = self.parse_identifier()?.name();
+                    let field_type = self.try_parse_identifier_type()?.map(Box::new);
+                    fields.push(EnumField { name, field_type });
+
+                    if !self.check(TokenKind::Comma) {
+                        break;
+                    }
+                    self.advance(); // consume ','
+                }
+                self.expect(TokenKind::RightParen)?;
+            }
+            variants.push(EnumVariant {
+                name,
+                fields,
+                span: Some(self.create_span(variant_pos, self.previous_position())),
+            });
+
+            if !self.check(TokenKind::Comma) {
+                break;
+            }
+            self.advance(); // consume ','
+        }
+        self.expect(TokenKind::RightBrace)?;
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::EnumDeclaration(EnumDeclaration {
+            id,
+            variants,
+            span: Some(span),
+        }))
+    }
+
+    /// Example members: x: int -> 0; static count -> 0; fn area() { ... }; get size() { ... }
+    fn parse_class_member(&mut self) -> ParseResult<ClassMember> {
+        let start_pos = self.current_position();
+
+        // `static`, `get` and `set` are only modifiers when a member name follows them
+        let is_static = self.check_contextual("static") && self.peek_is_member_name();
+        if is_static {
+            self.advance(); // consume 'static'
+        }
+
+        let kind = if self.check(TokenKind::Keyword(Keyword::Fn)) {
+            self.advance(); // consume 'fn'
+            ClassMemberKind::Method
+        } else if self.check_contextual("get") && self.peek_is_member_name() {
+            self.advance(); // consume 'get'
+            ClassMemberKind::Getter
+        } else if self.check_contextual("set") && self.peek_is_member_name() {
+            self.advance(); // consume 'set'
+            ClassMemberKind::Setter
+        } else if self.check_contextual("constructor") && self.peek_check(TokenKind::LeftParen) {
+            ClassMemberKind::Constructor
+        } else {
+            ClassMemberKind::Field
+        };
+        if is_static && kind == ClassMemberKind::Constructor {
+            return Err(ParserError::invalid_syntax(
+                "A constructor can't be static",
+                self.create_span_from_tokens(),
+            ));
+        }
+
+        let name = self.parse_identifier()?.name();
+        let (member_type, value) = if kind == ClassMemberKind::Field {
+            let member_type = self.try_parse_identifier_type()?.map(Box::new);
+            let value = if self.check(TokenKind::Arrow) {
+                self.advance(); // consume '->'
+                Some(Box::new(self.parse_expression()?))
+            } else {
+                None
+            };
+            if self.check(TokenKind::Semicolon) {
+                self.advance();
+            }
+            (member_type, value)
+        } else {
+            let method = self.parse_method(kind, start_pos)?;
+            (None, Some(Box::new(method)))
+        };
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(ClassMember {
+            name,
+            kind,
+            is_static,
+            member_type,
+            value,
+            span: Some(span),
+        })
+    }
+
+    /// Parse the parameters and body of a class method into a function expression
+    fn parse_method(
+        &mut self,
+        kind: ClassMemberKind,
+        start_pos: Option<Position>,
+    ) -> ParseResult<Node> {
+        self.expect(TokenKind::LeftParen)?;
+        let params = self.parse_parameters()?;
+        self.expect(TokenKind::RightParen)?;
+
+        let expected = match kind {
+            ClassMemberKind::Getter => Some((0, "A getter takes no parameters")),
+            ClassMemberKind::Setter => Some((1, "A setter takes exactly one parameter")),
+            _ => None,
+        };
+        if let Some((count, message)) = expected {
+            if params.len() != count || params.iter().any(|param| param.is_rest) {
+                return Err(ParserError::invalid_syntax(
+                    message,
+                    self.create_span_from_tokens(),
+                ));
+            }
+        }
+
+        let return_type = self.try_parse_identifier_type()?.map(Box::new);
+        let body = Box::new(self.parse_function_body()?);
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::FunctionExpression(FunctionExpression {
+            id: None,
+            params,
+            return_type,
+            body,
+            is_arrow: false,
+            span: Some(span),
+        }))
+    }
+
+    /// Whether the token after the current one names a class member, so the current
+    /// one is a modifier like `static` rather than the name itself
+    fn peek_is_member_name(&mut self) -> bool {
+        self.peek_check(TokenKind::Identifier("".into()))
+            || self.peek_check(TokenKind::Keyword(Keyword::Fn))
+    }
+
+    /// Example: new Point(1, 2), new shapes.Circle
+    fn parse_new_expression(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'new'
+
+        // The callee stops at the argument list, `new a.B(1)` calls `a.B`
+        let mut callee = self.parse_primary_expression()?;
+        while self.check(TokenKind::Dot) {
+            self.advance(); // consume '.'
+            let property = Box::new(self.parse_identifier()?);
+            let span = self.create_span_from_tokens();
+            callee = Node::MemberExpression(MemberExpression {
+                object: Box::new(callee),
+                property,
+                computed: false,
+                optional: false,
+                span: Some(span),
+            });
+        }
+
+        let arguments = if self.check(TokenKind::LeftParen) {
+            self.advance(); // consume '('
+            let arguments = self.parse_arguments()?;
+            self.expect(TokenKind::RightParen)?;
+            arguments
+        } else {
+            Vec::new()
+        };
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::NewExpression(NewExpression {
+            callee: Box::new(callee),
+            arguments,
+            span: Some(span),
+        }))
+    }
+
+    fn parse_parameters(&mut self) -> ParseResult<Vec<Parameter>> {
         let mut params = Vec::new();
 
         while !self.check(TokenKind::RightParen) && !self.is_eof() {
-            params.push(self.parse_identifier()?);
+            let param = self.parse_parameter()?;
+            if param.is_rest && !self.check(TokenKind::RightParen) {
+                return Err(ParserError::invalid_syntax(
+                    "A rest parameter must be the last parameter",
+                    self.create_span_from_tokens(),
+                ));
+            }
+            params.push(param);
 
             if self.check(TokenKind::Comma) {
                 self.advance();
@@ -217,11 +884,58 @@ impl Parser {
         Ok(params)
     }
 
+    /// Example parameters: a, b: int, c?: str, d -> 1, [e, f], { g }, ...rest
+    fn parse_parameter(&mut self) -> ParseResult<Parameter> {
+        let start_pos = self.current_position();
+        let is_rest = self.check(TokenKind::Ellipsis);
+        if is_rest {
+            self.advance(); // consume '...'
+        }
+        let name = Box::new(self.parse_binding_target()?);
+
+        let optional = !is_rest && self.check(TokenKind::Question);
+        if optional {
+            self.advance(); // consume '?'
+        }
+        let param_type = self.try_parse_identifier_type()?.map(Box::new);
+
+        let default_value = if !is_rest && self.check(TokenKind::Arrow) {
+            self.advance(); // consume '->'
+            Some(Box::new(self.parse_expression()?))
+        } else {
+            None
+        };
+
+        Ok(Parameter {
+            name,
+            param_type,
+            default_value,
+            optional,
+            is_rest,
+            span: Some(self.create_span(start_pos, self.previous_position())),
+        })
+    }
+
     fn parse_arguments(&mut self) -> ParseResult<Vec<Node>> {
         let mut arguments = Vec::new();
 
         while !self.check(TokenKind::RightParen) && !self.is_eof() {
-            arguments.push(self.parse_expression()?);
+            let is_named = matches!(
+                self.current_token().map(|t| &t.kind),
+                Some(TokenKind::Identifier(_))
+            ) && self.peek_check(TokenKind::Colon);
+            if is_named {
+                arguments.push(self.parse_named_argument()?);
+            } else {
+                let argument = self.parse_expression()?;
+                if matches!(arguments.last(), Some(Node::NamedArgument(_))) {
+                    return Err(ParserError::invalid_syntax(
+                        "Positional arguments must come before named arguments",
+                        self.create_span_from_tokens(),
+                    ));
+                }
+                arguments.push(argument);
+            }
 
             if self.check(TokenKind::Comma) {
                 self.advance();
@@ -231,6 +945,20 @@ impl Parser {
         Ok(arguments)
     }
 
+    /// Example named argument: name: "lumi"
+    fn parse_named_argument(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        let name = Box::new(self.parse_identifier()?);
+        self.expect(TokenKind::Colon)?;
+        let value = Box::new(self.parse_expression()?);
+
+        Ok(Node::NamedArgument(NamedArgument {
+            name,
+            value,
+            span: Some(self.create_span(start_pos, self.previous_position())),
+        }))
+    }
+
     fn parse_function_body(&mut self) -> ParseResult<Node> {
         self.parse_block_statement()
     }
@@ -261,17 +989,9 @@ impl Parser {
 
         let stmt = Box::new(self.parse_statement()?);
 
-        let else_part = if let Some(token) = &self.current {
-            if let TokenKind::Keyword(kw) = &token.kind {
-                if kw == "else" {
-                    self.advance(); // Consume 'else'
-                    Some(Box::new(self.parse_statement()?))
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+        let else_part = if self.check(TokenKind::Keyword(Keyword::Else)) {
+            self.advance(); // Consume 'else'
+            Some(Box::new(self.parse_statement()?))
         } else {
             None
         };
@@ -289,10 +1009,9 @@ impl Parser {
     fn parse_variable_declaration(&mut self) -> ParseResult<Node> {
         let kind = if let Some(token) = &self.current {
             if let TokenKind::Keyword(kw) = &token.kind {
-                match kw.as_str() {
-                    "let" => "let",
-                    "const" => "const",
-                    "var" => "var",
+                match kw {
+                    Keyword::Let => "let",
+                    Keyword::Const => "const",
                     _ => unreachable!(),
                 }
             } else {
@@ -307,7 +1026,7 @@ impl Parser {
         let mut declarations: Vec<VariableDeclarator> = Vec::new();
 
         loop {
-            let id = self.parse_identifier()?; // Name of the variable
+            let id = self.parse_binding_target()?; // Name of the variable, or a pattern
             let var_type = self.try_parse_identifier_type()?;
             let init = if self.check(TokenKind::Arrow) {
                 self.advance(); // consume the arrow
@@ -315,11 +1034,17 @@ impl Parser {
             } else {
                 None
             };
+            if matches!(id, Node::Pattern(_)) && init.is_none() {
+                return Err(ParserError::invalid_syntax(
+                    "A destructuring declaration must have an initializer",
+                    self.create_span_from_tokens(),
+                ));
+            }
             let span = self.create_span_from_tokens();
             declarations.push(VariableDeclarator {
                 var_name: Box::new(id),
                 var_type: var_type.map(Box::new),
-                init: init,
+                init,
                 span: Some(span),
             });
 
@@ -345,13 +1070,13 @@ impl Parser {
     fn parse_identifier(&mut self) -> ParseResult<Node> {
         if let Some(token) = &self.current {
             if let TokenKind::Identifier(name) = &token.kind {
-                let name = name.clone();
+                let name = name.to_string();
                 self.advance(); // consume the identifier token
-                return Ok(Node::Identifier(name));
+                Ok(Node::Identifier(name))
             } else {
                 Err(ParserError::invalid_syntax(
                     "Expected identifier",
-                    self.current_position().unwrap_or_default(),
+                    token.span.clone(),
                 ))
             }
         } else {
@@ -359,6 +1084,20 @@ impl Parser {
         }
     }
 
+    /// The name after `.` or `?.`, which may also be a keyword like `o.class`
+    fn parse_property_name(&mut self) -> ParseResult<Node> {
+        if let Some(Token {
+            kind: TokenKind::Keyword(keyword),
+            ..
+        }) = &self.current
+        {
+            let name = keyword.as_str().to_string();
+            self.advance(); // consume the keyword
+            return Ok(Node::Identifier(name));
+        }
+        self.parse_identifier()
+    }
+
     fn parse_expression_statement(&mut self) -> ParseResult<Node> {
         let expr = self.parse_expression()?;
 
@@ -373,28 +1112,98 @@ impl Parser {
         }))
     }
 
-    fn parse_expression(&mut self) -> ParseResult<Node> {
-        self.parse_assignment_expression()
+    fn parse_expression(&mut self) -> ParseResult<Node> {
+        self.parse_assignment_expression()
+    }
+
+    fn parse_assignment_expression(&mut self) -> ParseResult<Node> {
+        let mut left = self.parse_conditional_expression()?;
+
+        // `[a, b] -> [b, a]` destructures into existing variables
+        if self.check(TokenKind::Arrow)
+            && matches!(left, Node::ArrayLiteral(_) | Node::ObjectLiteral(_))
+        {
+            match expression_pattern(&left) {
+                Some(pattern) => left = Node::Pattern(pattern),
+                None => {
+                    return Err(ParserError::invalid_syntax(
+                        "Invalid destructuring target",
+                        self.create_span_from_tokens(),
+                    ))
+                }
+            }
+        }
+
+        if self.is_assignment_operator() {
+            let is_target = match &left {
+                Node::Identifier(_) | Node::Pattern(_) => true,
+                Node::MemberExpression(member) => !member.optional,
+                _ => false,
+            };
+            if !is_target {
+                let span = self.create_span_from_tokens();
+                return Err(ParserError::invalid_syntax(
+                    "Invalid assignment target",
+                    span,
+                ));
+            }
+
+            let operator = self.current_token_string();
+            self.advance(); // Consume operator
+            let right = Box::new(self.parse_assignment_expression()?);
+
+            let span = self.create_span_from_tokens();
+            Ok(Node::AssignmentExpression(AssignmentExpression {
+                left: Box::new(left),
+                operator,
+                right,
+                span: Some(span),
+            }))
+        } else {
+            Ok(left)
+        }
+    }
+
+    /// Parse `test ? consequent : alternate`, the branches may hold assignments and
+    /// nest to the right
+    fn parse_conditional_expression(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        let test = self.parse_nullish_expression()?;
+
+        if !self.check(TokenKind::Question) {
+            return Ok(test);
+        }
+        self.advance(); // consume '?'
+        let consequent = Box::new(self.parse_assignment_expression()?);
+        self.expect(TokenKind::Colon)?;
+        let alternate = Box::new(self.parse_assignment_expression()?);
+
+        Ok(Node::ConditionalExpression(ConditionalExpression {
+            test: Box::new(test),
+            consequent,
+            alternate,
+            span: Some(self.create_span(start_pos, self.previous_position())),
+        }))
     }
 
-    fn parse_assignment_expression(&mut self) -> ParseResult<Node> {
-        let left = self.parse_logical_or_expression()?;
+    fn parse_nullish_expression(&mut self) -> ParseResult<Node> {
+        let mut left = self.parse_logical_or_expression()?;
 
-        if self.is_assignment_operator() {
+        while self.check(TokenKind::NullishCoalescing) {
             let operator = self.current_token_string();
-            self.advance(); // Consume operator
-            let right = Box::new(self.parse_assignment_expression()?);
-
+            self.advance(); // consume the '??' token
+            let right = Box::new(self.parse_logical_or_expression()?);
             let span = self.create_span_from_tokens();
-            return Ok(Node::AssignmentExpression(AssignmentExpression {
+
+            left = Node::LogicalExpression(LogicalExpression {
                 left: Box::new(left),
                 operator,
                 right,
                 span: Some(span),
-            }));
-        } else {
-            return Ok(left);
+            });
         }
+
+        Ok(left)
     }
 
     fn parse_logical_or_expression(&mut self) -> ParseResult<Node> {
@@ -523,6 +1332,10 @@ impl Parser {
             let prefix = true;
             self.advance(); // Consume operator
             let argument = Box::new(self.parse_unary_expression()?);
+            if matches!(operator.as_str(), "++" | "--") && !is_update_target(&argument) {
+                let span = self.create_span_from_tokens();
+                return Err(ParserError::invalid_syntax("Invalid update target", span));
+            }
 
             let span = self.create_span_from_tokens();
             return Ok(Node::UnaryExpression(UnaryExpression {
@@ -533,73 +1346,110 @@ impl Parser {
             }));
         }
 
-        self.parse_postfix_expression()
+        self.parse_exponent_expression()
+    }
+
+    /// Parse `base ** exponent`, which groups to the right and binds tighter than a
+    /// unary operator on its left, `-2 ** 2` is `-(2 ** 2)`
+    fn parse_exponent_expression(&mut self) -> ParseResult<Node> {
+        let base = self.parse_postfix_expression()?;
+
+        if !self.check(TokenKind::Exponent) {
+            return Ok(base);
+        }
+        let operator = self.current_token_string();
+        self.advance(); // consume '**'
+        let exponent = Box::new(self.parse_unary_expression()?);
+
+        let span = self.create_span_from_tokens();
+        Ok(Node::BinaryExpression(BinaryExpression {
+            left: Box::new(base),
+            operator,
+            right: exponent,
+            span: Some(span),
+        }))
     }
 
     fn parse_postfix_expression(&mut self) -> ParseResult<Node> {
         let mut expr = self.parse_primary_expression()?;
 
-        loop {
-            if let Some(token) = &self.current {
-                match &token.kind {
-                    // TokenKind::LeftBracket => {
-                    //     self.advance(); // Consume '['
-                    //     let property = Box::new(self.parse_expression()?);
-                    //     self.expect(TokenKind::RightBracket)?;
-
-                    //     let span = self.create_span_from_tokens();
-                    //     expr = Node::MemberExpression(MemberExpression {
-                    //         object: Box::new(expr),
-                    //         property,
-                    //         computed: true,
-                    //         optional: false,
-                    //         span: Some(span),
-                    //     });
-                    // }
-
-                    // TokenKind::Dot => {
-                    //     self.advance(); // Consume '.'
-                    //     let property = Box::new(self.parse_identifier()?);
-
-                    //     let span = self.create_span_from_tokens();
-                    //     expr = Node::MemberExpression(MemberExpression {
-                    //         object: Box::new(expr),
-                    //         property,
-                    //         computed: false,
-                    //         optional: false,
-                    //         span: Some(span),
-                    //     });
-                    // }
-                    TokenKind::LeftParen => {
-                        self.advance(); // Consume '('
-                        let arguments = self.parse_arguments()?;
-                        self.expect(TokenKind::RightParen)?;
-
-                        let span = self.create_span_from_tokens();
-                        expr = Node::CallExpression(CallExpression {
-                            callee: Box::new(expr),
-                            arguments,
-                            span: Some(span),
-                        });
-                    }
+        while let Some(token) = &self.current {
+            match &token.kind {
+                TokenKind::LeftBracket => {
+                    self.advance(); // Consume '['
+                    let property = Box::new(self.parse_expression()?);
+                    self.expect(TokenKind::RightBracket)?;
+
+                    let span = self.create_span_from_tokens();
+                    expr = Node::MemberExpression(MemberExpression {
+                        object: Box::new(expr),
+                        property,
+                        computed: true,
+                        optional: false,
+                        span: Some(span),
+                    });
+                }
+                TokenKind::Dot => {
+                    self.advance(); // Consume '.'
+                    let property = Box::new(self.parse_property_name()?);
+
+                    let span = self.create_span_from_tokens();
+                    expr = Node::MemberExpression(MemberExpression {
+                        object: Box::new(expr),
+                        property,
+                        computed: false,
+                        optional: false,
+                        span: Some(span),
+                    });
+                }
+                TokenKind::QuestionDot => {
+                    self.advance(); // Consume '?.'
+                    let computed = self.check(TokenKind::LeftBracket);
+                    let property = if computed {
+                        self.advance(); // Consume '['
+                        let property = self.parse_expression()?;
+                        self.expect(TokenKind::RightBracket)?;
+                        property
+                    } else {
+                        self.parse_property_name()?
+                    };
 
-                    // TokenKind::Increment | TokenKind::Decrement => {
-                    //     let operator = self.current_token_string();
-                    //     let prefix = false;
-                    //     self.advance(); // Consume operator
-
-                    //     let span = self.create_span_from_tokens();
-                    //     expr = Node::UpdateExpression(UpdateExpression {
-                    //         operator,
-                    //         argument: Box::new(expr),
-                    //         prefix,
-                    //         span: Some(span),
-                    //     });
-                    // }
-                    _ => break,
+                    let span = self.create_span_from_tokens();
+                    expr = Node::MemberExpression(MemberExpression {
+                        object: Box::new(expr),
+                        property: Box::new(property),
+                        computed,
+                        optional: true,
+                        span: Some(span),
+                    });
                 }
-            } else {
-                break;
+                TokenKind::LeftParen => {
+                    self.advance(); // Consume '('
+                    let arguments = self.parse_arguments()?;
+                    self.expect(TokenKind::RightParen)?;
+
+                    let span = self.create_span_from_tokens();
+                    expr = Node::CallExpression(CallExpression {
+                        callee: Box::new(expr),
+                        arguments,
+                        span: Some(span),
+                    });
+                }
+
+                TokenKind::Increment | TokenKind::Decrement if is_update_target(&expr) => {
+                    let operator = self.current_token_string();
+                    let prefix = false;
+                    self.advance(); // Consume operator
+
+                    let span = self.create_span_from_tokens();
+                    expr = Node::UnaryExpression(UnaryExpression {
+                        operator,
+                        argument: Box::new(expr),
+                        prefix,
+                        span: Some(span),
+                    });
+                }
+                _ => break,
             }
         }
 
@@ -674,9 +1524,12 @@ impl Parser {
                 TokenKind::Arrow
                     | TokenKind::Assign
                     | TokenKind::PlusAssign
-                    | TokenKind::MinusAssign // TODO: add those when we implement them
-                                             // | TokenKind::PlusAssign | TokenKind::MinusAssign
-                                             // TokenKind::MultiplyAssign | TokenKind::DivideAssign | TokenKind::ModuloAssign |
+                    | TokenKind::MinusAssign
+                    | TokenKind::MultiplyAssign
+                    | TokenKind::DivideAssign
+                    | TokenKind::ModuloAssign
+                    | TokenKind::ExponentAssign
+                    | TokenKind::NullishAssign
             )
         } else {
             false
@@ -695,23 +1548,60 @@ impl Parser {
     }
 
     fn parse_primary_expression(&mut self) -> ParseResult<Node> {
+        let is_arrow = self.context != ParsingContext::MatchGuard
+            && ((self.check_idenfitier() && self.peek_check(TokenKind::FatArrow))
+                || (self.check(TokenKind::LeftParen) && self.is_arrow_parameter_list()));
+        let is_match = self.check_contextual("match") && self.is_match_expression();
+
         if let Some(token) = &self.current {
             match &token.kind {
-                TokenKind::Number(n) => {
+                _ if is_arrow => self.parse_arrow_function(),
+                _ if is_match => self.parse_match_expression(),
+                TokenKind::Integer(n) => {
                     let value = *n;
-                    self.advance(); // consume the number token
+                    self.advance(); // consume the integer token
+                    Ok(Node::Integer(value))
+                }
+                TokenKind::Float(n) => {
+                    let value = *n;
+                    self.advance(); // consume the float token
                     Ok(Node::Number(value))
                 }
+                TokenKind::BigInt(n) => {
+                    let value = n.clone();
+                    self.advance(); // consume the big integer token
+                    Ok(Node::BigInt(value))
+                }
                 TokenKind::Identifier(name) => {
-                    let value = name.clone();
+                    let value = name.to_string();
                     self.advance(); // consume the identifier
                     Ok(Node::Identifier(value))
                 }
+                TokenKind::LeftParen => {
+                    self.advance(); // consume '('
+                    let expr = self.parse_expression()?;
+                    self.expect(TokenKind::RightParen)?;
+                    Ok(expr)
+                }
+                TokenKind::Keyword(Keyword::Fn) => self.parse_function_expression(),
+                TokenKind::Keyword(Keyword::New) => self.parse_new_expression(),
+                TokenKind::Keyword(Keyword::This) => {
+                    self.advance(); // consume 'this'
+                    Ok(Node::This)
+                }
+                TokenKind::Keyword(Keyword::Super) => {
+                    self.advance(); // consume 'super'
+                    Ok(Node::Super)
+                }
                 TokenKind::String(s) => {
-                    let value = s.clone();
+                    let value = s.to_string();
                     self.advance(); // consume the string
                     Ok(Node::String(value))
                 }
+                TokenKind::TemplateHead(_) => self.parse_interpolated_string(),
+                // A `{` that starts a statement is a block, in an expression it's an object
+                TokenKind::LeftBrace => self.parse_object_literal(),
+                TokenKind::LeftBracket => self.parse_array_literal(),
                 TokenKind::Boolean(b) => {
                     let value = *b;
                     self.advance(); // consume the boolean
@@ -725,6 +1615,11 @@ impl Parser {
                     self.advance();
                     Ok(Node::Undefined)
                 }
+                TokenKind::Error(message) => {
+                    let error = ParserError::lexical(message, token);
+                    self.advance(); // skip the bad input, recovery continues from the next token
+                    Err(error)
+                }
                 _ => {
                     if self.check_idenfitier() {
                         self.parse_identifier()
@@ -741,13 +1636,412 @@ impl Parser {
         }
     }
 
+    /// Example: match (x) { 0 => "zero", 1 to 9 => "digit", n if n < 0 => "negative", _ => "many" }
+    fn parse_match_expression(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume 'match'
+        self.expect(TokenKind::LeftParen)?;
+        let discriminant = Box::new(self.parse_expression()?);
+        self.expect(TokenKind::RightParen)?;
+        self.expect(TokenKind::LeftBrace)?;
+
+        let mut arms = Vec::new();
+        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+            arms.push(self.parse_match_arm()?);
+
+            if !self.check(TokenKind::Comma) {
+                break;
+            }
+            self.advance(); // consume ','
+        }
+        self.expect(TokenKind::RightBrace)?;
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::MatchExpression(MatchExpression {
+            discriminant,
+            arms,
+            span: Some(span),
+        }))
+    }
+
+    fn parse_match_arm(&mut self) -> ParseResult<MatchArm> {
+        let start_pos = self.current_position();
+        let pattern = self.parse_pattern()?;
+
+        let guard = if self.check(TokenKind::Keyword(Keyword::If)) {
+            self.advance(); // consume 'if'
+            let old_context = self.context.clone();
+            self.context = ParsingContext::MatchGuard;
+            let guard = self.parse_expression();
+            self.context = old_context;
+            Some(Box::new(guard?))
+        } else {
+            None
+        };
+
+        self.expect(TokenKind::FatArrow)?;
+        let body = Box::new(self.parse_expression()?);
+
+        Ok(MatchArm {
+            pattern,
+            guard,
+            body,
+            span: Some(self.create_span(start_pos, self.previous_position())),
+        })
+    }
+
+    /// Example patterns: _, n, 42, "text", 1 to 9, [first, ...rest], { x, y: 0 }, Shape.Circle(r)
+    fn parse_pattern(&mut self) -> ParseResult<Pattern> {
+        match self.current.as_ref().map(|token| &token.kind) {
+            Some(TokenKind::Identifier(name)) => {
+                let name = name.to_string();
+                self.advance(); // consume the identifier
+                if self.check(TokenKind::Dot) {
+                    return self.parse_variant_pattern(name);
+                }
+                if name == "_" {
+                    Ok(Pattern::Wildcard)
+                } else {
+                    Ok(Pattern::Binding(name))
+                }
+            }
+            Some(TokenKind::LeftBracket) => self.parse_array_pattern(false),
+            Some(TokenKind::LeftBrace) => self.parse_object_pattern(false),
+            _ => {
+                let low = self.parse_pattern_literal()?;
+                if self.check_contextual("to") {
+                    self.advance(); // consume 'to'
+                    let high = self.parse_pattern_literal()?;
+                    return Ok(Pattern::Range(Box::new(low), Box::new(high)));
+                }
+                Ok(Pattern::Literal(Box::new(low)))
+            }
+        }
+    }
+
+    /// `[first, second, ...rest]`, the elements of a destructuring pattern may have defaults
+    fn parse_array_pattern(&mut self, destructuring: bool) -> ParseResult<Pattern> {
+        self.advance(); // consume '['
+        let mut elements = Vec::new();
+        let mut rest = None;
+        while !self.check(TokenKind::RightBracket) && !self.is_eof() {
+            if self.check(TokenKind::Ellipsis) {
+                self.advance(); // consume '...'
+                rest = Some(Box::new(match destructuring {
+                    true => self.parse_binding_pattern()?,
+                    false => self.parse_pattern()?,
+                }));
+                break; // The rest pattern must be the last one
+            }
+            elements.push(match destructuring {
+                true => self.parse_binding_element()?,
+                false => self.parse_pattern()?,
+            });
+
+            if !self.check(TokenKind::Comma) {
+                break;
+            }
+            self.advance(); // consume ','
+        }
+        self.expect(TokenKind::RightBracket)?;
+        Ok(Pattern::Array(ArrayPattern { elements, rest }))
+    }
+
+    /// `{ x, y: pattern }`, the properties of a destructuring pattern may have defaults
+    fn parse_object_pattern(&mut self, destructuring: bool) -> ParseResult<Pattern> {
+        self.advance(); // consume '{'
+        let mut properties = Vec::new();
+        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+            let key = match self.current.as_ref().map(|token| &token.kind) {
+                Some(TokenKind::String(s)) => {
+                    let key = s.to_string();
+                    self.advance(); // consume the string
+                    key
+                }
+                _ => self.parse_identifier()?.name(),
+            };
+            let value = if self.check(TokenKind::Colon) {
+                self.advance(); // consume ':'
+                match destructuring {
+                    true => self.parse_binding_element()?,
+                    false => self.parse_pattern()?,
+                }
+            } else if destructuring {
+                self.parse_pattern_default(Pattern::Binding(key.clone()))?
+            } else {
+                Pattern::Binding(key.clone())
+            };
+            properties.push(PropertyPattern { key, value });
+
+            if !self.check(TokenKind::Comma) {
+                break;
+            }
+            self.advance(); // consume ','
+        }
+        self.expect(TokenKind::RightBrace)?;
+        Ok(Pattern::Object(ObjectPattern { properties }))
+    }
+
+    /// What a declaration, parameter or loop binds: a name or a destructuring pattern
+    fn parse_binding_target(&mut self) -> ParseResult<Node> {
+        match self.current.as_ref().map(|token| &token.kind) {
+            Some(TokenKind::LeftBracket | TokenKind::LeftBrace) => {
+                Ok(Node::Pattern(self.parse_binding_pattern()?))
+            }
+            _ => self.parse_identifier(),
+        }
+    }
+
+    /// A destructuring pattern, only binding names to the parts of the value
+    fn parse_binding_pattern(&mut self) -> ParseResult<Pattern> {
+        match self.current.as_ref().map(|token| &token.kind) {
+            Some(TokenKind::LeftBracket) => self.parse_array_pattern(true),
+            Some(TokenKind::LeftBrace) => self.parse_object_pattern(true),
+            _ => match self.parse_identifier()?.name() {
+                name if name == "_" => Ok(Pattern::Wildcard),
+                name => Ok(Pattern::Binding(name)),
+            },
+        }
+    }
+
+    /// An element of a destructuring pattern with an optional default: a -> 1
+    fn parse_binding_element(&mut self) -> ParseResult<Pattern> {
+        let pattern = self.parse_binding_pattern()?;
+        self.parse_pattern_default(pattern)
+    }
+
+    /// The `-> default` after a destructuring pattern, used when the value is undefined
+    fn parse_pattern_default(&mut self, pattern: Pattern) -> ParseResult<Pattern> {
+        if !self.check(TokenKind::Arrow) {
+            return Ok(pattern);
+        }
+        self.advance(); // consume '->'
+        let default = self.parse_expression()?;
+        Ok(Pattern::Default(Box::new(pattern), Box::new(default)))
+    }
+
+    /// The rest of `Shape.Circle(r)` after the enum name
+    fn parse_variant_pattern(&mut self, enum_name: String) -> ParseResult<Pattern> {
+        self.advance(); // consume '.'
+        let variant = self.parse_identifier()?.name();
+
+        let fields = if self.check(TokenKind::LeftParen) {
+            self.advance(); // consume '('
+            let mut fields = Vec::new();
+            while !self.check(TokenKind::RightParen) && !self.is_eof() {
+                fields.push(self.parse_pattern()?);
+
+                if !self.check(TokenKind::Comma) {
+                    break;
+                }
+                self.advance(); // consume ','
+            }
+            self.expect(TokenKind::RightParen)?;
+            Some(fields)
+        } else {
+            None
+        };
+
+        Ok(Pattern::Variant(VariantPattern {
+            enum_name,
+            variant,
+            fields,
+        }))
+    }
+
+    /// A literal in a pattern, numbers can be negative
+    fn parse_pattern_literal(&mut self) -> ParseResult<Node> {
+        let negative = self.check(TokenKind::Minus);
+        if negative {
+            self.advance(); // consume '-'
+        }
+        let literal = match self.current.as_ref().map(|token| &token.kind) {
+            Some(TokenKind::Integer(n)) => Node::Integer(if negative { -*n } else { *n }),
+            Some(TokenKind::Float(n)) => Node::Number(if negative { -*n } else { *n }),
+            Some(TokenKind::String(s)) if !negative => Node::String(s.to_string()),
+            Some(TokenKind::Boolean(b)) if !negative => Node::Boolean(*b),
+            Some(TokenKind::Null) if !negative => Node::Null,
+            Some(TokenKind::Undefined) if !negative => Node::Undefined,
+            _ => {
+                return Err(ParserError::invalid_syntax(
+                    "Expected a pattern",
+                    self.create_span_from_tokens(),
+                ))
+            }
+        };
+        self.advance(); // consume the literal
+        Ok(literal)
+    }
+
+    /// Example array literal: [1, , 3, ...rest]
+    fn parse_array_literal(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume '['
+
+        let mut elements = Vec::new();
+        while !self.check(TokenKind::RightBracket) && !self.is_eof() {
+            if self.check(TokenKind::Comma) {
+                self.advance(); // consume ',' of a hole
+                elements.push(None);
+                continue;
+            }
+
+            let element = if self.check(TokenKind::Ellipsis) {
+                let spread_start = self.current_position();
+                self.advance(); // consume '...'
+                let argument = Box::new(self.parse_expression()?);
+                let span = self.create_span(spread_start, self.previous_position());
+                Node::SpreadElement(SpreadElement {
+                    argument,
+                    span: Some(span),
+                })
+            } else {
+                self.parse_expression()?
+            };
+            elements.push(Some(element));
+
+            if !self.check(TokenKind::Comma) {
+                break;
+            }
+            self.advance(); // consume ','
+        }
+        self.expect(TokenKind::RightBracket)?;
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::ArrayLiteral(ArrayLiteral {
+            elements,
+            span: Some(span),
+        }))
+    }
+
+    /// Example object literal: { name: "lumi", "quoted key": 1, [key]: 2, count }
+    fn parse_object_literal(&mut self) -> ParseResult<Node> {
+        let start_pos = self.current_position();
+        self.advance(); // consume '{'
+
+        let mut properties = Vec::new();
+        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+            properties.push(self.parse_object_property()?);
+
+            if !self.check(TokenKind::Comma) {
+                break;
+            }
+            self.advance(); // consume ','
+        }
+        self.expect(TokenKind::RightBrace)?;
+
+        let span = self.create_span(start_pos, self.previous_position());
+        Ok(Node::ObjectLiteral(ObjectLiteral {
+            properties,
+            span: Some(span),
+        }))
+    }
+
+    fn parse_object_property(&mut self) -> ParseResult<ObjectProperty> {
+        let key = match self.current.as_ref().map(|token| &token.kind) {
+            Some(TokenKind::LeftBracket) => {
+                self.advance(); // consume '['
+                let key = self.parse_expression()?;
+                self.expect(TokenKind::RightBracket)?;
+                PropertyKey::Computed(Box::new(key))
+            }
+            Some(TokenKind::String(s)) => {
+                let key = s.to_string();
+                self.advance(); // consume the string
+                PropertyKey::Named(key)
+            }
+            Some(TokenKind::Integer(n)) => {
+                let key = n.to_string();
+                self.advance(); // consume the integer
+                PropertyKey::Named(key)
+            }
+            Some(TokenKind::Keyword(keyword)) => {
+                // A keyword can't be a variable, so it has no shorthand
+                let key = keyword.as_str().to_string();
+                self.advance(); // consume the keyword
+                PropertyKey::Named(key)
+            }
+            _ => {
+                let name = self.parse_identifier()?.name();
+                if !self.check(TokenKind::Colon) {
+                    return Ok(ObjectProperty {
+                        value: Node::Identifier(name.clone()),
+                        key: PropertyKey::Named(name),
+                        shorthand: true,
+                    });
+                }
+                PropertyKey::Named(name)
+            }
+        };
+
+        self.expect(TokenKind::Colon)?;
+        let value = self.parse_expression()?;
+
+        Ok(ObjectProperty {
+            key,
+            value,
+            shorthand: false,
+        })
+    }
+
+    /// Example interpolated string: "count: ${x + 1}"
+    fn parse_interpolated_string(&mut self) -> ParseResult<Node> {
+        let mut parts = Vec::new();
+
+        if let Some(Token {
+            kind: TokenKind::TemplateHead(head),
+            ..
+        }) = &self.current
+        {
+            if !head.is_empty() {
+                parts.push(Node::String(head.to_string()));
+            }
+        }
+        self.advance(); // consume the head of the string
+
+        loop {
+            parts.push(self.parse_expression()?);
+
+            let token = self
+                .current
+                .as_ref()
+                .ok_or_else(|| ParserError::unexpected_end_of_file(Some("end of interpolation")))?;
+
+            match &token.kind {
+                TokenKind::TemplateMiddle(text) | TokenKind::TemplateTail(text) => {
+                    let is_tail = matches!(token.kind, TokenKind::TemplateTail(_));
+                    if !text.is_empty() {
+                        parts.push(Node::String(text.to_string()));
+                    }
+                    self.advance(); // consume the string piece
+                    if is_tail {
+                        break;
+                    }
+                }
+                _ => {
+                    return Err(ParserError::unexpected_token(
+                        token,
+                        Some("end of interpolation"),
+                    ))
+                }
+            }
+        }
+
+        let span = self.create_span_from_tokens();
+        Ok(Node::InterpolatedString(InterpolatedString {
+            parts,
+            span: Some(span),
+        }))
+    }
+
     /// Try to parse an identifier type (e.g., after a colon)
     fn try_parse_identifier_type(&mut self) -> ParseResult<Option<Node>> {
         if self.check(TokenKind::Colon) {
+            // Type names are not reserved, so they come through as identifiers
             self.advance(); // consume the colon
             if let Some(token) = &self.current {
-                if let TokenKind::Keyword(name) = &token.kind {
-                    let name = name.clone();
+                if let TokenKind::Identifier(name) = &token.kind {
+                    let name = name.to_string();
                     self.advance(); // consume the identifier token
                     return Ok(Some(Node::Identifier(name)));
                 }
@@ -777,15 +2071,33 @@ impl Parser {
                 TokenKind::LessThan => "<".to_string(),
                 TokenKind::GreaterThan => ">".to_string(),
                 TokenKind::LessThanEqual => "<=".to_string(),
+                TokenKind::Assign => "=".to_string(),
+                TokenKind::Arrow => "->".to_string(),
+                TokenKind::FatArrow => "=>".to_string(),
                 TokenKind::PlusAssign => "+=".to_string(),
                 TokenKind::MinusAssign => "-=".to_string(),
+                TokenKind::MultiplyAssign => "*=".to_string(),
+                TokenKind::DivideAssign => "/=".to_string(),
+                TokenKind::ModuloAssign => "%=".to_string(),
+                TokenKind::ExponentAssign => "**=".to_string(),
+                TokenKind::Exponent => "**".to_string(),
+                TokenKind::NullishAssign => "??=".to_string(),
+                TokenKind::NullishCoalescing => "??".to_string(),
                 TokenKind::GreaterThanEqual => ">=".to_string(),
                 TokenKind::Increment => "++".to_string(),
                 TokenKind::Decrement => "--".to_string(),
-                TokenKind::Identifier(name) => name.clone(),
-                TokenKind::String(s) => s.clone(),
+                TokenKind::Semicolon => ";".to_string(),
+                TokenKind::Comma => ",".to_string(),
+                TokenKind::RightParen => ")".to_string(),
+                TokenKind::RightBracket => "]".to_string(),
+                TokenKind::RightBrace => "}".to_string(),
+                TokenKind::Keyword(kw) => kw.to_string(),
+                TokenKind::Identifier(name) => name.to_string(),
+                TokenKind::String(s) => s.to_string(),
                 TokenKind::Boolean(b) => b.to_string(),
-                TokenKind::Number(n) => n.to_string(),
+                TokenKind::Integer(n) => n.to_string(),
+                TokenKind::Float(n) => n.to_string(),
+                TokenKind::BigInt(n) => format!("{}n", n),
                 TokenKind::Eof => "EOF".to_string(),
                 _ => format!("{:?}", token.kind), // Fallback for other token kinds
             }
@@ -794,54 +2106,138 @@ impl Parser {
         }
     }
 
-    /// Check if the current token matches a specific kind
+    /// Check if the current token matches a specific kind, keywords must match exactly
     fn check(&self, token_kind: TokenKind) -> bool {
         if let Some(token) = &self.current {
-            std::mem::discriminant(&token.kind) == std::mem::discriminant(&token_kind)
+            match token_kind {
+                TokenKind::Keyword(_) => token.kind == token_kind,
+                _ => std::mem::discriminant(&token.kind) == std::mem::discriminant(&token_kind),
+            }
         } else {
             false
         }
     }
 
+    /// Check if the current token is the contextual keyword `word`, which the lexer
+    /// hands over as a plain identifier
+    fn check_contextual(&self, word: &str) -> bool {
+        matches!(&self.current, Some(token) if matches!(&token.kind, TokenKind::Identifier(name) if name == word))
+    }
+
+    /// Consume the contextual keyword `word` or fail
+    fn expect_contextual(&mut self, word: &str) -> ParseResult<()> {
+        if self.check_contextual(word) {
+            self.advance();
+            Ok(())
+        } else {
+            Err(ParserError::unexpected_token(
+                self.current_token()
+                    .unwrap_or_else(|| panic!("No current token")),
+                Some(word),
+            ))
+        }
+    }
+
     /// Advance to the next token
     fn advance(&mut self) {
         self.previous = self.current.take();
-        match self.lexer.next_token() {
-            Ok(token) => self.current = Some(token),
-            Err(err) => {
-                eprintln!("Lexer error: {}", err);
-                self.current = None;
+        self.current = Some(match self.lookahead.pop_front() {
+            Some(token) => token,
+            None => self.next_lexer_token(),
+        });
+    }
+
+    /// Check if the token after the current one matches a specific kind
+    fn peek_check(&mut self, token_kind: TokenKind) -> bool {
+        let token = self.peek_nth(0);
+        std::mem::discriminant(&token.kind) == std::mem::discriminant(&token_kind)
+    }
+
+    /// The `n`th token after the current one, reading it from the lexer when needed
+    fn peek_nth(&mut self, n: usize) -> &Token<'a> {
+        while self.lookahead.len() <= n {
+            let token = self.next_lexer_token();
+            self.lookahead.push_back(token);
+        }
+        &self.lookahead[n]
+    }
+
+    /// Check if the current `(` opens the parameter list of an arrow function, which
+    /// is only known once the matching `)` is followed by `=>` or a return type and `=>`
+    fn is_arrow_parameter_list(&mut self) -> bool {
+        let Some(n) = self.peek_closing_paren(0) else {
+            return false;
+        };
+        match self.peek_nth(n + 1).kind {
+            TokenKind::FatArrow => true,
+            // A return type annotation, `(a: int): int => ...`
+            TokenKind::Colon => {
+                matches!(self.peek_nth(n + 2).kind, TokenKind::Identifier(_))
+                    && matches!(self.peek_nth(n + 3).kind, TokenKind::FatArrow)
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if the current `match` starts a match expression rather than naming a
+    /// variable or function, which it does when followed by `(subject) {`
+    fn is_match_expression(&mut self) -> bool {
+        if !matches!(self.peek_nth(0).kind, TokenKind::LeftParen) {
+            return false;
+        }
+        match self.peek_closing_paren(1) {
+            Some(n) => matches!(self.peek_nth(n + 1).kind, TokenKind::LeftBrace),
+            None => false,
+        }
+    }
+
+    /// The lookahead index of the `)` closing a `(` that was opened right before index `n`
+    fn peek_closing_paren(&mut self, mut n: usize) -> Option<usize> {
+        let mut depth = 1;
+        loop {
+            match self.peek_nth(n).kind {
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(n);
+                    }
+                }
+                TokenKind::Eof => return None,
+                _ => {}
             }
+            n += 1;
         }
     }
 
+    fn next_lexer_token(&mut self) -> Token<'a> {
+        // Lexical errors come through as error tokens so parsing can recover from them
+        self.lexer
+            .next_token()
+            .unwrap_or_else(|err| Token::error(&err))
+    }
+
     /// Check if we're at the end of the file
     fn is_eof(&self) -> bool {
         self.current.is_none()
             || self
                 .current
                 .as_ref()
-                .map_or(false, |t| t.kind == TokenKind::Eof)
+                .is_some_and(|t| t.kind == TokenKind::Eof)
     }
 
     /// Gets the position of the current token
     fn current_position(&self) -> Option<Position> {
-        self.current.as_ref().map(|t| Position {
-            line: t.start().line,
-            column: t.start().column,
-        })
+        self.current.as_ref().map(|t| t.start())
     }
 
     /// Gets the previous token
     fn previous_position(&self) -> Option<Position> {
-        self.previous.as_ref().map(|t| Position {
-            line: t.end().line,
-            column: t.end().column,
-        })
+        self.previous.as_ref().map(|t| t.end())
     }
 
     /// Get the current token
-    fn current_token(&self) -> Option<&Token> {
+    fn current_token(&self) -> Option<&Token<'a>> {
         self.current.as_ref()
     }
 
@@ -849,14 +2245,21 @@ impl Parser {
     fn create_span_from_tokens(&self) -> Span {
         let start = self.previous_position().unwrap_or_default();
         let end = self.current_position().unwrap_or_default();
-        Span::new(start, end)
+        Span::new(start, end).with_file(self.file)
     }
 
     /// Create a span from positions
     fn create_span(&self, start: Option<Position>, end: Option<Position>) -> Span {
         let start = start.unwrap_or_default();
         let end = end.unwrap_or_default();
-        Span::new(start, end)
+        Span::new(start, end).with_file(self.file)
+    }
+
+    /// Consume a `;` so recovery resumes at the start of the next statement
+    fn skip_statement_terminator(&mut self) {
+        if self.check(TokenKind::Semicolon) {
+            self.advance();
+        }
     }
 
     fn try_recover_from_error(&mut self, error: ParserError) -> bool {
@@ -877,13 +2280,9 @@ impl Parser {
         match strategy {
             RecoveryStrategy::SkipUntil(tokens) => {
                 while !self.is_eof() {
-                    if let Some(token) = self.current_token() {
-                        if tokens
-                            .iter()
-                            .any(|t| format!("{:?}", token.kind).contains(t))
-                        {
-                            break;
-                        }
+                    if tokens.contains(&self.current_token_string()) {
+                        self.skip_statement_terminator();
+                        break;
                     }
                     self.advance();
                 }
@@ -894,7 +2293,10 @@ impl Parser {
                 while !self.is_eof() {
                     if let Some(token) = self.current_token() {
                         match token.kind {
-                            TokenKind::Semicolon | TokenKind::RightBrace => break,
+                            TokenKind::Semicolon | TokenKind::RightBrace => {
+                                self.skip_statement_terminator();
+                                break;
+                            }
                             _ => self.advance(),
                         }
                     } else {
@@ -946,7 +2348,10 @@ impl Parser {
                 while !self.is_eof() {
                     let should_break = if let Some(token) = &self.current {
                         matches!(token.kind, TokenKind::RightBrace)
-                            || matches!(token.kind, TokenKind::Keyword(ref kw) if kw == "import" || kw == "export")
+                            || matches!(
+                                token.kind,
+                                TokenKind::Keyword(Keyword::Import | Keyword::Export)
+                            )
                     } else {
                         false
                     };
@@ -980,3 +2385,55 @@ impl Parser {
         }
     }
 }
+
+/// Whether `node` can be the operand of `++` or `--`
+fn is_update_target(node: &Node) -> bool {
+    matches!(node, Node::Identifier(_) | Node::MemberExpression(_))
+}
+
+/// The destructuring pattern an array or object literal on the left of `->` stands for,
+/// `None` when it holds something that can't be assigned to
+fn expression_pattern(node: &Node) -> Option<Pattern> {
+    match node {
+        Node::Identifier(name) if name == "_" => Some(Pattern::Wildcard),
+        Node::Identifier(name) => Some(Pattern::Binding(name.clone())),
+        Node::AssignmentExpression(assignment) if assignment.operator == "->" => {
+            Some(Pattern::Default(
+                Box::new(expression_pattern(&assignment.left)?),
+                assignment.right.clone(),
+            ))
+        }
+        Node::ArrayLiteral(array) => {
+            let (rest, elements) = match array.elements.split_last() {
+                Some((Some(Node::SpreadElement(spread)), elements)) => (
+                    Some(Box::new(expression_pattern(&spread.argument)?)),
+                    elements,
+                ),
+                _ => (None, &array.elements[..]),
+            };
+            let elements = elements
+                .iter()
+                .map(|element| match element {
+                    Some(element) => expression_pattern(element),
+                    None => Some(Pattern::Wildcard), // A hole skips an element
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(Pattern::Array(ArrayPattern { elements, rest }))
+        }
+        Node::ObjectLiteral(object) => {
+            let properties = object
+                .properties
+                .iter()
+                .map(|property| match &property.key {
+                    PropertyKey::Named(key) => Some(PropertyPattern {
+                        key: key.clone(),
+                        value: expression_pattern(&property.value)?,
+                    }),
+                    PropertyKey::Computed(_) => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(Pattern::Object(ObjectPattern { properties }))
+        }
+        _ => None,
+    }
+}
@@ -39,12 +39,12 @@ pub enum RecoveryStrategy {
 
 /// Error recovery context that holds information about the current parsing state
 #[derive(Debug, Clone)]
-pub struct RecoveryContext {
+pub struct RecoveryContext<'a> {
     /// Current token that caused the error
-    pub current_token: Option<Token>,
+    pub current_token: Option<Token<'a>>,
 
     /// Previous token
-    pub previous_token: Option<Token>,
+    pub previous_token: Option<Token<'a>>,
 
     /// Tokens that can be used for recovery
     pub recovery_tokens: Vec<String>,
@@ -79,12 +79,15 @@ pub enum ParsingContext {
 
     /// Parsing inside a variable declaration
     Declaration,
+
+    /// Parsing the guard of a match arm, where `=>` ends the expression
+    MatchGuard,
 }
 
-impl RecoveryContext {
+impl<'a> RecoveryContext<'a> {
     pub fn new(
-        current_token: Option<Token>,
-        previous_token: Option<Token>,
+        current_token: Option<Token<'a>>,
+        previous_token: Option<Token<'a>>,
         context: ParsingContext,
     ) -> Self {
         Self {
@@ -135,13 +138,15 @@ impl RecoveryContext {
                 "export".to_string(),
             ]),
 
-            ParsingContext::Expression => RecoveryStrategy::SkipUntil(vec![
-                ";".to_string(),
-                ",".to_string(),
-                ")".to_string(),
-                "]".to_string(),
-                "}".to_string(),
-            ]),
+            ParsingContext::Expression | ParsingContext::MatchGuard => {
+                RecoveryStrategy::SkipUntil(vec![
+                    ";".to_string(),
+                    ",".to_string(),
+                    ")".to_string(),
+                    "]".to_string(),
+                    "}".to_string(),
+                ])
+            }
 
             ParsingContext::Declaration => {
                 RecoveryStrategy::SkipUntil(vec![";".to_string(), "}".to_string()])
@@ -157,10 +162,7 @@ impl RecoveryContext {
 
     /// Get the current position of the parser
     pub fn current_position(&self) -> Option<Position> {
-        self.current_token.as_ref().map(|t| Position {
-            line: t.start().line,
-            column: t.start().column,
-        })
+        self.current_token.as_ref().map(|t| t.start())
     }
 }
 
@@ -1,4 +1,4 @@
-use lumi_ast::Node;
+use lumi_ast::{FileId, Node};
 use lumi_parser::Parser;
 
 #[test]
@@ -17,8 +17,8 @@ fn test_number() {
     if let Ok(Node::Program(program)) = result {
         assert_eq!(program.body.len(), 1);
         if let Node::ExpressionStatement(stmt) = &program.body[0] {
-            if let Node::Number(num) = &*stmt.expression {
-                assert_eq!(*num, 42.0);
+            if let Node::Integer(num) = &*stmt.expression {
+                assert_eq!(*num, 42);
             } else {
                 panic!("Expected a number node");
             }
@@ -107,15 +107,15 @@ fn test_binary_expression() {
         if let Node::ExpressionStatement(stmt) = &program.body[0] {
             if let Node::BinaryExpression(expr) = &*stmt.expression {
                 assert_eq!(expr.operator, "+");
-                if let Node::Number(left) = &*expr.left {
-                    assert_eq!(*left, 1.0);
+                if let Node::Integer(left) = &*expr.left {
+                    assert_eq!(*left, 1);
                 } else {
-                    panic!("Expected Number on left");
+                    panic!("Expected Integer on left");
                 }
-                if let Node::Number(right) = &*expr.right {
-                    assert_eq!(*right, 2.0);
+                if let Node::Integer(right) = &*expr.right {
+                    assert_eq!(*right, 2);
                 } else {
-                    panic!("Expected Number on right");
+                    panic!("Expected Integer on right");
                 }
             } else {
                 panic!("Expected BinaryExpression");
@@ -127,3 +127,18 @@ fn test_binary_expression() {
         panic!("Expected Program node");
     }
 }
+
+#[test]
+fn test_spans_carry_file_id() {
+    let source = "let x -> 42;";
+    let mut parser = Parser::with_file(source, FileId(1));
+    let result = parser.parse();
+
+    if let Ok(Node::Program(program)) = result {
+        let span = program.body[0].span().unwrap();
+        assert_eq!(span.file, FileId(1));
+        assert!(span.end.offset <= source.len());
+    } else {
+        panic!("Expected a program node");
+    }
+}
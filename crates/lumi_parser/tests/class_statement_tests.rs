@@ -0,0 +1,99 @@
+use lumi_ast::{ClassMemberKind, Node};
+use lumi_parser::Parser;
+
+#[test]
+fn test_class_declaration() {
+    let mut parser = Parser::new(
+        r#"
+        class Circle extends Shape {
+            radius: float -> 1.0;
+            static count -> 0;
+            constructor(radius) { super(); this.radius -> radius; }
+            fn area() { return this.radius * this.radius * 3.14; }
+            static fn unit() { return new Circle(1.0); }
+            get diameter() { return this.radius * 2; }
+            set diameter(d) { this.radius -> d / 2; }
+        }
+        "#,
+    );
+    let result = parser.parse().unwrap();
+
+    let Node::Program(program) = result else {
+        panic!("Expected a program");
+    };
+    let Node::ClassDeclaration(class) = &program.body[0] else {
+        panic!("Expected a class declaration node");
+    };
+    assert_eq!(class.id.name(), "Circle");
+    assert!(matches!(class.superclass.as_deref(), Some(Node::Identifier(name)) if name == "Shape"));
+
+    let members: Vec<(&str, ClassMemberKind, bool)> = class
+        .members
+        .iter()
+        .map(|member| (member.name.as_str(), member.kind, member.is_static))
+        .collect();
+    assert_eq!(
+        members,
+        vec![
+            ("radius", ClassMemberKind::Field, false),
+            ("count", ClassMemberKind::Field, true),
+            ("constructor", ClassMemberKind::Constructor, false),
+            ("area", ClassMemberKind::Method, false),
+            ("unit", ClassMemberKind::Method, true),
+            ("diameter", ClassMemberKind::Getter, false),
+            ("diameter", ClassMemberKind::Setter, false),
+        ]
+    );
+    assert!(class.members[0].member_type.is_some());
+}
+
+#[test]
+fn test_modifier_words_as_member_names() {
+    let mut parser = Parser::new("class Box { static -> 1; get -> 2; set -> 3; }");
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    let Node::ClassDeclaration(class) = &program.body[0] else {
+        panic!("Expected a class declaration node");
+    };
+    assert!(class
+        .members
+        .iter()
+        .all(|member| member.kind == ClassMemberKind::Field && !member.is_static));
+}
+
+#[test]
+fn test_new_expression() {
+    let mut parser = Parser::new("new shapes.Circle(1, 2).area();");
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else {
+        panic!("Expected an expression statement");
+    };
+    let Node::CallExpression(call) = &*stmt.expression else {
+        panic!("Expected the method call to wrap the new expression");
+    };
+    let Node::MemberExpression(member) = &*call.callee else {
+        panic!("Expected a member expression");
+    };
+    let Node::NewExpression(new) = &*member.object else {
+        panic!("Expected a new expression");
+    };
+    assert!(matches!(&*new.callee, Node::MemberExpression(_)));
+    assert_eq!(new.arguments.len(), 2);
+}
+
+#[test]
+fn test_invalid_class_members() {
+    for source in [
+        "class A { constructor() {} constructor() {} }",
+        "class A { static constructor() {} }",
+        "class A { get value(x) { return x; } }",
+        "class A { set value() {} }",
+    ] {
+        let mut parser = Parser::new(source);
+        let _ = parser.parse();
+        assert!(!parser.errors().is_empty(), "{source}");
+    }
+}
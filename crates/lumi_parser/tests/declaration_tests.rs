@@ -39,8 +39,8 @@ fn test_assignment_expression() {
     if let Ok(Node::Program(program)) = result {
         if let Node::ExpressionStatement(expr) = &program.body[1] {
             if let Node::AssignmentExpression(assign) = &*expr.expression {
-                if let Node::Number(n) = &*assign.right {
-                    assert_eq!(*n, 2.0);
+                if let Node::Integer(n) = &*assign.right {
+                    assert_eq!(*n, 2);
                 }
             }
         }
@@ -55,8 +55,8 @@ fn test_assignment_expression_plus_assign() {
     if let Ok(Node::Program(program)) = result {
         if let Node::ExpressionStatement(expr) = &program.body[1] {
             if let Node::AssignmentExpression(assign) = &*expr.expression {
-                if let Node::Number(n) = &*assign.right {
-                    assert_eq!(*n, 2.0);
+                if let Node::Integer(n) = &*assign.right {
+                    assert_eq!(*n, 2);
                 }
             }
         }
@@ -71,8 +71,8 @@ fn test_assignment_expression_minus_assign() {
     if let Ok(Node::Program(program)) = result {
         if let Node::ExpressionStatement(expr) = &program.body[1] {
             if let Node::AssignmentExpression(assign) = &*expr.expression {
-                if let Node::Number(n) = &*assign.right {
-                    assert_eq!(*n, 2.0);
+                if let Node::Integer(n) = &*assign.right {
+                    assert_eq!(*n, 2);
                 }
             }
         }
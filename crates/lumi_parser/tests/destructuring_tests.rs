@@ -0,0 +1,129 @@
+use lumi_ast::{ArrayPattern, Node, ObjectPattern, Pattern, PropertyPattern};
+use lumi_parser::Parser;
+
+fn binding(name: &str) -> Pattern {
+    Pattern::Binding(name.to_string())
+}
+
+#[test]
+fn test_destructuring_declarations() {
+    let mut parser =
+        Parser::new("let [a, _, ...rest] -> arr; let { x, y: renamed, z -> 0 } -> obj;");
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+
+    let Node::VariableDeclaration(decl) = &program.body[0] else {
+        panic!("Expected a variable declaration");
+    };
+    assert_eq!(
+        *decl.declarations[0].var_name,
+        Node::Pattern(Pattern::Array(ArrayPattern {
+            elements: vec![binding("a"), Pattern::Wildcard],
+            rest: Some(Box::new(binding("rest"))),
+        }))
+    );
+
+    let Node::VariableDeclaration(decl) = &program.body[1] else {
+        panic!("Expected a variable declaration");
+    };
+    assert_eq!(
+        *decl.declarations[0].var_name,
+        Node::Pattern(Pattern::Object(ObjectPattern {
+            properties: vec![
+                PropertyPattern {
+                    key: "x".to_string(),
+                    value: binding("x"),
+                },
+                PropertyPattern {
+                    key: "y".to_string(),
+                    value: binding("renamed"),
+                },
+                PropertyPattern {
+                    key: "z".to_string(),
+                    value: Pattern::Default(Box::new(binding("z")), Box::new(Node::Integer(0))),
+                },
+            ],
+        }))
+    );
+}
+
+#[test]
+fn test_destructuring_parameters_and_loops() {
+    let mut parser = Parser::new(
+        "fn dist({ x, y }, [dx, dy -> 1]) { return x; } for [key, value] in pairs { print key; }",
+    );
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+
+    let Node::FunctionDeclaration(func) = &program.body[0] else {
+        panic!("Expected a function declaration");
+    };
+    assert!(matches!(
+        *func.params[0].name,
+        Node::Pattern(Pattern::Object(_))
+    ));
+    assert_eq!(
+        *func.params[1].name,
+        Node::Pattern(Pattern::Array(ArrayPattern {
+            elements: vec![
+                binding("dx"),
+                Pattern::Default(Box::new(binding("dy")), Box::new(Node::Integer(1))),
+            ],
+            rest: None,
+        }))
+    );
+
+    let Node::ForEachStatement(stmt) = &program.body[1] else {
+        panic!("Expected a for-each statement");
+    };
+    assert_eq!(
+        *stmt.iterator,
+        Node::Pattern(Pattern::Array(ArrayPattern {
+            elements: vec![binding("key"), binding("value")],
+            rest: None,
+        }))
+    );
+    assert_eq!(*stmt.iterable, Node::Identifier("pairs".to_string()));
+}
+
+#[test]
+fn test_destructuring_assignment() {
+    let mut parser = Parser::new("[a, b] -> [b, a];");
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+    let Node::ExpressionStatement(stmt) = &program.body[0] else {
+        panic!("Expected an expression statement");
+    };
+    let Node::AssignmentExpression(expr) = &*stmt.expression else {
+        panic!("Expected an assignment");
+    };
+    assert_eq!(
+        *expr.left,
+        Node::Pattern(Pattern::Array(ArrayPattern {
+            elements: vec![binding("a"), binding("b")],
+            rest: None,
+        }))
+    );
+}
+
+#[test]
+fn test_invalid_destructuring() {
+    for source in [
+        "let [a, b];",
+        "let [a, 1] -> arr;",
+        "let { x: 1 } -> obj;",
+        "for [i, j] in 0 to 10 { print i; }",
+        "[a, 1] -> arr;",
+        "[a + b] -> arr;",
+    ] {
+        let mut parser = Parser::new(source);
+        let _ = parser.parse();
+        assert!(!parser.errors().is_empty(), "{source}");
+    }
+}
@@ -0,0 +1,88 @@
+use lumi_ast::{Node, Pattern, VariantPattern};
+use lumi_parser::Parser;
+
+#[test]
+fn test_enum_declaration() {
+    let mut parser = Parser::new("enum Shape { Circle(r: float), Rect(w: float, h), Empty }");
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+    let Node::EnumDeclaration(decl) = &program.body[0] else {
+        panic!("Expected an enum declaration");
+    };
+    assert_eq!(decl.id.name(), "Shape");
+
+    let variants: Vec<(&str, Vec<&str>)> = decl
+        .variants
+        .iter()
+        .map(|variant| {
+            (
+                variant.name.as_str(),
+                variant.fields.iter().map(|f| f.name.as_str()).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        variants,
+        vec![
+            ("Circle", vec!["r"]),
+            ("Rect", vec!["w", "h"]),
+            ("Empty", vec![])
+        ]
+    );
+    assert!(decl.variants[1].fields[0].field_type.is_some());
+    assert!(decl.variants[1].fields[1].field_type.is_none());
+}
+
+#[test]
+fn test_variant_patterns() {
+    let mut parser = Parser::new(
+        "let area -> match (shape) { Shape.Circle(r) => r, Shape.Rect(_, h) => h, Shape.Empty => 0 };",
+    );
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+    let Node::VariableDeclaration(decl) = &program.body[0] else {
+        panic!("Expected a variable declaration");
+    };
+    let Some(Node::MatchExpression(expr)) = decl.declarations[0].init.as_deref() else {
+        panic!("Expected a match expression");
+    };
+    let patterns: Vec<&Pattern> = expr.arms.iter().map(|arm| &arm.pattern).collect();
+    assert_eq!(
+        patterns,
+        vec![
+            &Pattern::Variant(VariantPattern {
+                enum_name: "Shape".to_string(),
+                variant: "Circle".to_string(),
+                fields: Some(vec![Pattern::Binding("r".to_string())]),
+            }),
+            &Pattern::Variant(VariantPattern {
+                enum_name: "Shape".to_string(),
+                variant: "Rect".to_string(),
+                fields: Some(vec![Pattern::Wildcard, Pattern::Binding("h".to_string())]),
+            }),
+            &Pattern::Variant(VariantPattern {
+                enum_name: "Shape".to_string(),
+                variant: "Empty".to_string(),
+                fields: None,
+            }),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_enum_declarations() {
+    for source in [
+        "enum { A }",
+        "enum Shape { Circle(r: float), Circle }",
+        "enum Shape { Circle(r: float) Rect }",
+        "enum Shape { Circle(1) }",
+    ] {
+        let mut parser = Parser::new(source);
+        let _ = parser.parse();
+        assert!(!parser.errors().is_empty(), "{source}");
+    }
+}
@@ -20,21 +20,21 @@ fn test_simple_for_statement() {
             } else {
                 panic!("Expected an Identifier");
             }
-            if let Node::Number(n) = &*stmt.start {
-                assert_eq!(*n, 0.0);
+            if let Node::Integer(n) = &*stmt.start {
+                assert_eq!(*n, 0);
             } else {
-                panic!("Expected a Number for start");
+                panic!("Expected an Integer for start");
             }
-            if let Node::Number(n) = &*stmt.end {
-                assert_eq!(*n, 10.0);
+            if let Node::Integer(n) = &*stmt.end {
+                assert_eq!(*n, 10);
             } else {
-                panic!("Expected a Number for end");
+                panic!("Expected an Integer for end");
             }
             if let Some(step) = &stmt.step {
-                if let Node::Number(n) = &**step {
-                    assert_eq!(*n, 1.0); // Default step is 1
+                if let Node::Integer(n) = &**step {
+                    assert_eq!(*n, 1); // Default step is 1
                 } else {
-                    panic!("Expected a Number for step");
+                    panic!("Expected an Integer for step");
                 }
             } else {
                 // If no step is provided, it should be None
@@ -70,21 +70,21 @@ fn test_simple_for_statement_with_step() {
             } else {
                 panic!("Expected an Identifier");
             }
-            if let Node::Number(n) = &*stmt.start {
-                assert_eq!(*n, 0.0);
+            if let Node::Integer(n) = &*stmt.start {
+                assert_eq!(*n, 0);
             } else {
-                panic!("Expected a Number for start");
+                panic!("Expected an Integer for start");
             }
-            if let Node::Number(n) = &*stmt.end {
-                assert_eq!(*n, 10.0);
+            if let Node::Integer(n) = &*stmt.end {
+                assert_eq!(*n, 10);
             } else {
-                panic!("Expected a Number for end");
+                panic!("Expected an Integer for end");
             }
             if let Some(step) = &stmt.step {
-                if let Node::Number(n) = &**step {
-                    assert_eq!(*n, 2.0); // Step is 2
+                if let Node::Integer(n) = &**step {
+                    assert_eq!(*n, 2); // Step is 2
                 } else {
-                    panic!("Expected a Number for step");
+                    panic!("Expected an Integer for step");
                 }
             } else {
                 // If no step is provided, it should be None
@@ -100,3 +100,39 @@ fn test_simple_for_statement_with_step() {
         }
     }
 }
+
+#[test]
+fn test_contextual_keywords_as_names() {
+    let mut parser = Parser::new(
+        r#"
+        let to: int -> 10;
+        let step: int -> 2;
+        for i in 0 to to step step {
+            print i;
+        }
+    "#,
+    );
+
+    let result = parser.parse();
+
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ForStatement(stmt) = &program.body[2] {
+            assert_eq!(*stmt.end, Node::Identifier("to".to_string()));
+            assert_eq!(
+                stmt.step.as_deref(),
+                Some(&Node::Identifier("step".to_string()))
+            );
+        } else {
+            panic!("Expected ForStatement node");
+        }
+    } else {
+        panic!("Expected program to parse");
+    }
+}
+
+#[test]
+fn test_for_statement_requires_to() {
+    let mut parser = Parser::new("for i in 0 until 10 { print i; }");
+    let _ = parser.parse();
+    assert!(!parser.errors().is_empty());
+}
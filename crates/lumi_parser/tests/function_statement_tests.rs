@@ -28,3 +28,134 @@ fn test_fn_statement() {
         }
     }
 }
+
+#[test]
+fn test_return_statements() {
+    let mut parser = Parser::new(
+        r#"
+        fn test(x) {
+            if (x > 1) { return; }
+            return x + 1;
+        }
+    "#,
+    );
+
+    let result = parser.parse();
+    assert!(parser.errors().is_empty());
+
+    let Ok(Node::Program(program)) = result else {
+        panic!("Expected Program node");
+    };
+    let Node::FunctionDeclaration(fn_decl) = &program.body[0] else {
+        panic!("Expected FunctionDeclaration node");
+    };
+    let Node::BlockStatement(blck) = &*fn_decl.body else {
+        panic!("Expected BlockStatement node");
+    };
+    let Node::IfStatement(if_stmt) = &blck.body[0] else {
+        panic!("Expected IfStatement node");
+    };
+    let Node::BlockStatement(then_block) = &*if_stmt.stmt else {
+        panic!("Expected BlockStatement node");
+    };
+    assert!(matches!(&then_block.body[0], Node::ReturnStatement(stmt) if stmt.argument.is_none()));
+    if let Node::ReturnStatement(stmt) = &blck.body[1] {
+        assert!(matches!(
+            stmt.argument.as_deref(),
+            Some(Node::BinaryExpression(_))
+        ));
+    } else {
+        panic!("Expected ReturnStatement node");
+    }
+}
+
+#[test]
+fn test_typed_fn_statement() {
+    let mut parser = Parser::new("fn add(a: int, b: int): int { return a + b; }");
+
+    let result = parser.parse();
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+
+    let Ok(Node::Program(program)) = result else {
+        panic!("Expected Program node");
+    };
+    let Node::FunctionDeclaration(fn_decl) = &program.body[0] else {
+        panic!("Expected FunctionDeclaration node");
+    };
+    for (param, name) in fn_decl.params.iter().zip(["a", "b"]) {
+        assert_eq!(*param.name, Node::Identifier(name.to_string()));
+        assert_eq!(
+            param.param_type.as_deref(),
+            Some(&Node::Identifier("int".to_string()))
+        );
+    }
+    assert_eq!(
+        fn_decl.return_type.as_deref(),
+        Some(&Node::Identifier("int".to_string()))
+    );
+}
+
+#[test]
+fn test_default_optional_and_rest_parameters() {
+    let mut parser = Parser::new(r#"fn greet(name, greeting -> "hi", punct?: str, ...rest) {}"#);
+
+    let result = parser.parse();
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+
+    let Ok(Node::Program(program)) = result else {
+        panic!("Expected Program node");
+    };
+    let Node::FunctionDeclaration(fn_decl) = &program.body[0] else {
+        panic!("Expected FunctionDeclaration node");
+    };
+    let [name, greeting, punct, rest] = fn_decl.params.as_slice() else {
+        panic!("Expected four parameters");
+    };
+    assert!(name.default_value.is_none() && !name.optional && !name.is_rest);
+    assert_eq!(
+        greeting.default_value.as_deref(),
+        Some(&Node::String("hi".to_string()))
+    );
+    assert!(punct.optional);
+    assert_eq!(
+        punct.param_type.as_deref(),
+        Some(&Node::Identifier("str".to_string()))
+    );
+    assert!(rest.is_rest);
+    assert_eq!(*rest.name, Node::Identifier("rest".to_string()));
+}
+
+#[test]
+fn test_rest_parameter_must_be_last() {
+    let mut parser = Parser::new("fn f(...rest, a) {}");
+    let _ = parser.parse();
+    assert!(!parser.errors().is_empty());
+}
+
+#[test]
+fn test_named_arguments() {
+    let mut parser = Parser::new(r#"greet("lumi", greeting: "hey");"#);
+
+    let result = parser.parse();
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+
+    let Ok(Node::Program(program)) = result else {
+        panic!("Expected Program node");
+    };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else {
+        panic!("Expected ExpressionStatement node");
+    };
+    let Node::CallExpression(call) = &*stmt.expression else {
+        panic!("Expected CallExpression node");
+    };
+    assert_eq!(call.arguments[0], Node::String("lumi".to_string()));
+    let Node::NamedArgument(named) = &call.arguments[1] else {
+        panic!("Expected NamedArgument node");
+    };
+    assert_eq!(*named.name, Node::Identifier("greeting".to_string()));
+    assert_eq!(*named.value, Node::String("hey".to_string()));
+
+    let mut parser = Parser::new(r#"greet(greeting: "hey", "lumi");"#);
+    let _ = parser.parse();
+    assert!(!parser.errors().is_empty());
+}
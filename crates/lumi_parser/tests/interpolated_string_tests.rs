@@ -0,0 +1,77 @@
+use lumi_ast::Node;
+use lumi_parser::Parser;
+
+#[test]
+fn test_interpolated_string() {
+    let mut parser = Parser::new(r#"let x -> 1; print "count: ${x + 1}!";"#);
+    let result = parser.parse();
+
+    if let Ok(Node::Program(program)) = result {
+        if let Node::PrintStatement(stmt) = &program.body[1] {
+            if let Node::InterpolatedString(s) = &*stmt.argument {
+                assert_eq!(s.parts.len(), 3);
+                assert_eq!(s.parts[0], Node::String("count: ".to_string()));
+                if let Node::BinaryExpression(expr) = &s.parts[1] {
+                    assert_eq!(expr.operator, "+");
+                } else {
+                    panic!("Expected BinaryExpression");
+                }
+                assert_eq!(s.parts[2], Node::String("!".to_string()));
+            } else {
+                panic!("Expected InterpolatedString");
+            }
+        } else {
+            panic!("Expected PrintStatement");
+        }
+    } else {
+        panic!("Expected Program node");
+    }
+}
+
+#[test]
+fn test_interpolated_string_multiple_segments() {
+    let mut parser = Parser::new(r#""${a} and ${b}""#);
+    let result = parser.parse();
+
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::InterpolatedString(s) = &*stmt.expression {
+                assert_eq!(
+                    s.parts,
+                    vec![
+                        Node::Identifier("a".to_string()),
+                        Node::String(" and ".to_string()),
+                        Node::Identifier("b".to_string()),
+                    ]
+                );
+            } else {
+                panic!("Expected InterpolatedString");
+            }
+        } else {
+            panic!("Expected ExpressionStatement");
+        }
+    } else {
+        panic!("Expected Program node");
+    }
+}
+
+#[test]
+fn test_nested_interpolated_string() {
+    let mut parser = Parser::new(r#""outer ${"inner ${x}"}""#);
+    let result = parser.parse();
+
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::InterpolatedString(s) = &*stmt.expression {
+                assert_eq!(s.parts.len(), 2);
+                assert!(matches!(s.parts[1], Node::InterpolatedString(_)));
+            } else {
+                panic!("Expected InterpolatedString");
+            }
+        } else {
+            panic!("Expected ExpressionStatement");
+        }
+    } else {
+        panic!("Expected Program node");
+    }
+}
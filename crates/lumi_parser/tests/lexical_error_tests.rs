@@ -0,0 +1,47 @@
+use lumi_ast::Node;
+use lumi_parser::{Parser, ParserError};
+
+#[test]
+fn test_lexical_error_is_recorded_and_parsing_continues() {
+    let mut parser = Parser::new("let a -> 1;\n@;\nlet b -> 2;");
+    let result = parser.parse();
+
+    if let Ok(Node::Program(program)) = result {
+        assert_eq!(program.body.len(), 2);
+        assert!(matches!(program.body[0], Node::VariableDeclaration(_)));
+        assert!(matches!(program.body[1], Node::VariableDeclaration(_)));
+    } else {
+        panic!("Expected a program node");
+    }
+
+    assert_eq!(parser.errors().len(), 1);
+    match &parser.errors()[0] {
+        ParserError::Lexical { message, span } => {
+            assert!(message.contains("Unexpected character: @"));
+            assert_eq!(span.start.line, 2);
+        }
+        e => panic!("Expected a lexical error, got {:?}", e),
+    }
+}
+
+#[test]
+fn test_multiple_lexical_errors_are_reported() {
+    let mut parser = Parser::new("let a -> \"bad \\q\";\n#;\nprint a;");
+    let result = parser.parse();
+
+    assert!(result.is_ok());
+    let errors = parser.errors();
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .all(|e| matches!(e, ParserError::Lexical { .. })));
+}
+
+#[test]
+fn test_valid_source_has_no_errors() {
+    let mut parser = Parser::new("let a -> 1;\nprint a;");
+    let result = parser.parse();
+
+    assert!(result.is_ok());
+    assert!(parser.errors().is_empty());
+}
@@ -0,0 +1,106 @@
+use lumi_ast::Node;
+use lumi_parser::Parser;
+
+fn parse_first(source: &str) -> Node {
+    let mut parser = Parser::new(source);
+    let result = parser.parse();
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+
+    match result {
+        Ok(Node::Program(mut program)) => program.body.remove(0),
+        other => panic!("Expected Program, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_while_statement() {
+    let node = parse_first("while (x < 10) { x += 1; }");
+
+    if let Node::WhileStatement(stmt) = node {
+        assert!(matches!(*stmt.condition, Node::BinaryExpression(_)));
+        if let Node::BlockStatement(block) = &*stmt.body {
+            assert_eq!(block.body.len(), 1);
+        } else {
+            panic!("Expected BlockStatement");
+        }
+    } else {
+        panic!("Expected WhileStatement node");
+    }
+}
+
+#[test]
+fn test_do_while_statement() {
+    let node = parse_first("do { x += 1; } while (x < 10);");
+
+    if let Node::DoWhileStatement(stmt) = node {
+        assert!(matches!(*stmt.body, Node::BlockStatement(_)));
+        assert!(matches!(*stmt.condition, Node::BinaryExpression(_)));
+    } else {
+        panic!("Expected DoWhileStatement node");
+    }
+}
+
+#[test]
+fn test_loop_with_break_and_continue() {
+    let node = parse_first("loop { continue; break; }");
+
+    if let Node::LoopStatement(stmt) = node {
+        if let Node::BlockStatement(block) = &*stmt.body {
+            assert!(matches!(&block.body[0], Node::ContinueStatement(s) if s.label.is_none()));
+            assert!(matches!(&block.body[1], Node::BreakStatement(s) if s.label.is_none()));
+        } else {
+            panic!("Expected BlockStatement");
+        }
+    } else {
+        panic!("Expected LoopStatement node");
+    }
+}
+
+#[test]
+fn test_labeled_loop_with_labeled_jumps() {
+    let node = parse_first(
+        r#"
+        outer: for i in 0 to 3 step 1 {
+            inner: while (true) {
+                continue outer;
+                break inner;
+            }
+        }
+    "#,
+    );
+
+    if let Node::LabeledStatement(stmt) = node {
+        assert_eq!(stmt.label, "outer");
+        let Node::ForStatement(for_stmt) = &*stmt.body else {
+            panic!("Expected ForStatement node");
+        };
+        let Node::BlockStatement(block) = &*for_stmt.body else {
+            panic!("Expected BlockStatement");
+        };
+        let Node::LabeledStatement(inner) = &block.body[0] else {
+            panic!("Expected LabeledStatement node");
+        };
+        assert_eq!(inner.label, "inner");
+        let Node::WhileStatement(while_stmt) = &*inner.body else {
+            panic!("Expected WhileStatement node");
+        };
+        let Node::BlockStatement(body) = &*while_stmt.body else {
+            panic!("Expected BlockStatement");
+        };
+        assert!(
+            matches!(&body.body[0], Node::ContinueStatement(s) if s.label.as_deref() == Some("outer"))
+        );
+        assert!(
+            matches!(&body.body[1], Node::BreakStatement(s) if s.label.as_deref() == Some("inner"))
+        );
+    } else {
+        panic!("Expected LabeledStatement node");
+    }
+}
+
+#[test]
+fn test_label_must_precede_loop() {
+    let mut parser = Parser::new("outer: print 1;");
+    let _ = parser.parse();
+    assert!(!parser.errors().is_empty());
+}
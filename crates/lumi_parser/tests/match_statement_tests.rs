@@ -0,0 +1,132 @@
+use lumi_ast::{ArrayPattern, Node, ObjectPattern, Pattern, PropertyPattern};
+use lumi_parser::Parser;
+
+#[test]
+fn test_switch_statement() {
+    let mut parser = Parser::new(
+        r#"
+        switch (x) {
+            case 1, 2:
+                print "small";
+                print "again";
+            case 3:
+                print "three";
+            default:
+                print "other";
+        }
+        "#,
+    );
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+    let Node::SwitchStatement(stmt) = &program.body[0] else {
+        panic!("Expected a switch statement");
+    };
+
+    assert_eq!(*stmt.discriminant, Node::Identifier("x".to_string()));
+    let cases: Vec<(Vec<Node>, usize)> = stmt
+        .cases
+        .iter()
+        .map(|case| (case.tests.clone(), case.body.len()))
+        .collect();
+    assert_eq!(
+        cases,
+        vec![
+            (vec![Node::Integer(1), Node::Integer(2)], 2),
+            (vec![Node::Integer(3)], 1),
+            (vec![], 1),
+        ]
+    );
+}
+
+#[test]
+fn test_match_expression_patterns() {
+    let mut parser = Parser::new(
+        r#"
+        let label -> match (value) {
+            0 => "zero",
+            -5 to 5 => "small",
+            "text" => "string",
+            [first, _, ...rest] => first,
+            { x, y: 0 } => x,
+            n if n > limit => "big",
+            _ => "other",
+        };
+        "#,
+    );
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+    let Node::VariableDeclaration(decl) = &program.body[0] else {
+        panic!("Expected a variable declaration");
+    };
+    let Some(Node::MatchExpression(expr)) = decl.declarations[0].init.as_deref() else {
+        panic!("Expected a match expression");
+    };
+
+    let patterns: Vec<Pattern> = expr.arms.iter().map(|arm| arm.pattern.clone()).collect();
+    assert_eq!(
+        patterns,
+        vec![
+            Pattern::Literal(Box::new(Node::Integer(0))),
+            Pattern::Range(Box::new(Node::Integer(-5)), Box::new(Node::Integer(5))),
+            Pattern::Literal(Box::new(Node::String("text".to_string()))),
+            Pattern::Array(ArrayPattern {
+                elements: vec![Pattern::Binding("first".to_string()), Pattern::Wildcard],
+                rest: Some(Box::new(Pattern::Binding("rest".to_string()))),
+            }),
+            Pattern::Object(ObjectPattern {
+                properties: vec![
+                    PropertyPattern {
+                        key: "x".to_string(),
+                        value: Pattern::Binding("x".to_string()),
+                    },
+                    PropertyPattern {
+                        key: "y".to_string(),
+                        value: Pattern::Literal(Box::new(Node::Integer(0))),
+                    },
+                ],
+            }),
+            Pattern::Binding("n".to_string()),
+            Pattern::Wildcard,
+        ]
+    );
+
+    // The guard ends at `=>` instead of starting an arrow function
+    let guards: Vec<bool> = expr.arms.iter().map(|arm| arm.guard.is_some()).collect();
+    assert_eq!(guards, vec![false, false, false, false, false, true, false]);
+    assert!(matches!(
+        expr.arms[5].guard.as_deref(),
+        Some(Node::BinaryExpression(_))
+    ));
+}
+
+#[test]
+fn test_match_is_contextual() {
+    let mut parser = Parser::new("let match -> 1; match(2);");
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+    assert!(matches!(program.body[0], Node::VariableDeclaration(_)));
+    let Node::ExpressionStatement(stmt) = &program.body[1] else {
+        panic!("Expected an expression statement");
+    };
+    assert!(matches!(*stmt.expression, Node::CallExpression(_)));
+}
+
+#[test]
+fn test_invalid_switch_and_match() {
+    for source in [
+        "switch (x) { print x; }",
+        "switch (x) { default: print 1; default: print 2; }",
+        "let y -> match (x) { 1 \"one\" };",
+        "let y -> match (x) { 1 + 2 => 3 };",
+    ] {
+        let mut parser = Parser::new(source);
+        let _ = parser.parse();
+        assert!(!parser.errors().is_empty(), "{source}");
+    }
+}
@@ -1,4 +1,4 @@
-use lumi_ast::Node;
+use lumi_ast::{Node, PropertyKey};
 use lumi_parser::Parser;
 
 #[test]
@@ -29,3 +29,319 @@ fn test_fn_call_expression() {
         }
     }
 }
+
+fn parse_expression(source: &str) -> Node {
+    let mut parser = Parser::new(source);
+    let result = parser.parse();
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+
+    let Ok(Node::Program(mut program)) = result else {
+        panic!("Expected Program node");
+    };
+    let Node::ExpressionStatement(stmt) = program.body.remove(0) else {
+        panic!("Expected ExpressionStatement node");
+    };
+    *stmt.expression
+}
+
+#[test]
+fn test_member_expressions() {
+    let Node::MemberExpression(outer) = parse_expression("obj.items[i];") else {
+        panic!("Expected MemberExpression node");
+    };
+    assert!(outer.computed);
+    assert!(!outer.optional);
+    assert_eq!(*outer.property, Node::Identifier("i".to_string()));
+
+    let Node::MemberExpression(inner) = &*outer.object else {
+        panic!("Expected MemberExpression node");
+    };
+    assert!(!inner.computed);
+    assert_eq!(*inner.object, Node::Identifier("obj".to_string()));
+    assert_eq!(*inner.property, Node::Identifier("items".to_string()));
+}
+
+#[test]
+fn test_optional_chaining() {
+    let Node::MemberExpression(outer) = parse_expression(r#"a?.b?.["c"];"#) else {
+        panic!("Expected MemberExpression node");
+    };
+    assert!(outer.optional && outer.computed);
+    assert_eq!(*outer.property, Node::String("c".to_string()));
+
+    let Node::MemberExpression(inner) = &*outer.object else {
+        panic!("Expected MemberExpression node");
+    };
+    assert!(inner.optional && !inner.computed);
+}
+
+#[test]
+fn test_keyword_property_names() {
+    let Node::MemberExpression(outer) = parse_expression("o.class?.new;") else {
+        panic!("Expected MemberExpression node");
+    };
+    assert!(outer.optional && !outer.computed);
+    assert_eq!(*outer.property, Node::Identifier("new".to_string()));
+
+    let Node::MemberExpression(inner) = &*outer.object else {
+        panic!("Expected MemberExpression node");
+    };
+    assert_eq!(*inner.property, Node::Identifier("class".to_string()));
+}
+
+#[test]
+fn test_member_assignment() {
+    let Node::AssignmentExpression(assign) = parse_expression(r#"obj["key"] = 1;"#) else {
+        panic!("Expected AssignmentExpression node");
+    };
+    assert!(matches!(*assign.left, Node::MemberExpression(_)));
+}
+
+#[test]
+fn test_optional_chain_is_not_assignable() {
+    let mut parser = Parser::new("a?.b = 1;");
+    let _ = parser.parse();
+    assert!(!parser.errors().is_empty());
+}
+
+#[test]
+fn test_object_literal() {
+    let mut parser =
+        Parser::new(r#"let o -> { name: "lumi", "quoted key": 1, [key]: 2, count, };"#);
+    let result = parser.parse();
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+
+    let Ok(Node::Program(program)) = result else {
+        panic!("Expected Program node");
+    };
+    let Node::VariableDeclaration(decl) = &program.body[0] else {
+        panic!("Expected VariableDeclaration node");
+    };
+    let Some(Node::ObjectLiteral(obj)) = decl.declarations[0].init.as_deref() else {
+        panic!("Expected ObjectLiteral node");
+    };
+
+    let keys: Vec<&PropertyKey> = obj.properties.iter().map(|p| &p.key).collect();
+    assert_eq!(
+        keys,
+        vec![
+            &PropertyKey::Named("name".to_string()),
+            &PropertyKey::Named("quoted key".to_string()),
+            &PropertyKey::Computed(Box::new(Node::Identifier("key".to_string()))),
+            &PropertyKey::Named("count".to_string()),
+        ]
+    );
+    assert!(obj.properties[3].shorthand);
+    assert_eq!(
+        obj.properties[3].value,
+        Node::Identifier("count".to_string())
+    );
+}
+
+#[test]
+fn test_keyword_object_keys() {
+    let Node::ObjectLiteral(obj) = parse_expression("({ new: 3, class: 1 });") else {
+        panic!("Expected ObjectLiteral node");
+    };
+
+    let keys: Vec<&PropertyKey> = obj.properties.iter().map(|p| &p.key).collect();
+    assert_eq!(
+        keys,
+        vec![
+            &PropertyKey::Named("new".to_string()),
+            &PropertyKey::Named("class".to_string()),
+        ]
+    );
+    assert_eq!(obj.properties[0].value, Node::Integer(3));
+
+    // A keyword can't be used as a shorthand property
+    let mut parser = Parser::new("let o -> { class };");
+    let _ = parser.parse();
+    assert!(!parser.errors().is_empty());
+}
+
+#[test]
+fn test_brace_at_statement_start_is_a_block() {
+    let mut parser = Parser::new("{ print 1; }");
+    let result = parser.parse();
+
+    let Ok(Node::Program(program)) = result else {
+        panic!("Expected Program node");
+    };
+    assert!(matches!(program.body[0], Node::BlockStatement(_)));
+}
+
+#[test]
+fn test_array_literal_with_holes_and_spread() {
+    let Node::ArrayLiteral(array) = parse_expression("[1, , ...rest, 4,];") else {
+        panic!("Expected ArrayLiteral node");
+    };
+
+    assert_eq!(array.elements.len(), 4);
+    assert_eq!(array.elements[0], Some(Node::Integer(1)));
+    assert_eq!(array.elements[1], None);
+    let Some(Node::SpreadElement(spread)) = &array.elements[2] else {
+        panic!("Expected SpreadElement node");
+    };
+    assert_eq!(*spread.argument, Node::Identifier("rest".to_string()));
+    assert_eq!(array.elements[3], Some(Node::Integer(4)));
+}
+
+#[test]
+fn test_arrow_functions() {
+    let Node::FunctionExpression(arrow) = parse_expression("(a, b) => a + b;") else {
+        panic!("Expected FunctionExpression node");
+    };
+    assert!(arrow.is_arrow);
+    let names: Vec<String> = arrow.params.iter().map(|p| p.name.name()).collect();
+    assert_eq!(names, vec!["a", "b"]);
+    assert!(matches!(*arrow.body, Node::BinaryExpression(_)));
+
+    let Node::FunctionExpression(arrow) = parse_expression("x => { return x; };") else {
+        panic!("Expected FunctionExpression node");
+    };
+    assert_eq!(arrow.params.len(), 1);
+    assert_eq!(*arrow.params[0].name, Node::Identifier("x".to_string()));
+    assert!(matches!(*arrow.body, Node::BlockStatement(_)));
+
+    let Node::FunctionExpression(arrow) = parse_expression("() => 1;") else {
+        panic!("Expected FunctionExpression node");
+    };
+    assert!(arrow.params.is_empty());
+}
+
+#[test]
+fn test_typed_arrow_function() {
+    let Node::FunctionExpression(arrow) = parse_expression("(a: int, b): int => a;") else {
+        panic!("Expected FunctionExpression node");
+    };
+    assert_eq!(
+        arrow.params[0].param_type.as_deref(),
+        Some(&Node::Identifier("int".to_string()))
+    );
+    assert!(arrow.params[1].param_type.is_none());
+    assert_eq!(
+        arrow.return_type.as_deref(),
+        Some(&Node::Identifier("int".to_string()))
+    );
+}
+
+#[test]
+fn test_function_expression() {
+    let mut parser = Parser::new("let f -> fn (a) { return a; };");
+    let result = parser.parse();
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+
+    let Ok(Node::Program(program)) = result else {
+        panic!("Expected Program node");
+    };
+    let Node::VariableDeclaration(decl) = &program.body[0] else {
+        panic!("Expected VariableDeclaration node");
+    };
+    let Some(Node::FunctionExpression(func)) = decl.declarations[0].init.as_deref() else {
+        panic!("Expected FunctionExpression node");
+    };
+    assert!(!func.is_arrow);
+    assert!(func.id.is_none());
+    assert_eq!(func.params.len(), 1);
+}
+
+#[test]
+fn test_parenthesized_expression_is_not_an_arrow() {
+    let Node::BinaryExpression(expr) = parse_expression("(a + b) * c;") else {
+        panic!("Expected BinaryExpression node");
+    };
+    assert_eq!(expr.operator, "*");
+    assert!(matches!(*expr.left, Node::BinaryExpression(_)));
+}
+
+#[test]
+fn test_calling_a_call_result() {
+    let Node::CallExpression(outer) = parse_expression("makeAdder(1)(2);") else {
+        panic!("Expected CallExpression node");
+    };
+    assert_eq!(outer.arguments, vec![Node::Integer(2)]);
+    assert!(matches!(*outer.callee, Node::CallExpression(_)));
+}
+
+#[test]
+fn test_conditional_expression() {
+    let Node::ConditionalExpression(expr) = parse_expression("a ? b : c ? d : e;") else {
+        panic!("Expected ConditionalExpression node");
+    };
+    assert_eq!(*expr.test, Node::Identifier("a".to_string()));
+    assert_eq!(*expr.consequent, Node::Identifier("b".to_string()));
+    assert!(matches!(*expr.alternate, Node::ConditionalExpression(_)));
+
+    let Node::AssignmentExpression(assign) = parse_expression("x = a ? 1 : 2;") else {
+        panic!("Expected AssignmentExpression node");
+    };
+    assert!(matches!(*assign.right, Node::ConditionalExpression(_)));
+}
+
+#[test]
+fn test_nullish_coalescing() {
+    let Node::ConditionalExpression(expr) = parse_expression("a ?? b ? c : d;") else {
+        panic!("Expected ConditionalExpression node");
+    };
+    let Node::LogicalExpression(test) = &*expr.test else {
+        panic!("Expected LogicalExpression node");
+    };
+    assert_eq!(test.operator, "??");
+
+    let Node::LogicalExpression(chain) = parse_expression("a ?? b ?? c;") else {
+        panic!("Expected LogicalExpression node");
+    };
+    assert!(matches!(*chain.left, Node::LogicalExpression(_)));
+
+    let Node::AssignmentExpression(assign) = parse_expression("a ??= b ?? 1;") else {
+        panic!("Expected AssignmentExpression node");
+    };
+    assert_eq!(assign.operator, "??=");
+    assert!(matches!(*assign.right, Node::LogicalExpression(_)));
+}
+
+#[test]
+fn test_update_expressions() {
+    let Node::UnaryExpression(postfix) = parse_expression("a.b++;") else {
+        panic!("Expected UnaryExpression node");
+    };
+    assert_eq!(postfix.operator, "++");
+    assert!(!postfix.prefix);
+    assert!(matches!(*postfix.argument, Node::MemberExpression(_)));
+
+    let Node::UnaryExpression(prefix) = parse_expression("--xs[0];") else {
+        panic!("Expected UnaryExpression node");
+    };
+    assert_eq!(prefix.operator, "--");
+    assert!(prefix.prefix);
+
+    let mut parser = Parser::new("++1;");
+    let _ = parser.parse();
+    assert!(!parser.errors().is_empty());
+}
+
+#[test]
+fn test_exponent_is_right_associative() {
+    let Node::BinaryExpression(expr) = parse_expression("2 ** 3 ** 2;") else {
+        panic!("Expected BinaryExpression node");
+    };
+    assert_eq!(expr.operator, "**");
+    assert_eq!(*expr.left, Node::Integer(2));
+    assert!(matches!(*expr.right, Node::BinaryExpression(_)));
+
+    // Binds tighter than multiplication and than a unary minus on its left
+    let Node::BinaryExpression(product) = parse_expression("2 * 3 ** 2;") else {
+        panic!("Expected BinaryExpression node");
+    };
+    assert_eq!(product.operator, "*");
+    let Node::UnaryExpression(negated) = parse_expression("-2 ** 2;") else {
+        panic!("Expected UnaryExpression node");
+    };
+    assert!(matches!(*negated.argument, Node::BinaryExpression(_)));
+
+    let Node::AssignmentExpression(assign) = parse_expression("x **= 2;") else {
+        panic!("Expected AssignmentExpression node");
+    };
+    assert_eq!(assign.operator, "**=");
+}
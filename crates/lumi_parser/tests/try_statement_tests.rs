@@ -0,0 +1,65 @@
+use lumi_ast::Node;
+use lumi_parser::Parser;
+
+#[test]
+fn test_try_catch_finally() {
+    let mut parser = Parser::new(
+        r#"
+        try { risky(); } catch (e) { print e; } finally { done(); }
+        try { risky(); } catch { recover(); }
+        try { risky(); } finally { done(); }
+        "#,
+    );
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+
+    let clauses: Vec<(bool, Option<String>, bool)> = program
+        .body
+        .iter()
+        .map(|stmt| match stmt {
+            Node::TryStatement(stmt) => (
+                stmt.handler.is_some(),
+                stmt.handler
+                    .as_ref()
+                    .and_then(|handler| handler.param.as_ref().map(|param| param.name())),
+                stmt.finalizer.is_some(),
+            ),
+            _ => panic!("Expected a try statement"),
+        })
+        .collect();
+    assert_eq!(
+        clauses,
+        vec![
+            (true, Some("e".to_string()), true),
+            (true, None, false),
+            (false, None, true),
+        ]
+    );
+}
+
+#[test]
+fn test_throw_statement() {
+    let mut parser = Parser::new(r#"throw "not found";"#);
+    let Node::Program(program) = parser.parse().unwrap() else {
+        panic!("Expected a program");
+    };
+    let Node::ThrowStatement(stmt) = &program.body[0] else {
+        panic!("Expected a throw statement");
+    };
+    assert_eq!(*stmt.argument, Node::String("not found".to_string()));
+}
+
+#[test]
+fn test_invalid_try_statements() {
+    for source in [
+        "try { risky(); }",
+        "try risky(); catch (e) {}",
+        "try {} catch (e) recover();",
+        "throw;",
+    ] {
+        let mut parser = Parser::new(source);
+        let _ = parser.parse();
+        assert!(!parser.errors().is_empty(), "{source}");
+    }
+}
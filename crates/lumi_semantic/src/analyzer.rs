@@ -1,8 +1,10 @@
+use std::collections::{BTreeMap, HashMap};
+
 use crate::{
     SemanticResult,
     errors::SemanticError,
     scope::{Scope, ScopeType},
-    types::{Type, TypeEnvironment},
+    types::{EnumVariants, Type, TypeEnvironment},
 };
 use lumi_ast::{Node, Span, node};
 
@@ -15,6 +17,28 @@ pub struct SemanticAnalyzer {
 
     /// Type environment for tracking variable types
     type_env: TypeEnvironment,
+
+    /// Labels of the loops enclosing the current statement, innermost last
+    loop_labels: Vec<Option<String>>,
+
+    /// Switch statements enclosing the current statement, an unlabeled `break` leaves them
+    switch_depth: usize,
+
+    /// Types returned by the functions being analyzed, innermost last. A bare `return;`
+    /// is `None`
+    function_returns: Vec<Vec<Option<Type>>>,
+
+    /// Whether the classes being analyzed extend another class, innermost last
+    classes: Vec<bool>,
+
+    /// Variants of the declared enums by enum name
+    enums: HashMap<String, EnumVariants>,
+}
+
+impl Default for SemanticAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SemanticAnalyzer {
@@ -24,6 +48,11 @@ impl SemanticAnalyzer {
             scope_stack: Vec::new(),
             errors: Vec::new(),
             type_env: TypeEnvironment::new(),
+            loop_labels: Vec::new(),
+            switch_depth: 0,
+            function_returns: Vec::new(),
+            classes: Vec::new(),
+            enums: HashMap::new(),
         };
 
         analyzer.scope_stack.push(Scope::new_global());
@@ -50,13 +79,45 @@ impl SemanticAnalyzer {
             Node::IfStatement(stmt) => self.visit_if_statement(stmt),
             Node::BlockStatement(stmt) => self.visit_block_statement(stmt),
             Node::FunctionDeclaration(fn_decl) => self.visit_function_declaration(fn_decl),
+            Node::FunctionExpression(func) => self.visit_function_expression(func),
             Node::CallExpression(expr) => self.visit_call_expression(expr),
+            Node::NamedArgument(arg) => self.visit_node(&arg.value),
+            Node::ConditionalExpression(expr) => self.visit_conditional_expression(expr),
+            Node::LogicalExpression(expr) => self.visit_logical_expression(expr),
+            Node::UnaryExpression(expr) => self.visit_unary_expression(expr),
             Node::PrintStatement(stmt) => self.visit_print_statement(stmt),
+            Node::InterpolatedString(s) => self.visit_interpolated_string(s),
+            Node::ForStatement(stmt) => self.visit_for_statement(stmt, None),
+            Node::ForEachStatement(stmt) => self.visit_for_each_statement(stmt, None),
+            Node::WhileStatement(stmt) => self.visit_while_statement(stmt, None),
+            Node::DoWhileStatement(stmt) => self.visit_do_while_statement(stmt, None),
+            Node::LoopStatement(stmt) => self.visit_loop_body(&stmt.body, None),
+            Node::LabeledStatement(stmt) => self.visit_labeled_statement(stmt),
+            Node::MemberExpression(expr) => self.visit_member_expression(expr),
+            Node::ObjectLiteral(obj) => self.visit_object_literal(obj),
+            Node::ArrayLiteral(array) => self.visit_array_literal(array),
+            Node::SpreadElement(spread) => self.visit_spread_element(spread),
+            Node::ReturnStatement(stmt) => self.visit_return_statement(stmt),
+            Node::ClassDeclaration(class) => self.visit_class_declaration(class),
+            Node::EnumDeclaration(decl) => self.visit_enum_declaration(decl),
+            Node::ThrowStatement(stmt) => self.visit_node(&stmt.argument),
+            Node::TryStatement(stmt) => self.visit_try_statement(stmt),
+            Node::SwitchStatement(stmt) => self.visit_switch_statement(stmt),
+            Node::MatchExpression(expr) => self.visit_match_expression(expr),
+            Node::NewExpression(expr) => self.visit_new_expression(expr),
+            Node::This => self.visit_this(),
+            Node::Super => self.visit_super(),
+            Node::BreakStatement(stmt) => self.visit_loop_jump("break", &stmt.label, &stmt.span),
+            Node::ContinueStatement(stmt) => {
+                self.visit_loop_jump("continue", &stmt.label, &stmt.span)
+            }
             // Node::BinaryExpression(expr) => self.visit_binary_expression(expr),
             Node::String(_) => Ok(Type::String),
             Node::Boolean(_) => Ok(Type::Boolean),
+            Node::Integer(_) => Ok(Type::Integer),
             Node::Number(_) => Ok(Type::Number),
-            // Node::Null => self.visit_null(),
+            Node::BigInt(_) => Ok(Type::BigInt),
+            Node::Null => Ok(Type::Null),
             Node::Identifier(i) => self.vist_identifier(i),
             // Node::Undefined => self.visit_undefined(),
             _ => Ok(Type::Undefined), // Temporary
@@ -79,7 +140,7 @@ impl SemanticAnalyzer {
         } else {
             self.errors.push(SemanticError::UndeclaredVariable {
                 name: id.to_string(),
-                position: None,
+                span: None,
             });
             Ok(Type::Undefined)
         }
@@ -92,20 +153,40 @@ impl SemanticAnalyzer {
         let is_const = decl.kind == "const";
 
         for var_decl in &decl.declarations {
+            if let Node::Pattern(pattern) = &*var_decl.var_name {
+                let mut value_type = match &var_decl.init {
+                    Some(init) => self.visit_node(init)?,
+                    None => Type::Undefined,
+                };
+                if let Some(var_type_node) = &var_decl.var_type {
+                    let declared = self.get_type_from_annotation(var_type_node, &decl.span)?;
+                    if is_known(&value_type) && !declared.is_assignable_from(&value_type) {
+                        self.errors.push(SemanticError::TypeMismatch {
+                            expected: declared.to_string(),
+                            found: value_type.to_string(),
+                            span: decl.span.clone(),
+                        });
+                    }
+                    value_type = declared;
+                }
+                self.destructure(pattern, &value_type, Some(!is_const), &decl.span)?;
+                continue;
+            }
             if let Node::Identifier(var_name) = &*var_decl.var_name {
                 // Get the type
                 let var_type = if let Some(var_type_node) = &var_decl.var_type {
                     // Get type from the type annotation
-                    let var_declared_type = self.get_type_from_annotation(var_type_node, decl)?;
+                    let var_declared_type =
+                        self.get_type_from_annotation(var_type_node, &decl.span)?;
                     // Check for mismatch between type annotation and initializer
                     if let Some(init) = &var_decl.init {
                         let init_type = self.visit_node(init)?;
-                        if init_type != var_declared_type {
+                        if !var_declared_type.is_assignable_from(&init_type) {
                             // Have to return here otherwise we still declare the variable even though there's a type mismatch
                             return Err(SemanticError::TypeMismatch {
                                 expected: var_declared_type.to_string(),
                                 found: init_type.to_string(),
-                                position: decl.span.as_ref().map(|s| s.start.clone()),
+                                span: decl.span.clone(),
                             });
                         }
                     }
@@ -118,7 +199,7 @@ impl SemanticAnalyzer {
                 } else {
                     return Err(SemanticError::InvalidVariableDeclaration {
                         name: var_name.to_string(),
-                        position: decl.span.as_ref().map(|s| s.start.clone()),
+                        span: decl.span.clone(),
                     });
                 };
 
@@ -129,7 +210,7 @@ impl SemanticAnalyzer {
                 if current_scope.is_variable_declared_in_current_scope(var_name) {
                     self.errors.push(SemanticError::DuplicateDeclaration {
                         name: var_name.clone(),
-                        position: decl.span.as_ref().map(|s| s.start.clone()),
+                        span: decl.span.clone(),
                     });
                     continue; // Skip further processing for this variable
                 }
@@ -155,64 +236,445 @@ impl SemanticAnalyzer {
         &mut self,
         func: &node::FunctionDeclaration,
     ) -> SemanticResult<Type> {
-        let func_name = if let Some(id) = &func.id {
-            if let Node::Identifier(name) = &**id {
-                name.clone()
-            } else {
-                return Ok(Type::Undefined);
+        let func_name = match func.id.as_deref() {
+            Some(Node::Identifier(name)) => Some(name.clone()),
+            Some(_) => return Ok(Type::Undefined),
+            None => None,
+        };
+
+        let func_type = self.visit_function(
+            func_name.as_deref(),
+            &func.params,
+            func.return_type.as_deref(),
+            &func.body,
+            &func.span,
+        )?;
+
+        if let Some(func_name) = func_name {
+            let current_scope = self.scope_stack.last_mut().unwrap();
+            let line_number = func.span.as_ref().map(|s| s.start.line).unwrap_or(1);
+            if let Type::Function {
+                params,
+                return_type,
+                ..
+            } = &func_type
+            {
+                current_scope.declare_function(
+                    &func_name,
+                    func.params
+                        .iter()
+                        .map(|param| param.name.as_identifier().unwrap_or_default().to_string())
+                        .collect(),
+                    params.clone(),
+                    (**return_type).clone(),
+                    false,
+                    line_number,
+                );
             }
-        } else {
-            return Ok(Type::Undefined);
+            current_scope.declare_variable(func_name, func_type.clone(), line_number);
+        }
+
+        Ok(func_type)
+    }
+
+    /// Visit function expression, the name of `fn name() {}` is only visible inside its body
+    fn visit_function_expression(
+        &mut self,
+        func: &node::FunctionExpression,
+    ) -> SemanticResult<Type> {
+        let func_name = match func.id.as_deref() {
+            Some(Node::Identifier(name)) => Some(name.as_str()),
+            _ => None,
         };
+        self.visit_function(
+            func_name,
+            &func.params,
+            func.return_type.as_deref(),
+            &func.body,
+            &func.span,
+        )
+    }
 
-        let _current_scope = self.scope_stack.last().unwrap();
-        let function_scope = Scope::new();
+    /// Analyze a function body in its own scope, which sees the variables around the
+    /// function so closures can use them
+    fn visit_function(
+        &mut self,
+        name: Option<&str>,
+        params: &[node::Parameter],
+        return_annotation: Option<&Node>,
+        body: &Node,
+        span: &Option<Span>,
+    ) -> SemanticResult<Type> {
+        let line_number = span.as_ref().map(|s| s.start.line).unwrap_or(1);
+        let enclosing_scope = self.scope_stack.last().unwrap().clone();
+        let mut function_scope = Scope::new_child(enclosing_scope, ScopeType::Function);
+
+        // Untyped parameters accept any argument
+        let mut annotations = Vec::with_capacity(params.len());
+        for param in params {
+            let param_type = match &param.param_type {
+                Some(annotation) => Some(self.get_type_from_annotation(annotation, &param.span)?),
+                None => None,
+            };
+            annotations.push(param_type);
+        }
+        let declared_return = match return_annotation {
+            Some(annotation) => Some(self.get_type_from_annotation(annotation, span)?),
+            None => None,
+        };
+
+        // A recursive call is made before an unannotated return type is known
+        if let Some(name) = name {
+            let signature = annotations
+                .iter()
+                .map(|t| t.clone().unwrap_or(Type::Any))
+                .collect();
+            function_scope.declare_variable(
+                name.to_string(),
+                function_type(
+                    params,
+                    signature,
+                    declared_return.clone().unwrap_or(Type::Unknown),
+                ),
+                line_number,
+            );
+        }
         self.scope_stack.push(function_scope);
 
-        for param in &func.params {
-            if let Node::Identifier(param_name) = param {
-                let current_scope = self.scope_stack.last_mut().unwrap();
-                let line_number = func.span.as_ref().map(|s| s.start.line).unwrap_or(1);
-                current_scope.declare_variable(param_name.clone(), Type::Undefined, line_number);
+        // Defaults are analyzed in the function scope, so they can use earlier parameters
+        let mut signature = Vec::with_capacity(params.len());
+        for (param, annotation) in params.iter().zip(annotations) {
+            let default_type = match &param.default_value {
+                Some(default) => match self.visit_node(default) {
+                    Ok(default_type) => Some(default_type),
+                    Err(err) => {
+                        self.scope_stack.pop();
+                        return Err(err);
+                    }
+                },
+                None => None,
+            };
+            if let (Some(expected), Some(found)) = (&annotation, &default_type)
+                && is_known(found)
+                && !expected.is_assignable_from(found)
+            {
+                self.errors.push(SemanticError::TypeMismatch {
+                    expected: expected.to_string(),
+                    found: found.to_string(),
+                    span: param.span.clone(),
+                });
+            }
+
+            let param_type = annotation.or(default_type.filter(is_known));
+            let local_type = match (param.is_rest, &param_type) {
+                (true, _) => Type::Array(Box::new(param_type.clone().unwrap_or(Type::Any))),
+                (false, Some(param_type)) => param_type.clone(),
+                (false, None) => Type::Any,
+            };
+            signature.push(param_type.unwrap_or(Type::Any));
+            match &*param.name {
+                Node::Pattern(pattern) => {
+                    if let Err(err) =
+                        self.destructure(pattern, &local_type, Some(true), &param.span)
+                    {
+                        self.scope_stack.pop();
+                        return Err(err);
+                    }
+                }
+                name => self.scope_stack.last_mut().unwrap().declare_variable(
+                    name.name(),
+                    local_type,
+                    line_number,
+                ),
             }
         }
 
-        let return_type = self.visit_node(&func.body)?;
+        // Parameters shadow the declared types around the function, and its own
+        // declarations end with it
+        let mut function_types = self.type_env.clone();
+        for (param, param_type) in params.iter().zip(&signature) {
+            match &*param.name {
+                Node::Pattern(pattern) => {
+                    for name in pattern.bindings() {
+                        function_types.remove(name);
+                    }
+                }
+                name if param.param_type.is_some() => {
+                    let declared = match param.is_rest {
+                        true => Type::Array(Box::new(param_type.clone())),
+                        false => param_type.clone(),
+                    };
+                    function_types.declare(&name.name(), declared);
+                }
+                name => function_types.remove(&name.name()),
+            }
+        }
+        let enclosing_types = std::mem::replace(&mut self.type_env, function_types);
 
+        // Loops and switches around the function can't be left from inside it
+        let enclosing_loops = std::mem::take(&mut self.loop_labels);
+        let enclosing_switches = std::mem::take(&mut self.switch_depth);
+        self.function_returns.push(Vec::new());
+        let body_result = self.visit_node(body);
+        let mut returns = self.function_returns.pop().unwrap_or_default();
+        self.loop_labels = enclosing_loops;
+        self.switch_depth = enclosing_switches;
+        self.type_env = enclosing_types;
         self.scope_stack.pop();
+        let body_type = body_result?;
 
-        let current_scope = self.scope_stack.last_mut().unwrap();
-        let line_number = func.span.as_ref().map(|s| s.start.line).unwrap_or(1);
-        current_scope.declare_variable(
-            func_name,
-            Type::Function {
-                params: vec![],
-                return_type: Box::new(Type::Unknown),
-            },
-            line_number,
-        );
+        let is_block = matches!(body, Node::BlockStatement(_));
+        if !is_block {
+            // The body of an arrow like `x => x * 2` is its return value
+            returns.push(Some(body_type));
+        }
+
+        let return_type = match declared_return {
+            Some(declared) => {
+                for found in &returns {
+                    // A bare `return;` only fits a function that returns undefined
+                    let mismatch = match found {
+                        Some(found) => is_known(found) && !declared.is_assignable_from(found),
+                        None => declared != Type::Undefined,
+                    };
+                    if mismatch {
+                        self.errors.push(SemanticError::TypeMismatch {
+                            expected: declared.to_string(),
+                            found: found.as_ref().unwrap_or(&Type::Undefined).to_string(),
+                            span: span.clone(),
+                        });
+                    }
+                }
+                if is_block && declared != Type::Undefined && !always_returns(body) {
+                    self.errors.push(SemanticError::MissingReturn {
+                        name: name.unwrap_or("<anonymous>").to_string(),
+                        span: span.clone(),
+                    });
+                }
+                declared
+            }
+            None => {
+                let returns: Vec<Type> = returns
+                    .into_iter()
+                    .map(|t| t.unwrap_or(Type::Undefined))
+                    .collect();
+                if is_block
+                    && returns.iter().any(|t| *t != Type::Undefined)
+                    && !always_returns(body)
+                {
+                    self.errors.push(SemanticError::MissingReturn {
+                        name: name.unwrap_or("<anonymous>").to_string(),
+                        span: span.clone(),
+                    });
+                }
+                returns
+                    .into_iter()
+                    .reduce(|a, b| a.common_type(&b))
+                    .unwrap_or(Type::Undefined)
+            }
+        };
 
-        Ok(Type::Function {
-            params: vec![],
-            return_type: Box::new(return_type),
-        })
+        Ok(function_type(params, signature, return_type))
     }
 
     fn visit_call_expression(&mut self, expr: &node::CallExpression) -> SemanticResult<Type> {
         let callee_type = self.visit_node(&expr.callee)?;
 
+        let mut arg_types = Vec::with_capacity(expr.arguments.len());
         for arg in &expr.arguments {
-            self.visit_node(arg)?;
+            arg_types.push(self.visit_node(arg)?);
         }
 
-        if !matches!(callee_type, Type::Function { .. }) {
-            self.errors.push(SemanticError::TypeMismatch {
-                expected: "function".to_string(),
-                found: format!("{callee_type:?}"),
-                position: None,
+        match callee_type {
+            Type::Function {
+                params,
+                required,
+                rest,
+                return_type,
+            } => {
+                let function_name = match &*expr.callee {
+                    Node::Identifier(name) => name.clone(),
+                    Node::MemberExpression(member) if !member.computed => member.property.name(),
+                    _ => "<anonymous>".to_string(),
+                };
+                // Named arguments are matched by the parameter names of a declared function
+                let param_names = match &*expr.callee {
+                    Node::Identifier(name) => self
+                        .scope_stack
+                        .last()
+                        .unwrap()
+                        .get_function(name)
+                        .map(|info| info.param_names.clone()),
+                    _ => None,
+                };
+                // A spread argument may fill any number of parameters
+                let has_spread = expr
+                    .arguments
+                    .iter()
+                    .any(|arg| matches!(arg, Node::SpreadElement(_)));
+
+                let mut passed = vec![false; params.len()];
+                let mut positional = 0;
+                let mut unmatched_names = false;
+                for (arg, found) in expr.arguments.iter().zip(&arg_types) {
+                    let expected = match arg {
+                        Node::NamedArgument(named) => {
+                            let Some(names) = &param_names else {
+                                unmatched_names = true;
+                                continue;
+                            };
+                            let name = named.name.name();
+                            match names.iter().position(|n| *n == name) {
+                                Some(idx) if idx < params.len() && !passed[idx] => {
+                                    passed[idx] = true;
+                                    &params[idx]
+                                }
+                                _ => {
+                                    self.errors.push(SemanticError::InvalidNamedArgument {
+                                        function_name: function_name.clone(),
+                                        name,
+                                        span: named.span.clone(),
+                                    });
+                                    continue;
+                                }
+                            }
+                        }
+                        _ if positional < params.len() => {
+                            passed[positional] = true;
+                            positional += 1;
+                            &params[positional - 1]
+                        }
+                        _ => {
+                            positional += 1;
+                            match &rest {
+                                Some(rest) => rest,
+                                None => continue,
+                            }
+                        }
+                    };
+                    if !has_spread
+                        && is_known(expected)
+                        && is_known(found)
+                        && !expected.is_assignable_from(found)
+                    {
+                        self.errors.push(SemanticError::TypeMismatch {
+                            expected: expected.to_string(),
+                            found: found.to_string(),
+                            span: expr.span.clone(),
+                        });
+                    }
+                }
+
+                if has_spread {
+                    // The number of arguments is only known at runtime
+                } else if rest.is_none() && positional > params.len() {
+                    self.errors.push(SemanticError::WrongArgumentCount {
+                        function_name,
+                        expected: params.len(),
+                        found: positional,
+                        span: expr.span.clone(),
+                    });
+                } else if !unmatched_names && passed[..required].contains(&false) {
+                    self.errors.push(SemanticError::WrongArgumentCount {
+                        function_name,
+                        expected: required,
+                        found: arg_types.len(),
+                        span: expr.span.clone(),
+                    });
+                }
+                Ok(*return_type)
+            }
+            // Parameters and results of unanalyzed expressions may hold a function
+            Type::Undefined | Type::Unknown | Type::Any => Ok(Type::Unknown),
+            // Which function of a union is called is only known at runtime, so its
+            // arguments aren't checked
+            Type::Union(members)
+                if members
+                    .iter()
+                    .all(|member| matches!(member, Type::Function { .. }) || !is_known(member)) =>
+            {
+                let return_type = members
+                    .into_iter()
+                    .map(|member| match member {
+                        Type::Function { return_type, .. } => *return_type,
+                        _ => Type::Unknown,
+                    })
+                    .reduce(|a, b| a.common_type(&b))
+                    .unwrap_or(Type::Unknown);
+                Ok(return_type)
+            }
+            _ => {
+                self.errors.push(SemanticError::TypeMismatch {
+                    expected: "function".to_string(),
+                    found: callee_type.to_string(),
+                    span: expr.callee.span().or(expr.span.as_ref()).cloned(),
+                });
+                Ok(Type::Unknown)
+            }
+        }
+    }
+
+    /// The result of `test ? a : b` has the common type of both branches
+    fn visit_conditional_expression(
+        &mut self,
+        expr: &node::ConditionalExpression,
+    ) -> SemanticResult<Type> {
+        self.visit_node(&expr.test)?;
+        let consequent = self.visit_node(&expr.consequent)?;
+        let alternate = self.visit_node(&expr.alternate)?;
+
+        Ok(consequent.common_type(&alternate))
+    }
+
+    /// `a ?? b` only falls back to `b` when `a` is null or undefined
+    fn visit_logical_expression(&mut self, expr: &node::LogicalExpression) -> SemanticResult<Type> {
+        let left = self.visit_node(&expr.left)?;
+        let right = self.visit_node(&expr.right)?;
+
+        if expr.operator != "??" {
+            return Ok(left.common_type(&right));
+        }
+        let result = match left {
+            Type::Null | Type::Undefined => right,
+            Type::Union(types) => types
+                .into_iter()
+                .filter(|t| !matches!(t, Type::Null | Type::Undefined))
+                .fold(right, |acc, t| t.common_type(&acc)),
+            left => left.common_type(&right),
+        };
+        Ok(result)
+    }
+
+    /// `+`, `-`, `++` and `--` need a number, `++` and `--` also a mutable variable
+    fn visit_unary_expression(&mut self, expr: &node::UnaryExpression) -> SemanticResult<Type> {
+        let operand = self.visit_node(&expr.argument)?;
+
+        if matches!(expr.operator.as_str(), "++" | "--")
+            && let Node::Identifier(name) = &*expr.argument
+        {
+            let current_scope = self.scope_stack.last().unwrap();
+            if current_scope
+                .get_variable(name)
+                .is_some_and(|var_info| !var_info.mutable)
+            {
+                self.errors.push(SemanticError::ConstReassignment {
+                    name: name.clone(),
+                    span: expr.span.clone(),
+                });
+            }
+        }
+
+        if !is_known(&operand) {
+            return Ok(Type::Number);
+        }
+        if !operand.is_numeric() {
+            self.errors.push(SemanticError::InvalidOperation {
+                operation: expr.operator.clone(),
+                type_name: operand.to_string(),
+                span: expr.span.clone(),
             });
         }
-        Ok(Type::Unknown)
+        Ok(operand)
     }
 
     fn visit_assignment_expression(
@@ -221,31 +683,60 @@ impl SemanticAnalyzer {
     ) -> SemanticResult<Type> {
         let value_type = self.visit_node(&expr.right)?;
 
-        if let Node::Identifier(var_name) = &*expr.left {
+        if let Node::Pattern(pattern) = &*expr.left {
+            self.destructure(pattern, &value_type, None, &expr.span)?;
+        } else if let Node::MemberExpression(member) = &*expr.left {
+            let member_type = self.visit_member_expression(member)?;
+            // `??=` only stores into a null or undefined member, which then takes the
+            // type of the value, as with `??`
+            let widened =
+                expr.operator == "??=" && matches!(member_type, Type::Null | Type::Undefined);
+            if matches!(expr.operator.as_str(), "=" | "->" | "??=")
+                && !widened
+                && is_known(&member_type)
+                && is_known(&value_type)
+                && !member_type.is_assignable_from(&value_type)
+            {
+                self.errors.push(SemanticError::TypeMismatch {
+                    expected: member_type.to_string(),
+                    found: value_type.to_string(),
+                    span: expr.span.clone(),
+                });
+            }
+        } else if let Node::Identifier(var_name) = &*expr.left {
             let current_scope = self.scope_stack.last().unwrap();
 
             if let Some(var_info) = current_scope.get_variable(var_name) {
                 if !var_info.mutable {
                     self.errors.push(SemanticError::ConstReassignment {
                         name: var_name.clone(),
-                        position: expr.span.as_ref().map(|s| s.start.clone()),
+                        span: expr.span.clone(),
                     });
                 }
             } else {
                 self.errors.push(SemanticError::UndeclaredVariable {
                     name: var_name.clone(),
-                    position: expr.span.as_ref().map(|s| s.start.clone()),
+                    span: expr.span.clone(),
                 });
             }
 
             if let Some(var_type) = self.type_env.get_type(var_name) {
                 match expr.operator.as_str() {
-                    "=" => {
-                        if *var_type != value_type {
+                    "=" | "->" => {
+                        if !var_type.is_assignable_from(&value_type) {
+                            self.errors.push(SemanticError::TypeMismatch {
+                                expected: var_type.to_string(),
+                                found: value_type.to_string(),
+                                span: expr.span.clone(),
+                            });
+                        }
+                    }
+                    "??=" => {
+                        if is_known(&value_type) && !var_type.is_assignable_from(&value_type) {
                             self.errors.push(SemanticError::TypeMismatch {
                                 expected: var_type.to_string(),
                                 found: value_type.to_string(),
-                                position: expr.span.as_ref().map(|s| s.start.clone()),
+                                span: expr.span.clone(),
                             });
                         }
                     }
@@ -254,23 +745,33 @@ impl SemanticAnalyzer {
                             self.errors.push(SemanticError::InvalidOperation {
                                 operation: "+=".to_string(),
                                 type_name: var_type.to_string(),
-                                position: expr.span.as_ref().map(|s| s.start.clone()),
+                                span: expr.span.clone(),
                             });
                         }
                     }
-                    "-=" => {
+                    "-=" | "*=" | "%=" | "**=" => {
                         if !self.can_subtract_types(var_type, &value_type) {
                             self.errors.push(SemanticError::InvalidOperation {
-                                operation: "-=".to_string(),
+                                operation: expr.operator.clone(),
+                                type_name: var_type.to_string(),
+                                span: expr.span.clone(),
+                            });
+                        }
+                    }
+                    "/=" => {
+                        // Dividing always yields a number, even for two integers
+                        if *var_type != Type::Number || !value_type.is_numeric() {
+                            self.errors.push(SemanticError::InvalidOperation {
+                                operation: "/=".to_string(),
                                 type_name: var_type.to_string(),
-                                position: expr.span.as_ref().map(|s| s.start.clone()),
+                                span: expr.span.clone(),
                             });
                         }
                     }
                     _ => {
                         self.errors.push(SemanticError::UnsupportedOperator {
                             operator: expr.operator.clone(),
-                            position: expr.span.as_ref().map(|s| s.start.clone()),
+                            span: expr.span.clone(),
                         });
                     }
                 }
@@ -280,15 +781,186 @@ impl SemanticAnalyzer {
         Ok(value_type)
     }
 
+    /// Visit property access or indexing, the member type is known for arrays and strings
+    fn visit_member_expression(&mut self, expr: &node::MemberExpression) -> SemanticResult<Type> {
+        let object_type = self.visit_node(&expr.object)?;
+        let key_type = if expr.computed {
+            Some(self.visit_node(&expr.property)?)
+        } else {
+            None
+        };
+
+        let member_type = match (&object_type, &key_type) {
+            (Type::Array(inner), Some(key)) => {
+                if !matches!(
+                    key,
+                    Type::Integer | Type::Any | Type::Unknown | Type::Undefined
+                ) {
+                    self.errors.push(SemanticError::TypeMismatch {
+                        expected: Type::Integer.to_string(),
+                        found: key.to_string(),
+                        span: expr.span.clone(),
+                    });
+                }
+                (**inner).clone()
+            }
+            (Type::String, Some(_)) => Type::String,
+            (Type::Array(inner), None) => match expr.property.name().as_str() {
+                "len" => Type::Integer,
+                "push" => Type::Function {
+                    params: vec![],
+                    required: 0,
+                    rest: Some(inner.clone()),
+                    return_type: Box::new(Type::Integer),
+                },
+                "pop" => Type::function(vec![], (**inner).clone()),
+                property => {
+                    self.errors.push(SemanticError::InvalidMemberAccess {
+                        property: property.to_string(),
+                        type_name: object_type.to_string(),
+                        span: expr.span.clone(),
+                    });
+                    Type::Undefined
+                }
+            },
+            (Type::String, None) if expr.property.name() == "len" => Type::Integer,
+            (Type::Record(fields), key) => {
+                let name = match (&*expr.property, key) {
+                    (Node::Identifier(name), None) | (Node::String(name), Some(_)) => {
+                        Some(name.clone())
+                    }
+                    _ => None,
+                };
+                match name {
+                    Some(name) => match fields.get(&name) {
+                        Some(field_type) => field_type.clone(),
+                        None => {
+                            self.errors.push(SemanticError::InvalidMemberAccess {
+                                property: name,
+                                type_name: object_type.to_string(),
+                                span: expr.span.clone(),
+                            });
+                            Type::Undefined
+                        }
+                    },
+                    // Computed keys can name any property
+                    None => Type::Any,
+                }
+            }
+            (Type::Null, _) if expr.optional => Type::Undefined,
+            (
+                Type::Null
+                | Type::Boolean
+                | Type::Integer
+                | Type::Number
+                | Type::BigInt
+                | Type::Symbol,
+                _,
+            ) => {
+                let property = match &*expr.property {
+                    Node::Identifier(name) if !expr.computed => name.clone(),
+                    _ => "[index]".to_string(),
+                };
+                self.errors.push(SemanticError::InvalidMemberAccess {
+                    property,
+                    type_name: object_type.to_string(),
+                    span: expr.span.clone(),
+                });
+                Type::Undefined
+            }
+            // Objects have no known shape yet, expressions that aren't analyzed are undefined
+            _ => Type::Any,
+        };
+
+        if expr.optional {
+            Ok(member_type.common_type(&Type::Undefined))
+        } else {
+            Ok(member_type)
+        }
+    }
+
+    /// Visit array literal, the element type is the common type of all elements
+    fn visit_array_literal(&mut self, array: &node::ArrayLiteral) -> SemanticResult<Type> {
+        let mut element_type: Option<Type> = None;
+
+        for element in &array.elements {
+            let next_type = match element {
+                Some(Node::SpreadElement(spread)) => match self.visit_spread_element(spread)? {
+                    Type::Array(inner) => *inner,
+                    Type::String => Type::String,
+                    _ => Type::Any,
+                },
+                Some(element) => self.visit_node(element)?,
+                // Holes read as undefined
+                None => Type::Undefined,
+            };
+            element_type = Some(match element_type {
+                Some(current) => current.common_type(&next_type),
+                None => next_type,
+            });
+        }
+
+        Ok(Type::Array(Box::new(element_type.unwrap_or(Type::Any))))
+    }
+
+    /// Visit spread element, only arrays and strings can be spread
+    fn visit_spread_element(&mut self, spread: &node::SpreadElement) -> SemanticResult<Type> {
+        let spread_type = self.visit_node(&spread.argument)?;
+        // Undefined is what expressions that aren't analyzed yet report
+        let is_iterable = matches!(
+            spread_type,
+            Type::Array(_)
+                | Type::String
+                | Type::Union(_)
+                | Type::Any
+                | Type::Unknown
+                | Type::Undefined
+        );
+        if !is_iterable {
+            self.errors.push(SemanticError::TypeMismatch {
+                expected: "array".to_string(),
+                found: spread_type.to_string(),
+                span: spread.span.clone(),
+            });
+        }
+        Ok(spread_type)
+    }
+
+    /// Visit object literal, the type lists each property unless a key is computed
+    fn visit_object_literal(&mut self, obj: &node::ObjectLiteral) -> SemanticResult<Type> {
+        let mut fields = BTreeMap::new();
+        let mut has_computed_key = false;
+
+        for property in &obj.properties {
+            if let node::PropertyKey::Computed(key) = &property.key {
+                self.visit_node(key)?;
+                has_computed_key = true;
+            }
+            let value_type = self.visit_node(&property.value)?;
+            if let node::PropertyKey::Named(name) = &property.key {
+                fields.insert(name.clone(), value_type);
+            }
+        }
+
+        if has_computed_key {
+            Ok(Type::Object)
+        } else {
+            Ok(Type::Record(fields))
+        }
+    }
+
     fn can_add_types(&self, left: &Type, right: &Type) -> bool {
-        matches!(
-            (left, right),
-            (Type::Number, Type::Number) | (Type::String, Type::String)
-        )
+        matches!((left, right), (Type::String, Type::String))
+            || self.can_subtract_types(left, right)
     }
 
     fn can_subtract_types(&self, left: &Type, right: &Type) -> bool {
-        matches!((left, right), (Type::Number, Type::Number))
+        // An integer variable only stays an integer when combined with integers
+        match left {
+            Type::Integer | Type::BigInt => left == right,
+            Type::Number => right.is_numeric(),
+            _ => false,
+        }
     }
 
     fn visit_expression_statement(
@@ -299,10 +971,270 @@ impl SemanticAnalyzer {
             Node::Identifier(i) => self.visit_identifier(i, stmt.span.clone()),
             Node::AssignmentExpression(expr) => self.visit_assignment_expression(expr),
             Node::CallExpression(expr) => self.visit_call_expression(expr),
+            Node::MemberExpression(expr) => self.visit_member_expression(expr),
+            Node::ConditionalExpression(expr) => self.visit_conditional_expression(expr),
+            Node::LogicalExpression(expr) => self.visit_logical_expression(expr),
+            Node::UnaryExpression(expr) => self.visit_unary_expression(expr),
+            Node::NewExpression(expr) => self.visit_new_expression(expr),
+            Node::MatchExpression(expr) => self.visit_match_expression(expr),
             _ => Ok(Type::Undefined),
         }
     }
 
+    /// Visit class declaration, the members are analyzed in a class scope where `this`
+    /// and, when the class extends another, `super` can be used
+    fn visit_class_declaration(&mut self, class: &node::ClassDeclaration) -> SemanticResult<Type> {
+        let name = class.id.name();
+        if let Some(superclass) = &class.superclass {
+            let superclass_type = self.visit_node(superclass)?;
+            if is_known(&superclass_type) && !matches!(superclass_type, Type::Class(_)) {
+                self.errors.push(SemanticError::TypeMismatch {
+                    expected: "class".to_string(),
+                    found: superclass_type.to_string(),
+                    span: class.span.clone(),
+                });
+            }
+        }
+
+        // Declared before the members, so methods can create instances of their own class
+        let line_number = class.span.as_ref().map(|s| s.start.line).unwrap_or(1);
+        let current_scope = self.scope_stack.last_mut().unwrap();
+        if !current_scope.declare_variable_with_details(
+            &name,
+            Type::Class(name.clone()),
+            false,
+            line_number,
+        ) {
+            self.errors.push(SemanticError::DuplicateDeclaration {
+                name: name.clone(),
+                span: class.span.clone(),
+            });
+        }
+        current_scope.initialize_variable(&name);
+
+        let enclosing_scope = self.scope_stack.last().unwrap().clone();
+        self.scope_stack
+            .push(Scope::new_child(enclosing_scope, ScopeType::Class));
+        self.classes.push(class.superclass.is_some());
+        let result = self.visit_class_members(&class.members);
+        self.classes.pop();
+        self.scope_stack.pop();
+        result?;
+
+        Ok(Type::Undefined)
+    }
+
+    /// Visit enum declaration, the enum name holds the constructors of the variants with
+    /// a payload and the values of the variants without one
+    fn visit_enum_declaration(&mut self, decl: &node::EnumDeclaration) -> SemanticResult<Type> {
+        let name = decl.id.name();
+        let enum_type = Type::Enum(name.clone());
+        // Known before the fields are resolved, so a variant can hold its own enum
+        self.enums.insert(name.clone(), Vec::new());
+
+        let mut variants = Vec::with_capacity(decl.variants.len());
+        let mut members = BTreeMap::new();
+        for variant in &decl.variants {
+            let mut fields = Vec::with_capacity(variant.fields.len());
+            for field in &variant.fields {
+                fields.push(match &field.field_type {
+                    Some(annotation) => self.get_type_from_annotation(annotation, &variant.span)?,
+                    None => Type::Any,
+                });
+            }
+            let member = match fields.is_empty() {
+                true => enum_type.clone(),
+                false => Type::function(fields.clone(), enum_type.clone()),
+            };
+            members.insert(variant.name.clone(), member);
+            variants.push((variant.name.clone(), fields));
+        }
+        self.enums.insert(name.clone(), variants);
+
+        let line_number = decl.span.as_ref().map(|s| s.start.line).unwrap_or(1);
+        let current_scope = self.scope_stack.last_mut().unwrap();
+        if !current_scope.declare_variable_with_details(
+            &name,
+            Type::Record(members),
+            false,
+            line_number,
+        ) {
+            self.errors.push(SemanticError::DuplicateDeclaration {
+                name: name.clone(),
+                span: decl.span.clone(),
+            });
+        }
+        current_scope.initialize_variable(&name);
+
+        Ok(Type::Undefined)
+    }
+
+    fn visit_class_members(&mut self, members: &[node::ClassMember]) -> SemanticResult<()> {
+        for member in members {
+            match (member.kind, member.value.as_deref()) {
+                (node::ClassMemberKind::Field, value) => {
+                    let declared = match &member.member_type {
+                        Some(annotation) => {
+                            Some(self.get_type_from_annotation(annotation, &member.span)?)
+                        }
+                        None => None,
+                    };
+                    let found = match value {
+                        Some(value) => self.visit_node(value)?,
+                        None => continue,
+                    };
+                    if let Some(expected) = declared
+                        && is_known(&found)
+                        && !expected.is_assignable_from(&found)
+                    {
+                        self.errors.push(SemanticError::TypeMismatch {
+                            expected: expected.to_string(),
+                            found: found.to_string(),
+                            span: member.span.clone(),
+                        });
+                    }
+                }
+                (_, Some(Node::FunctionExpression(method))) => {
+                    self.visit_function(
+                        None,
+                        &method.params,
+                        method.return_type.as_deref(),
+                        &method.body,
+                        &method.span,
+                    )?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Visit `new`, only classes can be instantiated
+    fn visit_new_expression(&mut self, expr: &node::NewExpression) -> SemanticResult<Type> {
+        let callee_type = self.visit_node(&expr.callee)?;
+        for arg in &expr.arguments {
+            self.visit_node(arg)?;
+        }
+        if is_known(&callee_type) && !matches!(callee_type, Type::Class(_)) {
+            self.errors.push(SemanticError::TypeMismatch {
+                expected: "class".to_string(),
+                found: callee_type.to_string(),
+                span: expr.span.clone(),
+            });
+        }
+        Ok(Type::Object)
+    }
+
+    /// `this` is the instance in methods and the class itself in static methods
+    fn visit_this(&mut self) -> SemanticResult<Type> {
+        if !self.scope_stack.last().unwrap().is_inside(ScopeType::Class) {
+            self.errors
+                .push(SemanticError::InvalidThisUsage { span: None });
+        }
+        Ok(Type::Any)
+    }
+
+    fn visit_super(&mut self) -> SemanticResult<Type> {
+        if self.classes.last() != Some(&true) {
+            self.errors
+                .push(SemanticError::InvalidSuperUsage { span: None });
+        }
+        Ok(Type::Any)
+    }
+
+    /// Visit try statement, the catch parameter is scoped to the catch block and can hold
+    /// any thrown value
+    fn visit_try_statement(&mut self, stmt: &node::TryStatement) -> SemanticResult<Type> {
+        self.visit_node(&stmt.block)?;
+
+        if let Some(handler) = &stmt.handler {
+            let current_scope = self.scope_stack.last().unwrap().clone();
+            let mut catch_scope = Scope::new_child(current_scope, ScopeType::Block);
+            if let Some(param) = &handler.param {
+                let line_number = handler.span.as_ref().map(|s| s.start.line).unwrap_or(1);
+                catch_scope.declare_variable(param.name(), Type::Any, line_number);
+            }
+            self.scope_stack.push(catch_scope);
+            let result = self.visit_node(&handler.body);
+            self.scope_stack.pop();
+            result?;
+        }
+
+        if let Some(finalizer) = &stmt.finalizer {
+            self.visit_node(finalizer)?;
+        }
+        Ok(Type::Undefined)
+    }
+
+    /// Visit switch statement, the body of each case has a scope of its own and can be
+    /// left with `break`
+    fn visit_switch_statement(&mut self, stmt: &node::SwitchStatement) -> SemanticResult<Type> {
+        self.visit_node(&stmt.discriminant)?;
+
+        for case in &stmt.cases {
+            for test in &case.tests {
+                self.visit_node(test)?;
+            }
+            let current_scope = self.scope_stack.last().unwrap().clone();
+            self.scope_stack
+                .push(Scope::new_child(current_scope, ScopeType::Block));
+            self.switch_depth += 1;
+            let result = case
+                .body
+                .iter()
+                .try_for_each(|stmt| self.visit_node(stmt).map(drop));
+            self.switch_depth -= 1;
+            self.scope_stack.pop();
+            result?;
+        }
+        Ok(Type::Undefined)
+    }
+
+    /// Visit match expression, the names a pattern binds are scoped to the guard and body
+    /// of its arm. The result has the common type of the arms, which must cover every
+    /// value of the subject
+    fn visit_match_expression(&mut self, expr: &node::MatchExpression) -> SemanticResult<Type> {
+        let subject = self.visit_node(&expr.discriminant)?;
+
+        let mut result: Option<Type> = None;
+        for arm in &expr.arms {
+            let current_scope = self.scope_stack.last().unwrap().clone();
+            self.scope_stack
+                .push(Scope::new_child(current_scope, ScopeType::Block));
+            self.bind_pattern(&arm.pattern, &subject, &arm.span);
+            let arm_type = self.visit_match_arm(arm);
+            self.scope_stack.pop();
+
+            let arm_type = arm_type?;
+            result = Some(match result {
+                Some(result) => result.common_type(&arm_type),
+                None => arm_type,
+            });
+        }
+
+        if let Some(missing) = missing_cases(&subject, &expr.arms, &self.enums) {
+            self.errors.push(SemanticError::NonExhaustiveMatch {
+                missing,
+                span: expr.span.clone(),
+            });
+        }
+        Ok(result.unwrap_or(Type::Undefined))
+    }
+
+    fn visit_match_arm(&mut self, arm: &node::MatchArm) -> SemanticResult<Type> {
+        if let Some(guard) = &arm.guard {
+            let guard_type = self.visit_node(guard)?;
+            if is_known(&guard_type) && !guard_type.is_compatible_with(&Type::Boolean) {
+                self.errors.push(SemanticError::TypeMismatch {
+                    expected: "boolean".to_string(),
+                    found: guard_type.to_string(),
+                    span: arm.span.clone(),
+                });
+            }
+        }
+        self.visit_node(&arm.body)
+    }
+
     /// Visit if statement
     fn visit_if_statement(&mut self, stmt: &node::IfStatement) -> SemanticResult<Type> {
         let condition_type = self.visit_node(&stmt.expr)?;
@@ -310,7 +1242,7 @@ impl SemanticAnalyzer {
             self.errors.push(SemanticError::TypeMismatch {
                 expected: "boolean".to_string(),
                 found: format!("{:?}", condition_type),
-                position: stmt.span.as_ref().map(|s| s.start.clone()),
+                span: stmt.span.clone(),
             })
         }
 
@@ -331,12 +1263,177 @@ impl SemanticAnalyzer {
         Ok(Type::Undefined)
     }
 
+    /// Visit for loop, the iterator is scoped to the loop
+    fn visit_for_statement(
+        &mut self,
+        stmt: &node::ForStatement,
+        label: Option<String>,
+    ) -> SemanticResult<Type> {
+        let iterator_type = self.visit_node(&stmt.start)?;
+        self.visit_node(&stmt.end)?;
+        if let Some(step) = &stmt.step {
+            self.visit_node(step)?;
+        }
+
+        let current_scope = self.scope_stack.last().unwrap().clone();
+        let mut loop_scope = Scope::new_child(current_scope, ScopeType::Block);
+        if let Node::Identifier(name) = &*stmt.iterator {
+            let line_number = stmt.span.as_ref().map(|s| s.start.line).unwrap_or(1);
+            loop_scope.declare_variable_with_details(name, iterator_type, true, line_number);
+            loop_scope.initialize_variable(name);
+        }
+
+        self.scope_stack.push(loop_scope);
+        let result = self.visit_loop_body(&stmt.body, label);
+        self.scope_stack.pop();
+        result
+    }
+
+    /// Visit for-each loop, the iterator or the names it destructures are scoped to the loop
+    fn visit_for_each_statement(
+        &mut self,
+        stmt: &node::ForEachStatement,
+        label: Option<String>,
+    ) -> SemanticResult<Type> {
+        let iterable_type = self.visit_node(&stmt.iterable)?;
+        let element_type = match &iterable_type {
+            Type::Array(inner) => (**inner).clone(),
+            Type::String => Type::String,
+            found if is_known(found) && !matches!(found, Type::Union(_)) => {
+                self.errors.push(SemanticError::TypeMismatch {
+                    expected: "array".to_string(),
+                    found: found.to_string(),
+                    span: stmt.span.clone(),
+                });
+                Type::Any
+            }
+            _ => Type::Any,
+        };
+
+        let current_scope = self.scope_stack.last().unwrap().clone();
+        self.scope_stack
+            .push(Scope::new_child(current_scope, ScopeType::Block));
+        let result = match &*stmt.iterator {
+            Node::Pattern(pattern) => {
+                self.destructure(pattern, &element_type, Some(true), &stmt.span)
+            }
+            iterator => {
+                let name = iterator.name();
+                let line_number = stmt.span.as_ref().map(|s| s.start.line).unwrap_or(1);
+                let loop_scope = self.scope_stack.last_mut().unwrap();
+                loop_scope.declare_variable_with_details(&name, element_type, true, line_number);
+                loop_scope.initialize_variable(&name);
+                Ok(())
+            }
+        }
+        .and_then(|_| self.visit_loop_body(&stmt.body, label));
+        self.scope_stack.pop();
+        result
+    }
+
+    /// Visit while loop
+    fn visit_while_statement(
+        &mut self,
+        stmt: &node::WhileStatement,
+        label: Option<String>,
+    ) -> SemanticResult<Type> {
+        self.visit_node(&stmt.condition)?;
+        self.visit_loop_body(&stmt.body, label)
+    }
+
+    /// Visit do-while loop
+    fn visit_do_while_statement(
+        &mut self,
+        stmt: &node::DoWhileStatement,
+        label: Option<String>,
+    ) -> SemanticResult<Type> {
+        self.visit_loop_body(&stmt.body, label)?;
+        self.visit_node(&stmt.condition)?;
+        Ok(Type::Undefined)
+    }
+
+    /// Visit the body of a loop, where `break` and `continue` are allowed
+    fn visit_loop_body(&mut self, body: &Node, label: Option<String>) -> SemanticResult<Type> {
+        self.loop_labels.push(label);
+        let result = self.visit_node(body);
+        self.loop_labels.pop();
+        result.map(|_| Type::Undefined)
+    }
+
+    /// Visit return statement, recording the returned type for the enclosing function
+    fn visit_return_statement(&mut self, stmt: &node::ReturnStatement) -> SemanticResult<Type> {
+        let return_type = match &stmt.argument {
+            Some(argument) => Some(self.visit_node(argument)?),
+            None => None,
+        };
+
+        match self.function_returns.last_mut() {
+            Some(returns) => returns.push(return_type.clone()),
+            None => self.errors.push(SemanticError::ReturnOutsideFunction {
+                span: stmt.span.clone(),
+            }),
+        }
+        Ok(return_type.unwrap_or(Type::Undefined))
+    }
+
+    /// Visit labeled loop
+    fn visit_labeled_statement(&mut self, stmt: &node::LabeledStatement) -> SemanticResult<Type> {
+        let label = stmt.label.clone();
+        if self.loop_labels.contains(&Some(label.clone())) {
+            self.errors.push(SemanticError::DuplicateLabel {
+                name: label.clone(),
+                span: stmt.span.clone(),
+            });
+        }
+
+        match &*stmt.body {
+            Node::ForStatement(body) => self.visit_for_statement(body, Some(label)),
+            Node::ForEachStatement(body) => self.visit_for_each_statement(body, Some(label)),
+            Node::WhileStatement(body) => self.visit_while_statement(body, Some(label)),
+            Node::DoWhileStatement(body) => self.visit_do_while_statement(body, Some(label)),
+            Node::LoopStatement(body) => self.visit_loop_body(&body.body, Some(label)),
+            body => self.visit_node(body),
+        }
+    }
+
+    /// Check that `break` or `continue` has a loop to jump to, or a switch for `break`
+    fn visit_loop_jump(
+        &mut self,
+        keyword: &str,
+        label: &Option<String>,
+        span: &Option<Span>,
+    ) -> SemanticResult<Type> {
+        let leaves_switch = keyword == "break" && label.is_none() && self.switch_depth > 0;
+        if self.loop_labels.is_empty() && !leaves_switch {
+            self.errors.push(SemanticError::JumpOutsideLoop {
+                keyword: keyword.to_string(),
+                span: span.clone(),
+            });
+        } else if let Some(name) = label
+            && !self.loop_labels.contains(&Some(name.clone()))
+        {
+            self.errors.push(SemanticError::UndeclaredLabel {
+                name: name.clone(),
+                span: span.clone(),
+            });
+        }
+        Ok(Type::Undefined)
+    }
+
     /// Visit print statement
     fn visit_print_statement(&mut self, stmt: &node::PrintStatement) -> SemanticResult<Type> {
         self.visit_node(&stmt.argument)?;
         Ok(Type::Undefined)
     }
 
+    /// Visit interpolated string, every embedded expression gets converted to a string
+    fn visit_interpolated_string(&mut self, s: &node::InterpolatedString) -> SemanticResult<Type> {
+        for part in &s.parts {
+            self.visit_node(part)?;
+        }
+        Ok(Type::String)
+    }
+
     /// Visit block statement
     fn visit_block_statement(&mut self, stmt: &node::BlockStatement) -> SemanticResult<Type> {
         let current_scope = self.scope_stack.last().unwrap().clone();
@@ -365,10 +1462,8 @@ impl SemanticAnalyzer {
 
         // If variable doesn't exist, return undeclared variable error
         if !current_scope.is_variable_declared_in_current_scope(&name) {
-            self.errors.push(SemanticError::UndeclaredVariable {
-                name,
-                position: span.as_ref().map(|s| s.start.clone()),
-            });
+            self.errors
+                .push(SemanticError::UndeclaredVariable { name, span });
             return Ok(Type::Undefined);
         }
 
@@ -381,29 +1476,249 @@ impl SemanticAnalyzer {
 
     fn get_type_from_annotation(
         &self,
-        var_type: &Box<Node>,
-        decl: &node::VariableDeclaration,
+        var_type: &Node,
+        span: &Option<Span>,
     ) -> SemanticResult<Type> {
-        return match &**var_type {
+        match var_type {
             Node::Identifier(id) => match id.to_string().as_str() {
-                "int" => Ok(Type::Number),
-                // "float" => Some(Type::Float), // TODO: implement when adding float type
+                "int" => Ok(Type::Integer),
+                "float" => Ok(Type::Number),
                 "str" => Ok(Type::String),
                 "bool" => Ok(Type::Boolean),
+                name if self.enums.contains_key(name) => Ok(Type::Enum(name.to_string())),
                 // Add more types as needed
                 _ => Err(SemanticError::InvalidType {
                     type_name: id.to_string(),
-                    position: decl.span.as_ref().map(|s| s.start.clone()),
+                    span: span.clone(),
                 }), // Handle unknown types
             },
             _ => {
                 // Handle complex types or type expressions
                 Err(SemanticError::InvalidType {
                     type_name: format!("{:?}", var_type),
-                    position: decl.span.as_ref().map(|s| s.start.clone()),
+                    span: span.clone(),
                 })
             }
-        };
+        }
+    }
+
+    /// Declare the names `pattern` binds in the current scope, typed by the part of
+    /// `value_type` they match
+    fn bind_pattern(&mut self, pattern: &node::Pattern, value_type: &Type, span: &Option<Span>) {
+        match pattern {
+            node::Pattern::Binding(name) => {
+                let line_number = span.as_ref().map(|s| s.start.line).unwrap_or(1);
+                self.scope_stack.last_mut().unwrap().declare_variable(
+                    name.clone(),
+                    value_type.clone(),
+                    line_number,
+                )
+            }
+            node::Pattern::Array(array) => {
+                let element_type = match value_type {
+                    Type::Array(inner) => (**inner).clone(),
+                    _ => Type::Any,
+                };
+                for element in &array.elements {
+                    self.bind_pattern(element, &element_type, span);
+                }
+                if let Some(rest) = &array.rest {
+                    self.bind_pattern(rest, &Type::Array(Box::new(element_type)), span);
+                }
+            }
+            node::Pattern::Object(object) => {
+                for property in &object.properties {
+                    let property_type = match value_type {
+                        Type::Record(fields) => {
+                            fields.get(&property.key).cloned().unwrap_or(Type::Any)
+                        }
+                        _ => Type::Any,
+                    };
+                    self.bind_pattern(&property.value, &property_type, span);
+                }
+            }
+            node::Pattern::Variant(variant) => {
+                let Some(variants) = self.enums.get(&variant.enum_name) else {
+                    self.errors.push(SemanticError::InvalidType {
+                        type_name: variant.enum_name.clone(),
+                        span: span.clone(),
+                    });
+                    return;
+                };
+                let Some((_, field_types)) = variants
+                    .iter()
+                    .find(|(name, _)| *name == variant.variant)
+                    .cloned()
+                else {
+                    self.errors.push(SemanticError::InvalidMemberAccess {
+                        property: variant.variant.clone(),
+                        type_name: variant.enum_name.clone(),
+                        span: span.clone(),
+                    });
+                    return;
+                };
+                if is_known(value_type)
+                    && !matches!(value_type, Type::Union(_))
+                    && *value_type != Type::Enum(variant.enum_name.clone())
+                {
+                    self.errors.push(SemanticError::TypeMismatch {
+                        expected: value_type.to_string(),
+                        found: variant.enum_name.clone(),
+                        span: span.clone(),
+                    });
+                }
+
+                let Some(fields) = &variant.fields else {
+                    return;
+                };
+                if fields.len() != field_types.len() {
+                    self.errors.push(SemanticError::WrongArgumentCount {
+                        function_name: format!("{}.{}", variant.enum_name, variant.variant),
+                        expected: field_types.len(),
+                        found: fields.len(),
+                        span: span.clone(),
+                    });
+                }
+                for (field, field_type) in fields.iter().zip(&field_types) {
+                    self.bind_pattern(field, field_type, span);
+                }
+            }
+            node::Pattern::Default(pattern, _) => self.bind_pattern(pattern, value_type, span),
+            node::Pattern::Wildcard | node::Pattern::Literal(_) | node::Pattern::Range(..) => {}
+        }
+    }
+
+    /// Bind the names of a destructuring pattern to the parts of `value_type`. `declare`
+    /// holds whether declared names are mutable, `None` assigns existing variables instead
+    fn destructure(
+        &mut self,
+        pattern: &node::Pattern,
+        value_type: &Type,
+        declare: Option<bool>,
+        span: &Option<Span>,
+    ) -> SemanticResult<()> {
+        match pattern {
+            node::Pattern::Binding(name) => match declare {
+                Some(mutable) => {
+                    let line_number = span.as_ref().map(|s| s.start.line).unwrap_or(1);
+                    let current_scope = self.scope_stack.last_mut().unwrap();
+                    if !current_scope.declare_variable_with_details(
+                        name,
+                        value_type.clone(),
+                        mutable,
+                        line_number,
+                    ) {
+                        self.errors.push(SemanticError::DuplicateDeclaration {
+                            name: name.clone(),
+                            span: span.clone(),
+                        });
+                    }
+                    current_scope.initialize_variable(name);
+                }
+                None => match self.scope_stack.last().unwrap().get_variable(name) {
+                    Some(var_info) if !var_info.mutable => {
+                        self.errors.push(SemanticError::ConstReassignment {
+                            name: name.clone(),
+                            span: span.clone(),
+                        });
+                    }
+                    Some(var_info) => {
+                        let var_type = &var_info.type_info;
+                        if is_known(var_type)
+                            && is_known(value_type)
+                            && !var_type.is_assignable_from(value_type)
+                        {
+                            self.errors.push(SemanticError::TypeMismatch {
+                                expected: var_type.to_string(),
+                                found: value_type.to_string(),
+                                span: span.clone(),
+                            });
+                        }
+                    }
+                    None => {
+                        self.errors.push(SemanticError::UndeclaredVariable {
+                            name: name.clone(),
+                            span: span.clone(),
+                        });
+                    }
+                },
+            },
+            node::Pattern::Default(pattern, default) => {
+                let default_type = self.visit_node(default)?;
+                if is_known(value_type)
+                    && is_known(&default_type)
+                    && !value_type.is_assignable_from(&default_type)
+                {
+                    self.errors.push(SemanticError::TypeMismatch {
+                        expected: value_type.to_string(),
+                        found: default_type.to_string(),
+                        span: span.clone(),
+                    });
+                }
+                // A missing part takes the type of its default
+                let value_type = match value_type {
+                    Type::Undefined => &default_type,
+                    value_type => value_type,
+                };
+                self.destructure(pattern, value_type, declare, span)?;
+            }
+            node::Pattern::Array(array) => {
+                let element_type = match value_type {
+                    Type::Array(inner) => (**inner).clone(),
+                    Type::String => Type::String,
+                    found if is_known(found) && !matches!(found, Type::Union(_)) => {
+                        self.errors.push(SemanticError::TypeMismatch {
+                            expected: "array".to_string(),
+                            found: found.to_string(),
+                            span: span.clone(),
+                        });
+                        Type::Any
+                    }
+                    _ => Type::Any,
+                };
+                for element in &array.elements {
+                    self.destructure(element, &element_type, declare, span)?;
+                }
+                if let Some(rest) = &array.rest {
+                    let rest_type = Type::Array(Box::new(element_type));
+                    self.destructure(rest, &rest_type, declare, span)?;
+                }
+            }
+            node::Pattern::Object(object) => {
+                if is_known(value_type) && value_type.is_primitive() {
+                    self.errors.push(SemanticError::TypeMismatch {
+                        expected: "object".to_string(),
+                        found: value_type.to_string(),
+                        span: span.clone(),
+                    });
+                }
+                for property in &object.properties {
+                    let property_type = match value_type {
+                        Type::Record(fields) => match fields.get(&property.key) {
+                            Some(field_type) => field_type.clone(),
+                            // A default stands in for a property the value doesn't have
+                            None if matches!(property.value, node::Pattern::Default(..)) => {
+                                Type::Undefined
+                            }
+                            None => {
+                                self.errors.push(SemanticError::InvalidMemberAccess {
+                                    property: property.key.clone(),
+                                    type_name: value_type.to_string(),
+                                    span: span.clone(),
+                                });
+                                Type::Any
+                            }
+                        },
+                        _ => Type::Any,
+                    };
+                    self.destructure(&property.value, &property_type, declare, span)?;
+                }
+            }
+            node::Pattern::Wildcard => {}
+            // The parser only allows tests of the value in match arms
+            node::Pattern::Literal(_) | node::Pattern::Range(..) | node::Pattern::Variant(_) => {}
+        }
+        Ok(())
     }
 
     /// Collect semantic errors found during analysis
@@ -411,3 +1726,189 @@ impl SemanticAnalyzer {
         &self.errors
     }
 }
+
+/// The type of a function taking `params`, the optional and defaulted ones after the
+/// last required parameter may be left out
+fn function_type(params: &[node::Parameter], mut signature: Vec<Type>, return_type: Type) -> Type {
+    let rest = match params.last() {
+        Some(param) if param.is_rest => signature.pop().map(Box::new),
+        _ => None,
+    };
+    let required = params
+        .iter()
+        .rposition(|param| !param.optional && param.default_value.is_none() && !param.is_rest)
+        .map_or(0, |idx| idx + 1);
+
+    Type::Function {
+        params: signature,
+        required,
+        rest,
+        return_type: Box::new(return_type),
+    }
+}
+
+/// Whether a type was worked out, parameters without annotations and unanalyzed
+/// expressions can hold anything
+fn is_known(t: &Type) -> bool {
+    !matches!(t, Type::Any | Type::Unknown | Type::Undefined)
+}
+
+/// The values of `subject` no unguarded arm matches, `None` when the arms cover all of
+/// them. Booleans need both literals, null and undefined their own literal, arrays an
+/// array pattern of only a rest, objects a pattern binding only keys they have and enums
+/// a pattern matching all of each variant, other types a catch-all arm. Subjects of an
+/// unknown type aren't checked
+fn missing_cases(
+    subject: &Type,
+    arms: &[node::MatchArm],
+    enums: &HashMap<String, EnumVariants>,
+) -> Option<String> {
+    if !is_known(subject) {
+        return None;
+    }
+    let patterns: Vec<&node::Pattern> = arms
+        .iter()
+        .filter(|arm| arm.guard.is_none())
+        .map(|arm| &arm.pattern)
+        .collect();
+    if patterns.iter().any(|pattern| pattern.is_irrefutable()) {
+        return None;
+    }
+    let has_literal = |value: &Node| {
+        patterns.iter().any(
+            |pattern| matches!(pattern, node::Pattern::Literal(literal) if **literal == *value),
+        )
+    };
+
+    let members = match subject {
+        Type::Union(types) => types.clone(),
+        subject => vec![subject.clone()],
+    };
+    let mut missing = Vec::new();
+    for member in members {
+        match member {
+            Type::Any | Type::Unknown => {}
+            Type::Boolean => {
+                for value in [true, false] {
+                    if !has_literal(&Node::Boolean(value)) {
+                        missing.push(value.to_string());
+                    }
+                }
+            }
+            Type::Null if has_literal(&Node::Null) => {}
+            Type::Undefined if has_literal(&Node::Undefined) => {}
+            Type::Array(_)
+                if patterns.iter().any(|pattern| {
+                    matches!(pattern, node::Pattern::Array(array)
+                        if array.elements.is_empty()
+                            && array.rest.as_deref().is_some_and(node::Pattern::is_irrefutable))
+                }) => {}
+            Type::Record(fields)
+                if patterns.iter().any(|pattern| {
+                    matches!(pattern, node::Pattern::Object(object)
+                    if object.properties.iter().all(|property| {
+                        fields.contains_key(&property.key) && property.value.is_irrefutable()
+                    }))
+                }) => {}
+            Type::Enum(name) if enums.contains_key(&name) => {
+                for (variant, _) in &enums[&name] {
+                    let covered = patterns.iter().any(|pattern| {
+                        matches!(pattern, node::Pattern::Variant(pattern)
+                            if pattern.enum_name == name
+                                && pattern.variant == *variant
+                                && pattern.fields.iter().flatten().all(node::Pattern::is_irrefutable))
+                    });
+                    if !covered {
+                        missing.push(format!("{}.{}", name, variant));
+                    }
+                }
+            }
+            member => missing.push(member.to_string()),
+        }
+    }
+
+    if missing.is_empty() {
+        None
+    } else {
+        Some(missing.join(", "))
+    }
+}
+
+/// Check whether every path through `node` ends in a return or throw statement
+fn always_returns(node: &Node) -> bool {
+    match node {
+        Node::ReturnStatement(_) | Node::ThrowStatement(_) => true,
+        Node::BlockStatement(block) => block.body.iter().any(always_returns),
+        Node::IfStatement(stmt) => {
+            always_returns(&stmt.stmt) && stmt.else_part.as_deref().is_some_and(always_returns)
+        }
+        // A finally block that returns replaces how the rest of the statement ended
+        Node::TryStatement(stmt) => {
+            stmt.finalizer.as_deref().is_some_and(always_returns)
+                || (always_returns(&stmt.block)
+                    && stmt
+                        .handler
+                        .as_ref()
+                        .is_none_or(|handler| always_returns(&handler.body)))
+        }
+        // Without a default case a value can match none of the cases, and a `break`
+        // continues after the switch
+        Node::SwitchStatement(stmt) => {
+            stmt.cases.iter().any(|case| case.tests.is_empty())
+                && stmt.cases.iter().all(|case| {
+                    case.body.iter().any(always_returns)
+                        && !case
+                            .body
+                            .iter()
+                            .any(|node| contains_break(node, false, &[]))
+                })
+        }
+        // A loop without `break` can only be left by returning
+        Node::LoopStatement(stmt) => !contains_break(&stmt.body, false, &[]),
+        Node::LabeledStatement(stmt) => match &*stmt.body {
+            Node::LoopStatement(inner) => !contains_break(&inner.body, false, &[]),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Check whether a `break` in `node` leaves the loop `node` is the body of. Inside
+/// `nested` loops and switches only a `break` naming a label outside of them does,
+/// `labels` are the labels declared within the loop. Nested functions are not entered
+fn contains_break(node: &Node, nested: bool, labels: &[&str]) -> bool {
+    let leaves = |node: &Node| contains_break(node, nested, labels);
+    match node {
+        Node::BreakStatement(stmt) => match &stmt.label {
+            Some(label) => !labels.contains(&label.as_str()),
+            None => !nested,
+        },
+        Node::BlockStatement(block) => block.body.iter().any(leaves),
+        Node::IfStatement(stmt) => {
+            leaves(&stmt.stmt) || stmt.else_part.as_deref().is_some_and(leaves)
+        }
+        Node::TryStatement(stmt) => {
+            leaves(&stmt.block)
+                || stmt
+                    .handler
+                    .as_ref()
+                    .is_some_and(|handler| leaves(&handler.body))
+                || stmt.finalizer.as_deref().is_some_and(leaves)
+        }
+        Node::SwitchStatement(stmt) => stmt
+            .cases
+            .iter()
+            .flat_map(|case| &case.body)
+            .any(|node| contains_break(node, true, labels)),
+        Node::ForStatement(stmt) => contains_break(&stmt.body, true, labels),
+        Node::ForEachStatement(stmt) => contains_break(&stmt.body, true, labels),
+        Node::WhileStatement(stmt) => contains_break(&stmt.body, true, labels),
+        Node::DoWhileStatement(stmt) => contains_break(&stmt.body, true, labels),
+        Node::LoopStatement(stmt) => contains_break(&stmt.body, true, labels),
+        Node::LabeledStatement(stmt) => {
+            let labels = [labels, &[stmt.label.as_str()]].concat();
+            contains_break(&stmt.body, nested, &labels)
+        }
+        _ => false,
+    }
+}
@@ -1,132 +1,170 @@
-use lumi_ast::Position;
+use lumi_ast::Span;
 use serde::{Deserialize, Serialize};
 
 /// Semantic analysis errors
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SemanticError {
     /// Variable is not declared
-    UndeclaredVariable {
-        name: String,
-        position: Option<Position>,
-    },
+    UndeclaredVariable { name: String, span: Option<Span> },
 
     /// Variable is declared without type and without initializer
-    InvalidVariableDeclaration {
-        name: String,
-        position: Option<Position>,
-    },
+    InvalidVariableDeclaration { name: String, span: Option<Span> },
 
     /// Variable is declared but not initialized
-    UninitializedVariable {
-        name: String,
-        position: Option<Position>,
-    },
+    UninitializedVariable { name: String, span: Option<Span> },
 
     /// Attempting to reassign a const variable
-    ConstReassignment {
-        name: String,
-        position: Option<Position>,
-    },
+    ConstReassignment { name: String, span: Option<Span> },
 
     /// Type mismatch in assignment or operation
     TypeMismatch {
         expected: String,
         found: String,
-        position: Option<Position>,
+        span: Option<Span>,
     },
 
     /// Type does not exist or is invalid
     InvalidType {
         type_name: String,
-        position: Option<Position>,
+        span: Option<Span>,
     },
 
     /// Function is not declared
-    UndeclaredFunction {
-        name: String,
-        position: Option<Position>,
-    },
+    UndeclaredFunction { name: String, span: Option<Span> },
 
     /// Wrong number of arguments for function call
     WrongArgumentCount {
         function_name: String,
         expected: usize,
         found: usize,
-        position: Option<Position>,
+        span: Option<Span>,
+    },
+
+    /// Named argument matches no parameter, or one that was already passed
+    InvalidNamedArgument {
+        function_name: String,
+        name: String,
+        span: Option<Span>,
     },
 
     /// Invalid use of 'this' outside of method or constructor
-    InvalidThisUsage { position: Option<Position> },
+    InvalidThisUsage { span: Option<Span> },
+
+    /// Use of 'super' outside of a class that extends another class
+    InvalidSuperUsage { span: Option<Span> },
 
     /// Duplicate variable declaration in same scope
-    DuplicateDeclaration {
-        name: String,
-        position: Option<Position>,
-    },
+    DuplicateDeclaration { name: String, span: Option<Span> },
 
     /// Invalid operation on type
     InvalidOperation {
         operation: String,
         type_name: String,
-        position: Option<Position>,
+        span: Option<Span>,
     },
 
     /// Unsuported operator in assignment expression
     UnsupportedOperator {
         operator: String,
-        position: Option<Position>,
+        span: Option<Span>,
     },
+
+    /// `break` or `continue` used outside of a loop
+    JumpOutsideLoop { keyword: String, span: Option<Span> },
+
+    /// `break` or `continue` refers to a label no enclosing loop has
+    UndeclaredLabel { name: String, span: Option<Span> },
+
+    /// Loop label already used by an enclosing loop
+    DuplicateLabel { name: String, span: Option<Span> },
+
+    /// Property access or indexing on a type that has no members
+    InvalidMemberAccess {
+        property: String,
+        type_name: String,
+        span: Option<Span>,
+    },
+
+    /// `return` used outside of a function body
+    ReturnOutsideFunction { span: Option<Span> },
+
+    /// Function returns a value on some paths but not on all of them
+    MissingReturn { name: String, span: Option<Span> },
+
+    /// Match expression has no arm for some values of its subject
+    NonExhaustiveMatch { missing: String, span: Option<Span> },
 }
 
 impl std::fmt::Display for SemanticError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            SemanticError::UndeclaredVariable { name, position } => {
+            SemanticError::UndeclaredVariable { name, span } => {
                 write!(f, "Undeclared variable '{}'", name)?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
-            SemanticError::UninitializedVariable { name, position } => {
+            SemanticError::UninitializedVariable { name, span } => {
                 write!(f, "Variable '{}' is used before being initialized", name)?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
-            SemanticError::ConstReassignment { name, position } => {
+            SemanticError::ConstReassignment { name, span } => {
                 write!(f, "Cannot reassign const variable '{}'", name)?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
             SemanticError::TypeMismatch {
                 expected,
                 found,
-                position,
+                span,
             } => {
                 write!(f, "Type mismatch: expected {}, found {}", expected, found)?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
-            SemanticError::InvalidType {
-                type_name,
-                position,
-            } => {
+            SemanticError::InvalidType { type_name, span } => {
                 write!(f, "Invalid type '{}'", type_name)?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
-            SemanticError::UndeclaredFunction { name, position } => {
+            SemanticError::UndeclaredFunction { name, span } => {
                 write!(f, "Undeclared function '{}'", name)?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
@@ -134,62 +172,208 @@ impl std::fmt::Display for SemanticError {
                 function_name,
                 expected,
                 found,
-                position,
+                span,
             } => {
                 write!(
                     f,
                     "Function '{}' expects {} arguments, but {} were provided",
                     function_name, expected, found
                 )?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
-            SemanticError::InvalidThisUsage { position } => {
+            SemanticError::InvalidNamedArgument {
+                function_name,
+                name,
+                span,
+            } => {
+                write!(
+                    f,
+                    "Function '{}' has no parameter '{}' left to pass by name",
+                    function_name, name
+                )?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
+                }
+                Ok(())
+            }
+            SemanticError::InvalidThisUsage { span } => {
                 write!(f, "Invalid use of 'this' outside of method or constructor")?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
-            SemanticError::DuplicateDeclaration { name, position } => {
+            SemanticError::InvalidSuperUsage { span } => {
+                write!(
+                    f,
+                    "Invalid use of 'super' outside of a class that extends another"
+                )?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
+                }
+                Ok(())
+            }
+            SemanticError::DuplicateDeclaration { name, span } => {
                 write!(f, "Duplicate declaration of '{}'", name)?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
             SemanticError::InvalidOperation {
                 operation,
                 type_name,
-                position,
+                span,
             } => {
                 write!(
                     f,
                     "Invalid operation '{}' on type '{}'",
                     operation, type_name
                 )?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
-            SemanticError::UnsupportedOperator { operator, position } => {
+            SemanticError::UnsupportedOperator { operator, span } => {
                 write!(f, "Unsuported operator found '{}'", operator)?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
+                }
+                Ok(())
+            }
+            SemanticError::JumpOutsideLoop { keyword, span } => {
+                write!(f, "'{}' used outside of a loop", keyword)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
+                }
+                Ok(())
+            }
+            SemanticError::UndeclaredLabel { name, span } => {
+                write!(f, "Undeclared loop label '{}'", name)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
+                }
+                Ok(())
+            }
+            SemanticError::DuplicateLabel { name, span } => {
+                write!(f, "Duplicate loop label '{}'", name)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
+                }
+                Ok(())
+            }
+            SemanticError::InvalidMemberAccess {
+                property,
+                type_name,
+                span,
+            } => {
+                write!(
+                    f,
+                    "Cannot access property '{}' on type '{}'",
+                    property, type_name
+                )?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
-            SemanticError::InvalidVariableDeclaration { name, position } => {
+            SemanticError::ReturnOutsideFunction { span } => {
+                write!(f, "'return' used outside of a function")?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
+                }
+                Ok(())
+            }
+            SemanticError::MissingReturn { name, span } => {
+                write!(
+                    f,
+                    "Function '{}' does not return a value on all paths",
+                    name
+                )?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
+                }
+                Ok(())
+            }
+            SemanticError::NonExhaustiveMatch { missing, span } => {
+                write!(f, "Match is not exhaustive, missing: {}", missing)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
+                }
+                Ok(())
+            }
+            SemanticError::InvalidVariableDeclaration { name, span } => {
                 write!(
                     f,
                     "Variable cannot be created without type and initializer '{}'",
                     name
                 )?;
-                if let Some(pos) = position {
-                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                if let Some(span) = span {
+                    write!(
+                        f,
+                        " at line {}, column {}",
+                        span.start.line, span.start.column
+                    )?;
                 }
                 Ok(())
             }
@@ -197,4 +381,33 @@ impl std::fmt::Display for SemanticError {
     }
 }
 
+impl SemanticError {
+    /// The source span the error points at, if known
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            SemanticError::UndeclaredVariable { span, .. }
+            | SemanticError::InvalidVariableDeclaration { span, .. }
+            | SemanticError::UninitializedVariable { span, .. }
+            | SemanticError::ConstReassignment { span, .. }
+            | SemanticError::TypeMismatch { span, .. }
+            | SemanticError::InvalidType { span, .. }
+            | SemanticError::UndeclaredFunction { span, .. }
+            | SemanticError::WrongArgumentCount { span, .. }
+            | SemanticError::InvalidNamedArgument { span, .. }
+            | SemanticError::InvalidThisUsage { span }
+            | SemanticError::InvalidSuperUsage { span }
+            | SemanticError::DuplicateDeclaration { span, .. }
+            | SemanticError::InvalidOperation { span, .. }
+            | SemanticError::UnsupportedOperator { span, .. }
+            | SemanticError::JumpOutsideLoop { span, .. }
+            | SemanticError::UndeclaredLabel { span, .. }
+            | SemanticError::DuplicateLabel { span, .. }
+            | SemanticError::InvalidMemberAccess { span, .. }
+            | SemanticError::ReturnOutsideFunction { span }
+            | SemanticError::MissingReturn { span, .. }
+            | SemanticError::NonExhaustiveMatch { span, .. } => span.as_ref(),
+        }
+    }
+}
+
 impl std::error::Error for SemanticError {}
@@ -1,12 +1,14 @@
 use lumi_ast::Node;
 
-use crate::errors::SemanticError;
-
 pub mod analyzer;
 mod errors;
 mod scope;
 mod types;
 
+pub use errors::SemanticError;
+pub use scope::{FunctionInfo, Scope, ScopeType, VariableInfo};
+pub use types::{EnumVariants, Type, TypeEnvironment};
+
 /// Result type for semantic analysis
 pub type SemanticResult<T> = Result<T, SemanticError>;
 
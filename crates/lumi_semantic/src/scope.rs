@@ -43,6 +43,9 @@ pub struct FunctionInfo {
     /// Function name
     pub name: String,
 
+    /// Parameter names, used to match named arguments
+    pub param_names: Vec<String>,
+
     /// Parameter types
     pub param_types: Vec<Type>,
 
@@ -75,6 +78,12 @@ pub enum ScopeType {
     Module,
 }
 
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Scope {
     pub fn new() -> Self {
         Self {
@@ -110,11 +119,11 @@ impl Scope {
         self.variables.insert(
             name.clone(),
             VariableInfo {
-                name: name,
+                name,
                 type_info: var_type,
                 mutable: true,
                 initialized: true,
-                line: line,
+                line,
             },
         );
     }
@@ -177,7 +186,7 @@ impl Scope {
             || self
                 .parent
                 .as_ref()
-                .map_or(false, |p| p.is_variable_declared(name))
+                .is_some_and(|p| p.is_variable_declared(name))
     }
 
     /// Check if a variable is declared only in this scope (not in parent)
@@ -189,6 +198,7 @@ impl Scope {
     pub fn declare_function(
         &mut self,
         name: &str,
+        param_names: Vec<String>,
         param_types: Vec<Type>,
         return_type: Type,
         is_method: bool,
@@ -201,6 +211,7 @@ impl Scope {
                 name.to_string(),
                 FunctionInfo {
                     name: name.to_string(),
+                    param_names,
                     param_types,
                     return_type,
                     is_method,
@@ -224,7 +235,7 @@ impl Scope {
             || self
                 .parent
                 .as_ref()
-                .map_or(false, |p| p.is_function_declared(name))
+                .is_some_and(|p| p.is_function_declared(name))
     }
 
     /// Get the scope type
@@ -242,6 +253,15 @@ impl Scope {
         &self.functions
     }
 
+    /// Check if this scope or one of its parents is of `scope_type`
+    pub fn is_inside(&self, scope_type: ScopeType) -> bool {
+        self.scope_type == scope_type
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|p| p.is_inside(scope_type))
+    }
+
     /// Check if this is a function scope
     pub fn is_function_scope(&self) -> bool {
         matches!(self.scope_type, ScopeType::Function)
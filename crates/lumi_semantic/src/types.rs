@@ -1,9 +1,12 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
 
 use serde::{Deserialize, Serialize};
 
 /// Represents a Lumi type
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub enum Type {
     /// Undefined type
     Undefined,
@@ -14,9 +17,15 @@ pub enum Type {
     /// Boolean type
     Boolean,
 
-    /// Number type (including NaN and Infinity)
+    /// 64-bit integer type
+    Integer,
+
+    /// Floating point number type (including NaN and Infinity)
     Number,
 
+    /// Arbitrary-precision integer type
+    BigInt,
+
     /// String type
     String,
 
@@ -26,19 +35,32 @@ pub enum Type {
     /// Object type
     Object,
 
+    /// Object with known property types, compared by shape
+    Record(BTreeMap<String, Type>),
+
     /// Array type
     Array(Box<Type>),
 
-    /// Function type with parameter types and return type
+    /// Function type with parameter types and return type, the parameters after
+    /// `required` may be left out and `rest` takes any number of extra arguments
     Function {
         params: Vec<Type>,
+        required: usize,
+        rest: Option<Box<Type>>,
         return_type: Box<Type>,
     },
 
+    /// A class by name, `new` creates its instances
+    Class(String),
+
+    /// An enum by name, its values are the variants of its declaration
+    Enum(String),
+
     /// Union type (e.g., string | number)
     Union(Vec<Type>),
 
     /// Any type (can be anything)
+    #[default]
     Any,
 
     /// Type is unkown
@@ -52,21 +74,40 @@ impl Type {
             (Type::Any, _) | (_, Type::Any) => true,
             (Type::Union(types), other) => types.iter().any(|t| t.is_compatible_with(other)),
             (Type::Array(inner1), Type::Array(inner2)) => inner1.is_compatible_with(inner2),
+            (Type::Object, Type::Record(_)) | (Type::Record(_), Type::Object) => true,
+            (Type::Record(fields1), Type::Record(fields2)) => {
+                fields1.len() == fields2.len()
+                    && fields1.iter().all(|(name, t1)| {
+                        fields2
+                            .get(name)
+                            .is_some_and(|t2| t1.is_compatible_with(t2))
+                    })
+            }
             (
                 Type::Function {
                     params: p1,
+                    required: q1,
+                    rest: s1,
                     return_type: r1,
                 },
                 Type::Function {
                     params: p2,
+                    required: q2,
+                    rest: s2,
                     return_type: r2,
                 },
             ) => {
                 p1.len() == p2.len()
+                    && q1 == q2
                     && p1
                         .iter()
                         .zip(p2.iter())
                         .all(|(a, b)| a.is_compatible_with(b))
+                    && match (s1, s2) {
+                        (Some(a), Some(b)) => a.is_compatible_with(b),
+                        (None, None) => true,
+                        _ => false,
+                    }
                     && r1.is_compatible_with(r2)
             }
             (Type::Boolean, Type::Boolean) => true,
@@ -85,6 +126,38 @@ impl Type {
         }
     }
 
+    /// Check if a value of type `value` can be stored where this type is expected,
+    /// integers widen to floating point numbers
+    pub fn is_assignable_from(&self, value: &Type) -> bool {
+        match (self, value) {
+            // Records may have more properties than the expected shape
+            (Type::Record(expected), Type::Record(actual)) => {
+                expected.iter().all(|(name, expected_type)| {
+                    actual
+                        .get(name)
+                        .is_some_and(|actual_type| expected_type.is_assignable_from(actual_type))
+                })
+            }
+            (Type::Object, Type::Record(_)) => true,
+            _ => self == value || matches!((self, value), (Type::Number, Type::Integer)),
+        }
+    }
+
+    /// A function type taking exactly `params`
+    pub fn function(params: Vec<Type>, return_type: Type) -> Type {
+        Type::Function {
+            required: params.len(),
+            params,
+            rest: None,
+            return_type: Box::new(return_type),
+        }
+    }
+
+    /// Check if this type is one of the number types
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Type::Integer | Type::Number | Type::BigInt)
+    }
+
     /// Check if this type is a primitive type
     pub fn is_primitive(&self) -> bool {
         matches!(
@@ -92,7 +165,9 @@ impl Type {
             Type::Undefined
                 | Type::Null
                 | Type::Boolean
+                | Type::Integer
                 | Type::Number
+                | Type::BigInt
                 | Type::String
                 | Type::Symbol
         )
@@ -100,45 +175,76 @@ impl Type {
 
     /// Check if this type is an object type
     pub fn is_object(&self) -> bool {
-        matches!(self, Type::Object | Type::Array(_) | Type::Function { .. })
+        matches!(
+            self,
+            Type::Object
+                | Type::Record(_)
+                | Type::Array(_)
+                | Type::Function { .. }
+                | Type::Class(_)
+                | Type::Enum(_)
+        )
     }
+}
 
-    pub fn to_string(&self) -> String {
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Type::Undefined => "undefined".to_string(),
-            Type::Null => "null".to_string(),
-            Type::Boolean => "boolean".to_string(),
-            Type::Number => "number".to_string(),
-            Type::String => "string".to_string(),
-            Type::Symbol => "symbol".to_string(),
-            Type::Object => "object".to_string(),
-            Type::Array(inner) => format!("Array<{}>", inner.to_string()),
+            Type::Undefined => write!(f, "undefined"),
+            Type::Null => write!(f, "null"),
+            Type::Boolean => write!(f, "boolean"),
+            Type::Integer => write!(f, "int"),
+            Type::Number => write!(f, "float"),
+            Type::BigInt => write!(f, "bigint"),
+            Type::String => write!(f, "string"),
+            Type::Symbol => write!(f, "symbol"),
+            Type::Object => write!(f, "object"),
+            Type::Record(fields) => {
+                let fields_str: Vec<String> = fields
+                    .iter()
+                    .map(|(name, t)| format!("{}: {}", name, t))
+                    .collect();
+                write!(f, "{{ {} }}", fields_str.join(", "))
+            }
+            Type::Array(inner) => write!(f, "Array<{}>", inner),
+            Type::Class(name) => write!(f, "class {}", name),
+            Type::Enum(name) => write!(f, "{}", name),
             Type::Function {
                 params,
+                required,
+                rest,
                 return_type,
             } => {
-                let params_str: Vec<String> = params.iter().map(|p| p.to_string()).collect();
-                format!(
+                let mut params_str: Vec<String> = params
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| match i < *required {
+                        true => p.to_string(),
+                        false => format!("{}?", p),
+                    })
+                    .collect();
+                if let Some(rest) = rest {
+                    params_str.push(format!("...{}", rest));
+                }
+                write!(
+                    f,
                     "Function<({}) -> {}>",
                     params_str.join(", "),
-                    return_type.to_string()
+                    return_type
                 )
             }
             Type::Union(types) => {
                 let types_str: Vec<String> = types.iter().map(|t| t.to_string()).collect();
-                types_str.join(" | ")
+                write!(f, "{}", types_str.join(" | "))
             }
-            Type::Any => "any".to_string(),
-            Type::Unknown => "unkown".to_string(),
+            Type::Any => write!(f, "any"),
+            Type::Unknown => write!(f, "unkown"),
         }
     }
 }
 
-impl Default for Type {
-    fn default() -> Self {
-        Type::Any
-    }
-}
+/// The variants of an enum with the types of their fields, in declaration order
+pub type EnumVariants = Vec<(String, Vec<Type>)>;
 
 /// Type environment for tracking variable types
 #[derive(Debug, Clone)]
@@ -146,6 +252,12 @@ pub struct TypeEnvironment {
     types: HashMap<String, Type>,
 }
 
+impl Default for TypeEnvironment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TypeEnvironment {
     pub fn new() -> Self {
         Self {
@@ -168,6 +280,11 @@ impl TypeEnvironment {
         self.types.contains_key(name)
     }
 
+    /// Forget the declared type of a variable, for a name shadowed without one
+    pub fn remove(&mut self, name: &str) {
+        self.types.remove(name);
+    }
+
     /// Update the type of an existing variable
     pub fn update_type(&mut self, name: &str, type_info: Type) -> bool {
         if self.types.contains_key(name) {
@@ -10,7 +10,7 @@ fn test_invalid_operation_assignment_expression_plus_assign() {
     if let Err(e) = result {
         assert_eq!(
             e.to_string(),
-            "Invalid operation '+=' on type 'number' at line 1, column 36"
+            "Invalid operation '+=' on type 'int' at line 1, column 29"
         );
     }
 }
@@ -24,7 +24,7 @@ fn test_invalid_operation_assignment_expression_minus_assign() {
     if let Err(e) = result {
         assert_eq!(
             e.to_string(),
-            "Invalid operation '-=' on type 'number' at line 1, column 36"
+            "Invalid operation '-=' on type 'int' at line 1, column 29"
         );
     }
 }
@@ -39,7 +39,46 @@ fn test_invalid_operation_assignment_expression_minus_assign() {
 //     if let Err(e) = result {
 //         assert_eq!(
 //             e.to_string(),
-//             "Unsupported operator found '$' at line 1, column 36"
+//             "Unsupported operator found '$' at line 1, column 29"
 //         );
 //     }
 // }
+
+#[test]
+fn test_compound_assignment_operators() {
+    let mut parser = Parser::new("let x: int -> 5; x *= 2; x %= 3; x **= 2;");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new("let x: int -> 5; x /= 2");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::InvalidOperation { .. })
+    ));
+
+    let mut parser = Parser::new("let x: float -> 5.0; x /= 2");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_update_operators() {
+    let mut parser = Parser::new("let x: int -> 5; x++; --x;");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new("let s: str -> \"a\"; s++;");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::InvalidOperation { .. })
+    ));
+
+    let mut parser = Parser::new("const c -> 1; c--;");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::ConstReassignment { .. })
+    ));
+}
@@ -1,3 +1,4 @@
+use lumi_ast::FileId;
 use lumi_parser::Parser;
 use lumi_semantic::analyze;
 
@@ -10,7 +11,7 @@ fn test_type_mismatch_number_and_string() {
     if let Err(e) = result {
         assert_eq!(
             e.to_string(),
-            "Type mismatch: expected number, found string at line 1, column 27"
+            "Type mismatch: expected int, found string at line 1, column 23"
         );
     }
 }
@@ -24,7 +25,7 @@ fn test_type_mismatch_string_and_number() {
     if let Err(e) = result {
         assert_eq!(
             e.to_string(),
-            "Type mismatch: expected string, found number at line 1, column 22"
+            "Type mismatch: expected string, found int at line 1, column 18"
         );
     }
 }
@@ -37,6 +38,28 @@ fn test_declare_number_variable() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_declare_float_variable() {
+    let mut parser = Parser::new("let x: float -> 4.2; let y: float -> 42;");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_type_mismatch_int_and_float() {
+    let mut parser = Parser::new("let x: int -> 4.2;");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(
+            e.to_string(),
+            "Type mismatch: expected int, found float at line 1, column 19"
+        );
+    }
+}
+
 #[test]
 fn test_declare_string_variable() {
     let mut parser = Parser::new("let x: str -> \"hello\";");
@@ -106,3 +129,39 @@ fn test_wrong_function_call() {
     let result = analyze(&ast);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_interpolated_string_is_string() {
+    let mut parser = Parser::new("let x: int -> 1; let y: str -> \"count: ${x + 1}\";");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_interpolated_string_undeclared_variable() {
+    let mut parser = Parser::new("let y -> \"count: ${z}\";");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_error_span_points_into_file() {
+    let mut parser = Parser::with_file("let a -> 1;\nlet x: int -> \"hello\";", FileId(2));
+    let ast = parser.parse().unwrap();
+    let error = analyze(&ast).unwrap_err();
+
+    let span = error.span().expect("Expected the error to carry a span");
+    assert_eq!(span.file, FileId(2));
+    assert_eq!(span.start.line, 2);
+}
+
+#[test]
+fn test_normalized_identifiers_resolve_to_same_variable() {
+    // Declared with a precomposed "é", used with "e" plus a combining accent
+    let mut parser = Parser::new("let caf\u{e9}: int -> 1; let y: int -> cafe\u{301};");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok());
+}
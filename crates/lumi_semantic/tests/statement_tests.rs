@@ -8,3 +8,877 @@ fn test_valid_if_statement() {
     let result = analyze(&ast);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_break_and_continue_inside_loops() {
+    let mut parser = Parser::new(
+        r#"
+        outer: loop {
+            while (true) {
+                continue outer;
+            }
+            do { break; } while (false);
+            break outer;
+        }
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok(), "{:?}", result);
+}
+
+#[test]
+fn test_break_outside_loop() {
+    let mut parser = Parser::new("break;");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "'break' used outside of a loop at line 1, column 1"
+    );
+}
+
+#[test]
+fn test_continue_inside_function_does_not_see_outer_loop() {
+    let mut parser = Parser::new("loop { fn f() { continue; } break; }");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(matches!(
+        result,
+        Err(lumi_semantic::SemanticError::JumpOutsideLoop { .. })
+    ));
+}
+
+#[test]
+fn test_undeclared_label() {
+    let mut parser = Parser::new("loop { break missing; }");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert!(e.to_string().starts_with("Undeclared loop label 'missing'"));
+    }
+}
+
+#[test]
+fn test_duplicate_label() {
+    let mut parser = Parser::new("a: loop { a: loop { break a; } }");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert!(e.to_string().starts_with("Duplicate loop label 'a'"));
+    }
+}
+
+#[test]
+fn test_for_iterator_is_declared() {
+    let mut parser = Parser::new("for i in 0 to 3 step 1 { let x -> i; }");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok(), "{:?}", result);
+}
+
+#[test]
+fn test_return_type_is_inferred() {
+    let mut parser = Parser::new(
+        r#"
+        fn answer() {
+            if (true) { return 1; }
+            return 2;
+        }
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    let mut analyzer = lumi_semantic::analyzer::SemanticAnalyzer::new();
+    assert!(analyzer.analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_function_without_return_is_valid() {
+    let mut parser = Parser::new("fn log(x) { print x; return; }");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_missing_return_on_some_paths() {
+    let mut parser = Parser::new("fn pick() { if (true) { return 1; } }");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert!(
+            e.to_string()
+                .starts_with("Function 'pick' does not return a value on all paths")
+        );
+    }
+}
+
+#[test]
+fn test_loop_without_break_always_returns() {
+    let mut parser = Parser::new("fn forever() { loop { return 1; } }");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_break_of_nested_loop_does_not_leave_outer_loop() {
+    let mut parser = Parser::new("fn f(): int { loop { loop { break; } return 1; } }");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new("fn f(): int { outer: loop { loop { break outer; } return 1; } }");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::MissingReturn { .. })
+    ));
+}
+
+#[test]
+fn test_throw_try_and_switch_can_end_a_function() {
+    for source in [
+        r#"fn f(x: bool): int { if (x) { return 1; } throw "no"; }"#,
+        r#"fn f(): int { try { return 1; } catch (e) { throw e; } }"#,
+        r#"fn f(): int { try { let x -> 1; } finally { return 2; } }"#,
+        r#"fn f(x: int): int { switch (x) { case 1: return 1; default: throw "no"; } }"#,
+    ] {
+        let mut parser = Parser::new(source);
+        let ast = parser.parse().unwrap();
+        assert!(analyze(&ast).is_ok(), "{source}");
+    }
+
+    for source in [
+        r#"fn f(): int { try { return 1; } catch (e) { let x -> e; } }"#,
+        r#"fn f(x: int): int { switch (x) { case 1: return 1; case 2: return 2; } }"#,
+    ] {
+        let mut parser = Parser::new(source);
+        let ast = parser.parse().unwrap();
+        assert!(
+            matches!(
+                analyze(&ast),
+                Err(lumi_semantic::SemanticError::MissingReturn { .. })
+            ),
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn test_return_outside_function() {
+    let mut parser = Parser::new("return 1;");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(matches!(
+        result,
+        Err(lumi_semantic::SemanticError::ReturnOutsideFunction { .. })
+    ));
+}
+
+#[test]
+fn test_member_access_on_string() {
+    let mut parser = Parser::new(r#"let s: str -> "abc"; let c: str -> s[0];"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_member_access_on_primitive() {
+    let mut parser = Parser::new("let n: int -> 1; n.size;");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert!(
+            e.to_string()
+                .starts_with("Cannot access property 'size' on type 'int'")
+        );
+    }
+}
+
+#[test]
+fn test_object_literal_shape() {
+    let mut parser = Parser::new(r#"let o -> { a: 1, b: "two" }; let b: str -> o.b; o.a = 3;"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_object_missing_property() {
+    let mut parser = Parser::new("let o -> { a: 1 }; o.b;");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert!(
+            e.to_string()
+                .starts_with("Cannot access property 'b' on type '{ a: int }'")
+        );
+    }
+}
+
+#[test]
+fn test_object_property_type_mismatch() {
+    let mut parser = Parser::new(r#"let o -> { a: 1 }; o.a = "one";"#);
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(matches!(
+        result,
+        Err(lumi_semantic::SemanticError::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_array_element_type_is_inferred() {
+    let mut parser = Parser::new(
+        r#"
+        let a -> [1, 2, 3];
+        let first: int -> a[0];
+        let size: int -> a.len;
+        a.push(4);
+        let b -> [0, ...a];
+        let second: int -> b[1];
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok(), "{:?}", result);
+}
+
+#[test]
+fn test_array_element_type_mismatch() {
+    let mut parser = Parser::new(r#"let a -> [1, 2]; let s: str -> a[0];"#);
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert!(e.to_string().starts_with("Type mismatch"), "{}", e);
+    }
+}
+
+#[test]
+fn test_spread_of_non_iterable() {
+    let mut parser = Parser::new("let n: int -> 1; let a -> [...n];");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(matches!(
+        result,
+        Err(lumi_semantic::SemanticError::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_closure_sees_enclosing_variables() {
+    let mut parser = Parser::new(
+        r#"
+        fn makeCounter() {
+            let count -> 0;
+            return () => {
+                count += 1;
+                return count;
+            };
+        }
+        let counter -> makeCounter();
+        counter();
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_arrow_return_type_is_inferred() {
+    let mut parser = Parser::new(r#"let greet -> (name) => "hi"; let s: str -> greet("lumi");"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new(r#"let greet -> (name) => "hi"; let n: int -> greet("lumi");"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+}
+
+#[test]
+fn test_recursive_function_sees_itself() {
+    let mut parser = Parser::new("fn spin(n) { return spin(n); }");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_function_expression_name_is_local() {
+    let mut parser = Parser::new("let f -> fn inner() { return 1; }; inner();");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+}
+
+#[test]
+fn test_calling_a_non_function() {
+    let mut parser = Parser::new("let n -> 1; n();");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+}
+
+#[test]
+fn test_calling_a_union_of_functions() {
+    let mut parser = Parser::new(
+        "fn inc(x: int): int { return x + 1; } fn get(): int { return 0; } \
+         let n: int -> [inc, get][1](2);",
+    );
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok(), "{:?}", result);
+
+    let mut parser = Parser::new("fn get(): int { return 0; } let f -> true ? get : 1; f();");
+    let ast = parser.parse().unwrap();
+    match analyze(&ast) {
+        Err(lumi_semantic::SemanticError::TypeMismatch {
+            expected,
+            found,
+            span,
+        }) => {
+            assert_eq!(expected, "function");
+            assert_eq!(found, "Function<() -> int> | int");
+            assert!(span.is_some());
+        }
+        result => panic!("Expected a type mismatch, got {result:?}"),
+    }
+}
+
+#[test]
+fn test_typed_parameters_and_return() {
+    let mut parser = Parser::new(
+        r#"
+        fn add(a: int, b: int): int { return a + b; }
+        fn twice(s: str): str { return s; }
+        let n: int -> add(1, 2);
+        let s: str -> twice("lumi");
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_wrong_argument_count() {
+    let mut parser = Parser::new("fn add(a: int, b: int): int { return a + b; } add(1);");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::WrongArgumentCount {
+            expected: 2,
+            found: 1,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_wrong_argument_type() {
+    let mut parser = Parser::new(r#"fn add(a: int, b: int): int { return a + b; } add(1, "2");"#);
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_return_type_annotation_is_checked() {
+    let mut parser = Parser::new(r#"fn name(): int { return "lumi"; }"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+
+    let mut parser = Parser::new("fn pick(): int { if (true) { return 1; } }");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::MissingReturn { .. })
+    ));
+}
+
+#[test]
+fn test_bare_return_in_typed_function() {
+    let mut parser = Parser::new("fn f(): int { return; }");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::TypeMismatch { .. })
+    ));
+
+    let mut parser = Parser::new("fn f(x: int): int { if (true) { return; } return x; }");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+}
+
+#[test]
+fn test_annotated_return_type_is_used_by_callers() {
+    let mut parser = Parser::new("fn half(x: float): float { return x; } let s: str -> half(1);");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+}
+
+#[test]
+fn test_untyped_parameter_accepts_any_use() {
+    let mut parser = Parser::new("fn f(x) { if (x) { print x; } return x + 1; }");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok(), "{:?}", result);
+}
+
+#[test]
+fn test_parameter_types_are_scoped_to_their_function() {
+    let mut parser = Parser::new(r#"fn f(a: int) { a -> "s"; }"#);
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::TypeMismatch { .. })
+    ));
+
+    // An untyped parameter shadows the declared type of an outer variable
+    let mut parser = Parser::new(r#"let a: int -> 1; fn f(a) { a -> "s"; }"#);
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok(), "{:?}", result);
+
+    // Neither parameters nor locals keep their type after the function
+    let mut parser = Parser::new(
+        r#"fn f(a: int) { let b: int -> 1; } let a -> "s"; let b -> "t"; a -> "u"; b -> "v";"#,
+    );
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok(), "{:?}", result);
+}
+
+#[test]
+fn test_optional_and_default_parameters_widen_arity() {
+    let mut parser = Parser::new(
+        r#"
+        fn greet(name: str, greeting -> "hi", punct?: str): str { return greeting; }
+        greet("lumi");
+        greet("lumi", "hey");
+        greet("lumi", "hey", "!");
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new(r#"fn greet(name, greeting -> "hi") {} greet();"#);
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::WrongArgumentCount {
+            expected: 1,
+            found: 0,
+            ..
+        })
+    ));
+
+    let mut parser = Parser::new(r#"fn greet(name, greeting -> "hi") {} greet(1, "a", "b");"#);
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::WrongArgumentCount {
+            expected: 2,
+            found: 3,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_default_value_types_the_parameter() {
+    let mut parser = Parser::new(r#"fn greet(greeting -> "hi") {} greet(1);"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+
+    let mut parser = Parser::new(r#"fn count(n: int -> "one") {}"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+}
+
+#[test]
+fn test_rest_parameter_takes_any_number_of_arguments() {
+    let mut parser = Parser::new(
+        r#"
+        fn sum(first: int, ...rest: int): int { return first; }
+        sum(1);
+        sum(1, 2, 3);
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new(r#"fn sum(...rest: int) {} sum(1, "2");"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+}
+
+#[test]
+fn test_named_arguments() {
+    let mut parser = Parser::new(
+        r#"
+        fn greet(name: str, greeting -> "hi", punct?: str) {}
+        greet(name: "lumi");
+        greet("lumi", punct: "!");
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new(r#"fn greet(name, greeting -> "hi") {} greet(greeting: "hey");"#);
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::WrongArgumentCount { .. })
+    ));
+
+    let mut parser = Parser::new(r#"fn greet(name) {} greet("lumi", nickname: "l");"#);
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::InvalidNamedArgument { .. })
+    ));
+
+    let mut parser = Parser::new(r#"fn greet(name: str) {} greet(name: 1);"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+}
+
+#[test]
+fn test_conditional_type_is_common_type_of_branches() {
+    let mut parser = Parser::new("let n: int -> true ? 1 : 2;");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new(r#"let n: int -> true ? 1 : "two";"#);
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_nullish_coalescing_type() {
+    let mut parser = Parser::new(r#"let s: str -> null ?? "fallback";"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new(r#"let name -> "lumi"; let n: int -> name ?? 1;"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+
+    let mut parser = Parser::new(r#"let name: str -> "lumi"; name ??= 1;"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_err());
+}
+
+#[test]
+fn test_nullish_assignment_to_member() {
+    let mut parser = Parser::new("let o -> { a: null }; o.a ??= 3;");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok(), "{:?}", result);
+
+    let mut parser = Parser::new(r#"let o -> { a: "set" }; o.a ??= 3;"#);
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_class_declaration() {
+    let mut parser = Parser::new(
+        r#"
+        class Animal {
+            name: str -> "";
+            constructor(name) { this.name -> name; }
+        }
+        class Dog extends Animal {
+            fn speak() { return super.speak(); }
+        }
+        let d -> new Dog("rex");
+        "#,
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new(r#"class A { count: int -> "zero"; }"#);
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::TypeMismatch { .. })
+    ));
+
+    let mut parser = Parser::new("let n -> 1; let x -> new n();");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::TypeMismatch { .. })
+    ));
+
+    let mut parser = Parser::new("class A {} class A {}");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::DuplicateDeclaration { .. })
+    ));
+}
+
+#[test]
+fn test_this_and_super_outside_of_a_class() {
+    let mut parser = Parser::new("fn f() { return this; }");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::InvalidThisUsage { .. })
+    ));
+
+    let mut parser = Parser::new("class A { fn f() { return super.f(); } }");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::InvalidSuperUsage { .. })
+    ));
+}
+
+#[test]
+fn test_try_statement_scopes_the_catch_parameter() {
+    let mut parser = Parser::new(
+        r#"
+        try { throw "oops"; } catch (e) { let message -> e; } finally { let done -> true; }
+        "#,
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new("try { throw 1; } catch (e) {} let x -> e;");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::UndeclaredVariable { .. })
+    ));
+}
+
+#[test]
+fn test_break_in_switch() {
+    let mut parser =
+        Parser::new("let x: int -> 1; switch (x) { case 1: break; default: print x; }");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new("let x: int -> 1; switch (x) { case 1: continue; }");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::JumpOutsideLoop { .. })
+    ));
+
+    // The `break` leaves the switch, not the loop
+    let mut parser =
+        Parser::new("fn f(x: int): int { loop { switch (x) { case 1: break; } return 1; } }");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_match_bindings_are_scoped_to_their_arm() {
+    let mut parser = Parser::new(
+        r#"
+        let x: int -> 3;
+        let label -> match (x) { 1 => "one", n => n };
+        switch (x) { case 1: let y -> 1; default: let y -> 2; }
+        "#,
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new("let x: int -> 3; let a -> match (x) { n => n }; let b -> n;");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::UndeclaredVariable { .. })
+    ));
+}
+
+#[test]
+fn test_match_exhaustiveness() {
+    let mut parser =
+        Parser::new("let flag: bool -> true; let label -> match (flag) { true => 1, false => 0 };");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    for (source, expected) in [
+        (
+            "let flag: bool -> true; let label -> match (flag) { true => 1 };",
+            "false",
+        ),
+        // A guarded arm may not match, so it covers nothing
+        (
+            "let flag: bool -> true; let label -> match (flag) { true => 1, false if flag => 0 };",
+            "false",
+        ),
+        (
+            "let x: int -> 1; let label -> match (x) { 1 => \"one\", 2 => \"two\" };",
+            "int",
+        ),
+        (
+            "let x -> true ? 1 : null; let label -> match (x) { 1 => \"one\" };",
+            "int, null",
+        ),
+        (
+            "let o -> { k: 1 }; let label -> match (o) { { k: 1 } => 1, { j } => 2 };",
+            "{ k: int }",
+        ),
+    ] {
+        let mut parser = Parser::new(source);
+        let ast = parser.parse().unwrap();
+        match analyze(&ast) {
+            Err(lumi_semantic::SemanticError::NonExhaustiveMatch { missing, .. }) => {
+                assert_eq!(missing, expected, "{source}")
+            }
+            result => panic!("Expected a non-exhaustive match for {source}, got {result:?}"),
+        }
+    }
+
+    let mut parser =
+        Parser::new("let x -> true ? 1 : null; let label -> match (x) { null => 0, _ => 1 };");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    // An object pattern of only bindings matches every object with those keys
+    let mut parser =
+        Parser::new("let o -> { k: 1 }; let label -> match (o) { { k: 1 } => 1, { k } => 2 };");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    assert!(result.is_ok(), "{:?}", result);
+
+    // An untyped parameter can hold anything, the match isn't checked
+    let mut parser = Parser::new(r#"fn f(x) { return match (x) { 1 => "a", 2 => "b" }; }"#);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_enum_constructors_are_typed() {
+    let mut parser = Parser::new(
+        "enum Shape { Circle(r: float), Rect(w: float, h: float), Empty } \
+         let a: Shape -> Shape.Circle(2); let b -> Shape.Empty;",
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new("enum Shape { Circle(r: float) } let a -> Shape.Circle(\"big\");");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::TypeMismatch { .. })
+    ));
+
+    let mut parser = Parser::new("enum Shape { Circle(r: float) } let a -> Shape.Square;");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::InvalidMemberAccess { .. })
+    ));
+}
+
+#[test]
+fn test_enum_match_exhaustiveness() {
+    let declaration = "enum Shape { Circle(r: float), Rect(w: float, h: float), Empty } \
+                       let s: Shape -> Shape.Empty;";
+    let source = format!(
+        "{declaration} let area -> match (s) {{ Shape.Circle(r) => r, Shape.Rect(w, h) => w, Shape.Empty => 0 }};"
+    );
+    let mut parser = Parser::new(&source);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    // The type of an untyped parameter isn't known, so it is never missing a case
+    let source = format!(
+        "{declaration} fn area(shape) {{ return match (shape) {{ Shape.Circle(r) => r, Shape.Rect(w, h) => w * h, Shape.Empty => 0 }}; }}"
+    );
+    let mut parser = Parser::new(&source);
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    for (arms, expected) in [
+        ("Shape.Circle(r) => r", "Shape.Rect, Shape.Empty"),
+        // A refutable field only covers part of its variant
+        (
+            "Shape.Circle(1.5) => 1, Shape.Rect => 2, Shape.Empty => 0",
+            "Shape.Circle",
+        ),
+    ] {
+        let source = format!("{declaration} let area -> match (s) {{ {arms} }};");
+        let mut parser = Parser::new(&source);
+        let ast = parser.parse().unwrap();
+        match analyze(&ast) {
+            Err(lumi_semantic::SemanticError::NonExhaustiveMatch { missing, .. }) => {
+                assert_eq!(missing, expected, "{arms}")
+            }
+            result => panic!("Expected a non-exhaustive match for {arms}, got {result:?}"),
+        }
+    }
+
+    let source =
+        format!("{declaration} let area -> match (s) {{ Shape.Circle(r, x) => r, _ => 0 }};");
+    let mut parser = Parser::new(&source);
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::WrongArgumentCount { .. })
+    ));
+}
+
+#[test]
+fn test_destructuring_is_typed() {
+    let mut parser = Parser::new(
+        "let [a, ...rest] -> [1, 2, 3]; let b: int -> a; let c: int -> rest[0]; \
+         let { x, y: renamed, z -> \"none\" } -> { x: 1, y: true }; \
+         let n: int -> x; let flag: bool -> renamed; let label: str -> z;",
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    for source in [
+        "let [a, b] -> 5;",
+        "let { x } -> true;",
+        "let { missing } -> { x: 1 };",
+        "let [a] -> [1]; let s: str -> a;",
+        "let { x -> \"none\" } -> { x: 1 };",
+        "for item in 10 { print item; }",
+    ] {
+        let mut parser = Parser::new(source);
+        let ast = parser.parse().unwrap();
+        assert!(analyze(&ast).is_err(), "{source}");
+    }
+}
+
+#[test]
+fn test_destructuring_bindings() {
+    let mut parser = Parser::new(
+        "fn first([head, ..._]) { return head; } \
+         for [key, value] in [[1, 2]] { print key + value; } \
+         let a -> 1; let b -> 2; [a, b] -> [b, a];",
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+
+    let mut parser = Parser::new("for [key, value] in [[1, 2]] { print key; } print value;");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::UndeclaredVariable { .. })
+    ));
+
+    let mut parser = Parser::new("const a -> 1; let b -> 2; [a, b] -> [b, a];");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        analyze(&ast),
+        Err(lumi_semantic::SemanticError::ConstReassignment { .. })
+    ));
+}
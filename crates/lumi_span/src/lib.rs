@@ -0,0 +1,5 @@
+mod source_map;
+mod span;
+
+pub use source_map::{SourceFile, SourceMap};
+pub use span::{FileId, Position, Span};
@@ -0,0 +1,186 @@
+use crate::span::{FileId, Position, Span};
+
+/// A source file with its line table
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    id: FileId,
+    name: String,
+    source: String,
+    /// Byte offset at which each line starts
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    pub fn new(id: FileId, name: &str, source: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            id,
+            name: name.to_string(),
+            source: source.to_string(),
+            line_starts,
+        }
+    }
+
+    pub fn id(&self) -> FileId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Clamp an offset to the file and move it back onto a character boundary
+    fn clamp_offset(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+
+    /// Line (1-based) containing the byte offset
+    fn line_index(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        }
+    }
+
+    /// Line and column (1-based, counted in characters) of a byte offset
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = self.clamp_offset(offset);
+        let line = self.line_index(offset);
+        let line_start = self.line_starts[line];
+        let column = self.source[line_start..offset].chars().count() + 1;
+
+        Position::with_offset(line + 1, column, offset)
+    }
+
+    /// Byte offset of a 1-based line and column, if it lies inside the file
+    pub fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        let line_text = self.line_text(line)?;
+        let line_start = self.line_starts[line - 1];
+
+        if column == 0 {
+            return None;
+        }
+        line_text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(line_text.len()))
+            .nth(column - 1)
+            .map(|i| line_start + i)
+    }
+
+    /// Text of a 1-based line, without its line break
+    pub fn line_text(&self, line: usize) -> Option<&str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line)
+            .copied()
+            .unwrap_or(self.source.len());
+
+        Some(self.source[start..end].trim_end_matches(['\n', '\r']))
+    }
+
+    /// Source text covered by a span
+    pub fn slice(&self, span: &Span) -> Option<&str> {
+        self.source.get(span.range())
+    }
+
+    /// Zero-based line and UTF-16 column of a byte offset, as used by the Language Server Protocol
+    pub fn lsp_position(&self, offset: usize) -> (u32, u32) {
+        let offset = self.clamp_offset(offset);
+        let line = self.line_index(offset);
+        let line_start = self.line_starts[line];
+        let character: usize = self.source[line_start..offset]
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+
+        (line as u32, character as u32)
+    }
+
+    /// Render the first line of a span with a caret underline, for error messages
+    pub fn snippet(&self, span: &Span) -> String {
+        let start = self.position(span.start.offset);
+        let end = self.position(span.end.offset);
+        let line_text = self.line_text(start.line).unwrap_or_default();
+
+        let width = if end.line == start.line {
+            end.column.saturating_sub(start.column).max(1)
+        } else {
+            line_text
+                .chars()
+                .count()
+                .saturating_sub(start.column - 1)
+                .max(1)
+        };
+
+        format!(
+            "{:>4} | {}\n     | {}{}",
+            start.line,
+            line_text,
+            " ".repeat(start.column - 1),
+            "^".repeat(width)
+        )
+    }
+}
+
+/// Collection of all source files of a program
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a file and return its id
+    pub fn add_file(&mut self, name: &str, source: &str) -> FileId {
+        let id = FileId(self.files.len());
+        self.files.push(SourceFile::new(id, name, source));
+        id
+    }
+
+    pub fn file(&self, id: FileId) -> Option<&SourceFile> {
+        self.files.get(id.0)
+    }
+
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// Source text covered by a span
+    pub fn slice(&self, span: &Span) -> Option<&str> {
+        self.file(span.file)?.slice(span)
+    }
+
+    /// Render an error location as `name:line:column` followed by a snippet of the line
+    pub fn describe(&self, span: &Span) -> Option<String> {
+        let file = self.file(span.file)?;
+        let start = file.position(span.start.offset);
+
+        Some(format!(
+            "{}:{}:{}\n{}",
+            file.name(),
+            start.line,
+            start.column,
+            file.snippet(span)
+        ))
+    }
+}
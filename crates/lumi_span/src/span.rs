@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, ops::Range};
+
+/// Identifies a file registered in a `SourceMap`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FileId(pub usize);
+
+/// Represents a position in the source code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    /// Byte offset from the start of the file
+    #[serde(default)]
+    pub offset: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self {
+            line,
+            column,
+            offset: 0,
+        }
+    }
+
+    pub fn with_offset(line: usize, column: usize, offset: usize) -> Self {
+        Self {
+            line,
+            column,
+            offset,
+        }
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position::new(1, 1)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Represents a span of source code in one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+    #[serde(default)]
+    pub file: FileId,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self {
+            start,
+            end,
+            file: FileId::default(),
+        }
+    }
+
+    pub fn from_positions(
+        start_line: usize,
+        start_col: usize,
+        end_line: usize,
+        end_col: usize,
+    ) -> Self {
+        Self::new(
+            Position::new(start_line, start_col),
+            Position::new(end_line, end_col),
+        )
+    }
+
+    pub fn with_file(mut self, file: FileId) -> Self {
+        self.file = file;
+        self
+    }
+
+    /// Byte range of the span in its file
+    pub fn range(&self) -> Range<usize> {
+        self.start.offset..self.end.offset
+    }
+
+    /// Span covering both `self` and `other`
+    pub fn to(&self, other: &Span) -> Span {
+        let start = if other.start.offset < self.start.offset {
+            other.start
+        } else {
+            self.start
+        };
+        let end = if other.end.offset > self.end.offset {
+            other.end
+        } else {
+            self.end
+        };
+
+        Span {
+            start,
+            end,
+            file: self.file,
+        }
+    }
+}
@@ -0,0 +1,2 @@
+mod source_map_tests;
+mod span_tests;
@@ -0,0 +1,56 @@
+use lumi_span::{FileId, Position, SourceMap, Span};
+
+fn span(start: usize, end: usize, file: FileId) -> Span {
+    Span::new(
+        Position::with_offset(0, 0, start),
+        Position::with_offset(0, 0, end),
+    )
+    .with_file(file)
+}
+
+#[test]
+fn test_add_files() {
+    let mut map = SourceMap::new();
+    let main = map.add_file("main.lm", "let a -> 1;");
+    let lib = map.add_file("lib.lm", "let b -> 2;");
+
+    assert_ne!(main, lib);
+    assert_eq!(map.file(lib).unwrap().name(), "lib.lm");
+    assert_eq!(map.slice(&span(4, 5, lib)), Some("b"));
+    assert!(map.file(FileId(5)).is_none());
+}
+
+#[test]
+fn test_position_from_offset() {
+    let mut map = SourceMap::new();
+    let id = map.add_file("main.lm", "let a;\nlet é -> 1;\n");
+    let file = map.file(id).unwrap();
+
+    assert_eq!(file.line_count(), 3);
+    let position = file.position(13); // after 'é', which is two bytes
+    assert_eq!((position.line, position.column), (2, 6));
+    assert_eq!(file.offset(2, 6), Some(13));
+    assert_eq!(file.position(12).column, 5); // inside 'é'
+    assert_eq!(file.line_text(2), Some("let é -> 1;"));
+    assert_eq!(file.line_text(4), None);
+}
+
+#[test]
+fn test_lsp_position_counts_utf16_units() {
+    let mut map = SourceMap::new();
+    let id = map.add_file("main.lm", "x\n\"😀\" + y");
+    let file = map.file(id).unwrap();
+
+    // 'y' comes after a quote, a surrogate pair, a quote and " + "
+    let offset = file.source().find('y').unwrap();
+    assert_eq!(file.lsp_position(offset), (1, 7));
+}
+
+#[test]
+fn test_describe_renders_snippet() {
+    let mut map = SourceMap::new();
+    let id = map.add_file("main.lm", "let a -> 1;\nprint b;\n");
+
+    let description = map.describe(&span(18, 19, id)).unwrap();
+    assert_eq!(description, "main.lm:2:7\n   2 | print b;\n     |       ^");
+}
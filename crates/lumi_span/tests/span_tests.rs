@@ -0,0 +1,36 @@
+use lumi_span::{FileId, Position, Span};
+
+#[test]
+fn test_span_range() {
+    let span = Span::new(
+        Position::with_offset(1, 5, 4),
+        Position::with_offset(1, 9, 8),
+    );
+
+    assert_eq!(span.range(), 4..8);
+    assert_eq!(span.file, FileId::default());
+}
+
+#[test]
+fn test_span_to_covers_both_spans() {
+    let first = Span::new(
+        Position::with_offset(1, 1, 0),
+        Position::with_offset(1, 4, 3),
+    )
+    .with_file(FileId(2));
+    let second = Span::new(
+        Position::with_offset(2, 1, 10),
+        Position::with_offset(2, 6, 15),
+    );
+
+    let merged = first.to(&second);
+    assert_eq!(merged.range(), 0..15);
+    assert_eq!(merged.start.line, 1);
+    assert_eq!(merged.end.line, 2);
+    assert_eq!(merged.file, FileId(2));
+}
+
+#[test]
+fn test_position_display() {
+    assert_eq!(Position::new(3, 7).to_string(), "3:7");
+}
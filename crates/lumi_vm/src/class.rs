@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use lumi_bytecode::MethodKind;
+
+use crate::{
+    heap::HandleId,
+    value::{Closure, Value},
+};
+
+/// A class, the parent it extends and the members its instances and itself have
+#[derive(Debug, Clone)]
+pub struct Class {
+    pub name: String,
+    pub superclass: Option<HandleId>,
+    pub constructor: Option<Closure>,
+    pub prototype: Members,             // Shared by the instances of the class
+    pub statics: Members,               // Called on the class itself
+    pub fields: HashMap<String, Value>, // Static fields
+}
+
+impl Class {
+    pub fn new(name: String, superclass: Option<HandleId>) -> Self {
+        Class {
+            name,
+            superclass,
+            constructor: None,
+            prototype: Members::default(),
+            statics: Members::default(),
+            fields: HashMap::new(),
+        }
+    }
+
+    /// Add a method, getter, setter or the constructor
+    pub fn define(&mut self, name: String, kind: MethodKind, is_static: bool, method: Closure) {
+        if kind == MethodKind::Constructor {
+            self.constructor = Some(method);
+            return;
+        }
+        let members = if is_static {
+            &mut self.statics
+        } else {
+            &mut self.prototype
+        };
+        let table = match kind {
+            MethodKind::Getter => &mut members.getters,
+            MethodKind::Setter => &mut members.setters,
+            _ => &mut members.methods,
+        };
+        table.insert(name, method);
+    }
+}
+
+/// Methods, getters and setters by name
+#[derive(Debug, Clone, Default)]
+pub struct Members {
+    pub methods: HashMap<String, Closure>,
+    pub getters: HashMap<String, Closure>,
+    pub setters: HashMap<String, Closure>,
+}
+
+impl Members {
+    pub fn get(&self, kind: MethodKind, name: &str) -> Option<&Closure> {
+        match kind {
+            MethodKind::Getter => self.getters.get(name),
+            MethodKind::Setter => self.setters.get(name),
+            MethodKind::Method => self.methods.get(name),
+            MethodKind::Constructor => None,
+        }
+    }
+}
+
+/// An object created by `new`, it looks up what it doesn't hold itself in its class
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub class: HandleId,
+    pub fields: HashMap<String, Value>,
+}
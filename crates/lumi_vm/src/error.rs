@@ -1,3 +1,4 @@
+use lumi_bytecode::FunctionObj;
 use thiserror::Error;
 
 use crate::Value;
@@ -8,8 +9,26 @@ pub type VmResult<T> = Result<T, VMError>;
 pub enum VMError {
     #[error("Callee is not a function: {callee}")]
     CalleeIsNotFunction { callee: String },
-    #[error("Function not found: {name}")]
-    FunctionNotFound { name: String },
+    #[error("Cannot read property '{property}' of {target}")]
+    InvalidPropertyAccess { property: String, target: String },
+    #[error("Cannot set property '{property}' of {target}")]
+    InvalidPropertyAssignment { property: String, target: String },
+    #[error("{value} is not iterable")]
+    NotIterable { value: String },
+    #[error("Unknown method '{method}' on {target}")]
+    UnknownMethod { method: String, target: String },
+    #[error("{value} is not a class")]
+    NotAClass { value: String },
+    #[error("Function '{function}' has no parameter '{name}' left to pass by name")]
+    InvalidNamedArgument { function: String, name: String },
+    #[error("No match arm matches {value}")]
+    NoMatch { value: String },
+    #[error("Uncaught {value}")]
+    Uncaught { value: String, trace: Vec<String> },
+    #[error("Stack underflow")]
+    StackUnderflow,
+    #[error("Return outside of a function")]
+    ReturnOutsideFunction,
 }
 
 impl VMError {
@@ -19,9 +38,59 @@ impl VMError {
         }
     }
 
-    pub fn function_not_found(name: &str) -> Self {
-        VMError::FunctionNotFound {
+    pub fn invalid_property_access(property: &Value, target: &Value) -> Self {
+        VMError::InvalidPropertyAccess {
+            property: property.to_string(),
+            target: target.to_string(),
+        }
+    }
+
+    pub fn not_iterable(value: &Value) -> Self {
+        VMError::NotIterable {
+            value: value.to_string(),
+        }
+    }
+
+    pub fn uncaught(value: &Value, trace: Vec<String>) -> Self {
+        VMError::Uncaught {
+            value: value.to_string(),
+            trace,
+        }
+    }
+
+    pub fn no_match(value: &Value) -> Self {
+        VMError::NoMatch {
+            value: value.to_string(),
+        }
+    }
+
+    pub fn not_a_class(value: &Value) -> Self {
+        VMError::NotAClass {
+            value: value.to_string(),
+        }
+    }
+
+    pub fn unknown_method(method: &str, target: &Value) -> Self {
+        VMError::UnknownMethod {
+            method: method.to_string(),
+            target: target.to_string(),
+        }
+    }
+
+    pub fn invalid_named_argument(name: &str, function: &FunctionObj) -> Self {
+        VMError::InvalidNamedArgument {
+            function: function
+                .name
+                .clone()
+                .unwrap_or_else(|| "<anonymous>".to_string()),
             name: name.to_string(),
         }
     }
+
+    pub fn invalid_property_assignment(property: &Value, target: &Value) -> Self {
+        VMError::InvalidPropertyAssignment {
+            property: property.to_string(),
+            target: target.to_string(),
+        }
+    }
 }
@@ -1,6 +1,8 @@
-use lumi_bytecode::{Constant, Instruction};
+use std::collections::HashMap;
 
-use crate::Value;
+use lumi_bytecode::{Constant, Handler, Instruction};
+
+use crate::{HandleId, Value};
 
 /// Frame management for the Lumi virtual machine.
 #[derive(Debug, Clone)]
@@ -10,7 +12,31 @@ pub struct Frame {
     pub arg_count: usize,
     pub return_instructions: Vec<Instruction>,
     pub return_constants: Vec<Constant>,
+    pub return_handlers: Vec<Handler>,
+    pub function: Option<String>, // Name of the running function, for stack traces
     pub locals: Vec<Value>,
+    pub upvalues: Vec<HandleId>, // Cells captured by the running closure
+    pub cells: HashMap<usize, HandleId>, // Locals moved into cells because a closure captured them
+    pub kind: CallKind,
+}
+
+/// What a call hands back to its caller when it returns
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallKind {
+    /// The returned value
+    Function,
+    /// The returned value, a getter is read like a property
+    Getter,
+    /// The instance the constructor ran on, whatever it returned
+    Construct(Value),
+    /// Nothing, a setter runs for an assignment
+    Setter,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Frame {
@@ -21,7 +47,12 @@ impl Frame {
             arg_count: 0,
             return_instructions: Vec::new(),
             return_constants: Vec::new(),
+            return_handlers: Vec::new(),
+            function: None,
             locals: Vec::new(),
+            upvalues: Vec::new(),
+            cells: HashMap::new(),
+            kind: CallKind::Function,
         }
     }
 }
@@ -1,6 +1,9 @@
 use std::{collections::HashMap, ops::Deref};
 
-use crate::value::Value;
+use crate::{
+    class::{Class, Instance},
+    value::Value,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
@@ -39,6 +42,10 @@ impl PartialEq<usize> for HandleId {
 pub enum HeapEntry {
     Object(HashMap<String, Value>),
     Array(Vec<Value>),
+    Class(Box<Class>),
+    Instance(Instance),
+    /// A variable captured by a closure, shared with the frame that declared it
+    Cell(Value),
     Function(HandleId),
     String(String),
     Number(f64),
@@ -53,6 +60,12 @@ pub struct Heap {
     entries: Vec<HeapEntry>,
 }
 
+impl Default for Heap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Heap {
     pub fn new() -> Self {
         Heap {
@@ -62,7 +75,7 @@ impl Heap {
 
     pub fn allocate(&mut self, entry: HeapEntry) -> HandleId {
         self.entries.push(entry);
-        HandleId(self.entries.len())
+        HandleId(self.entries.len() - 1)
     }
 
     pub fn get(&self, handle: HandleId) -> Option<&HeapEntry> {
@@ -1,12 +1,16 @@
+mod class;
 mod error;
 mod frame;
 mod heap;
+mod numeric;
 mod stack;
 mod value;
 mod vm;
 
-pub use frame::Frame;
-pub use heap::Heap;
+pub use class::{Class, Instance, Members};
+pub use error::VMError;
+pub use frame::{CallKind, Frame};
+pub use heap::{HandleId, Heap, HeapEntry};
 pub use stack::Stack;
-pub use value::Value;
+pub use value::{Closure, Value, Variant};
 pub use vm::Vm;
@@ -0,0 +1,107 @@
+use std::cmp::Ordering;
+
+use num_bigint::BigInt;
+
+use crate::value::Value;
+
+/// Arithmetic operations shared by all number kinds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumericOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+}
+
+/// Two numeric operands converted to the wider of their kinds: integer < big integer < float
+enum NumericPair {
+    Integer(i64, i64),
+    BigInt(BigInt, BigInt),
+    Float(f64, f64),
+}
+
+impl NumericPair {
+    fn new(a: &Value, b: &Value) -> Option<Self> {
+        match (a, b) {
+            (Value::Integer(a), Value::Integer(b)) => Some(NumericPair::Integer(*a, *b)),
+            (Value::Integer(_) | Value::BigInt(_), Value::Integer(_) | Value::BigInt(_)) => {
+                Some(NumericPair::BigInt(a.as_big_int()?, b.as_big_int()?))
+            }
+            _ => Some(NumericPair::Float(a.as_number()?, b.as_number()?)),
+        }
+    }
+}
+
+/// Apply `op` to two numbers, `None` when either operand is not a number.
+///
+/// Integer results that overflow 64 bits fall back to floats, division of
+/// integers always yields a float and big integers divide with truncation.
+/// Exponents that are negative or too large for an exact power yield floats.
+pub fn apply(op: NumericOp, a: &Value, b: &Value) -> Option<Value> {
+    let value = match NumericPair::new(a, b)? {
+        NumericPair::Integer(a, b) => {
+            let result = match op {
+                NumericOp::Add => a.checked_add(b),
+                NumericOp::Sub => a.checked_sub(b),
+                NumericOp::Mul => a.checked_mul(b),
+                NumericOp::Div => None,
+                NumericOp::Mod => a.checked_rem(b),
+                // Negative exponents produce fractions
+                NumericOp::Pow => u32::try_from(b).ok().and_then(|b| a.checked_pow(b)),
+            };
+            match result {
+                Some(n) => Value::Integer(n),
+                None => float_op(op, a as f64, b as f64),
+            }
+        }
+        NumericPair::BigInt(a, b) => match op {
+            NumericOp::Add => Value::BigInt(a + b),
+            NumericOp::Sub => Value::BigInt(a - b),
+            NumericOp::Mul => Value::BigInt(a * b),
+            NumericOp::Div | NumericOp::Mod if b == BigInt::ZERO => Value::Number(f64::NAN),
+            NumericOp::Div => Value::BigInt(a / b),
+            NumericOp::Mod => Value::BigInt(a % b),
+            NumericOp::Pow => match u32::try_from(&b) {
+                Ok(exponent) => Value::BigInt(a.pow(exponent)),
+                Err(_) => float_op(
+                    op,
+                    Value::BigInt(a).as_number()?,
+                    Value::BigInt(b).as_number()?,
+                ),
+            },
+        },
+        NumericPair::Float(a, b) => float_op(op, a, b),
+    };
+
+    Some(value)
+}
+
+fn float_op(op: NumericOp, a: f64, b: f64) -> Value {
+    Value::Number(match op {
+        NumericOp::Add => a + b,
+        NumericOp::Sub => a - b,
+        NumericOp::Mul => a * b,
+        NumericOp::Div => a / b,
+        NumericOp::Mod => a % b,
+        NumericOp::Pow => a.powf(b),
+    })
+}
+
+/// Order two numbers, `None` when either is not a number or a float is NaN
+pub fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match NumericPair::new(a, b)? {
+        NumericPair::Integer(a, b) => Some(a.cmp(&b)),
+        NumericPair::BigInt(a, b) => Some(a.cmp(&b)),
+        NumericPair::Float(a, b) => a.partial_cmp(&b),
+    }
+}
+
+/// Equality that compares numbers by value across kinds, so `1 == 1.0`
+pub fn equals(a: &Value, b: &Value) -> bool {
+    match NumericPair::new(a, b) {
+        Some(_) => compare(a, b) == Some(Ordering::Equal),
+        None => a == b,
+    }
+}
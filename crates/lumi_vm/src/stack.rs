@@ -1,6 +1,9 @@
 use lumi_bytecode::Constant;
 
-use crate::{frame::Frame, value::Value};
+use crate::{
+    frame::Frame,
+    value::{Closure, Value},
+};
 
 /// Stack is a structure that holds the values and frames for the virtual machine.
 #[derive(Debug, Clone)]
@@ -9,6 +12,12 @@ pub struct Stack {
     pub frames: Vec<Frame>,
 }
 
+impl Default for Stack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Stack {
     pub fn new() -> Self {
         Stack {
@@ -39,10 +48,12 @@ impl Stack {
 
     pub fn convert_constant_to_value(constant: Constant) -> Value {
         match constant {
+            Constant::Integer(n) => Value::Integer(n),
             Constant::Number(n) => Value::Number(n),
+            Constant::BigInt(n) => Value::BigInt(n),
             Constant::String(s) => Value::String(s),
             Constant::Boolean(b) => Value::Boolean(b),
-            Constant::Function(f) => Value::Function(f),
+            Constant::Function(f) => Value::Function(Closure::new(f)),
             Constant::Null => Value::Null,
             Constant::Undefined => Value::Undefined,
         }
@@ -1,30 +1,86 @@
+use std::{fmt, rc::Rc};
+
 use lumi_bytecode::FunctionObj;
+use num_bigint::BigInt;
 
 use crate::heap::HandleId;
 
 /// Represents a value in the Lumi VM.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
+    Integer(i64),
     Number(f64),
+    BigInt(BigInt),
     String(String),
     Boolean(bool),
     Object(HandleId),
     Array(HandleId),
-    Function(FunctionObj),
+    Function(Closure),
+    Class(HandleId),
+    Variant(Rc<Variant>),
     Null,
     Undefined,
 }
 
+/// A function value together with the variables it captured from enclosing functions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Closure {
+    pub function: Rc<FunctionObj>,
+    pub upvalues: Vec<HandleId>, // Heap cells shared with the frames that declared the variables
+}
+
+impl Closure {
+    pub fn new(function: FunctionObj) -> Self {
+        Closure {
+            function: Rc::new(function),
+            upvalues: Vec::new(),
+        }
+    }
+}
+
+/// A value of an enum variant, tagged with the enum and variant it belongs to. The
+/// payload can't change, so values with equal payloads are equal
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub enum_name: String,
+    pub name: String,
+    pub fields: Vec<(String, Value)>,
+}
+
+impl Variant {
+    /// The payload field `name`
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+}
+
 impl Value {
     pub fn is_number(&self) -> bool {
-        matches!(self, Value::Number(_))
+        matches!(
+            self,
+            Value::Integer(_) | Value::Number(_) | Value::BigInt(_)
+        )
     }
 
+    /// The value as a float, for any kind of number
     pub fn as_number(&self) -> Option<f64> {
-        if let Value::Number(n) = self {
-            Some(*n)
-        } else {
-            None
+        match self {
+            Value::Integer(n) => Some(*n as f64),
+            Value::Number(n) => Some(*n),
+            Value::BigInt(n) => n.to_string().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The value as a big integer, for integer kinds
+    pub fn as_big_int(&self) -> Option<BigInt> {
+        match self {
+            Value::Integer(n) => Some(BigInt::from(*n)),
+            Value::BigInt(n) => Some(n.clone()),
+            _ => None,
         }
     }
 
@@ -36,17 +92,35 @@ impl Value {
         }
     }
 
-    pub fn to_string(&self) -> String {
+    // TODO: add more methods for other types as needed
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Value::Number(n) => n.to_string(),
-            Value::String(s) => s.clone(),
-            Value::Boolean(b) => b.to_string(),
-            Value::Object(_) => "[object]".to_string(),
-            Value::Array(_) => "[array]".to_string(),
-            Value::Function(_) => "[function]".to_string(),
-            Value::Null => "null".to_string(),
-            Value::Undefined => "undefined".to_string(),
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::BigInt(n) => write!(f, "{}", n),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::Object(_) => write!(f, "[object]"),
+            Value::Array(_) => write!(f, "[array]"),
+            Value::Function(_) => write!(f, "[function]"),
+            Value::Class(_) => write!(f, "[class]"),
+            Value::Variant(variant) => {
+                write!(f, "{}.{}", variant.enum_name, variant.name)?;
+                if !variant.fields.is_empty() {
+                    let fields: Vec<String> = variant
+                        .fields
+                        .iter()
+                        .map(|(_, value)| value.to_string())
+                        .collect();
+                    write!(f, "({})", fields.join(", "))?;
+                }
+                Ok(())
+            }
+            Value::Null => write!(f, "null"),
+            Value::Undefined => write!(f, "undefined"),
         }
     }
-    // TODO: add more methods for other types as needed
 }
@@ -1,37 +1,56 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{collections::HashMap, rc::Rc};
 
-use lumi_bytecode::{Bytecode, Constant, Env, Instruction};
+use lumi_ast::Span;
+use lumi_bytecode::{
+    Bytecode, Capture, Constant, DebugInfo, FunctionObj, Handler, Instruction, MethodKind,
+};
 
 use crate::{
+    class::{Class, Instance},
     error::{VMError, VmResult},
-    frame::Frame,
+    frame::{CallKind, Frame},
+    heap::{HandleId, Heap, HeapEntry},
+    numeric::{self, NumericOp},
     stack::Stack,
-    value::Value,
+    value::{Closure, Value, Variant},
 };
 
 /// The virtual machine (VM) for the Lumi engine.
 pub struct Vm {
     // TODO: add later
     // pub registers: Registers
-    // pub heap: Heap
-    pub env: Rc<RefCell<Env>>, // TODO: add instruction when entering a new scope and leaving a scope.
+    pub heap: Heap, // Objects, arrays and captured variables, referenced by handle
     pub stack: Stack,
     pub globals: Vec<Value>,
     pub locals: Vec<Value>,
+    pub cells: HashMap<usize, HandleId>, // Locals of the main program captured by closures
     instructions: Vec<Instruction>,
     constants: Vec<Constant>,
+    handlers: Vec<Handler>,
+    debug_info: DebugInfo,     // Source lines for stack traces
+    thrown_trace: Vec<String>, // Stack trace of the value last thrown, for `Rethrow`
     ip: usize,
 }
 
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Vm {
     pub fn new() -> Self {
         Vm {
-            env: Rc::new(RefCell::new(Env::new(None))),
+            heap: Heap::new(),
             stack: Stack::new(),
             globals: vec![Value::Undefined; 256],
             locals: vec![Value::Undefined; 16],
+            cells: HashMap::new(),
             instructions: Vec::new(),
             constants: Vec::new(),
+            handlers: Vec::new(),
+            debug_info: DebugInfo::default(),
+            thrown_trace: Vec::new(),
             ip: 0,
         }
     }
@@ -40,237 +59,961 @@ impl Vm {
         let start_ip = self.instructions.len();
         self.instructions = bytecode.instructions;
         self.constants = bytecode.constants;
+        self.handlers = bytecode.handlers;
+        self.debug_info = bytecode.debug_info;
         self.ip = start_ip;
 
         while self.ip < self.instructions.len() {
-            match &self.instructions[self.ip] {
-                Instruction::PushConst(idx) => {
-                    let constant = self
-                        .constants
-                        .get(*idx)
-                        .cloned()
-                        .unwrap_or(Constant::Undefined);
-
-                    match constant {
-                        Constant::Function(ref f) => {
-                            self.env.borrow_mut().add_function(f.clone());
-                        }
-                        _ => self.stack.push(Stack::convert_constant_to_value(constant)),
-                    }
-                    self.ip += 1;
+            if let Err(error) = self.step() {
+                if matches!(error, VMError::Uncaught { .. }) {
+                    return Err(error);
                 }
-                Instruction::Add => {
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-
-                    match (a.clone(), b.clone()) {
-                        (Value::Number(a), Value::Number(b)) => {
-                            self.stack.push(Value::Number(a + b));
-                        }
-                        _ => {
-                            let a_str = a.to_string();
-                            let b_str = b.to_string();
-                            self.stack.push(Value::String(format!("{a_str}{b_str}")));
-                        }
-                    }
-                    self.ip += 1;
+                // Faults of the VM are thrown as error values, a `catch` can handle them
+                let value = self.error_value(&error);
+                self.thrown_trace = self.stack_trace();
+                if !self.unwind(value) {
+                    return Err(error);
                 }
-                Instruction::Sub => {
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Number(a - b));
-                    } else {
-                        self.stack.push(Value::Number(f64::NAN));
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the instruction at `ip`
+    fn step(&mut self) -> VmResult<()> {
+        match &self.instructions[self.ip] {
+            Instruction::PushConst(idx) => {
+                let constant = self
+                    .constants
+                    .get(*idx)
+                    .cloned()
+                    .unwrap_or(Constant::Undefined);
+
+                self.stack.push(Stack::convert_constant_to_value(constant));
+                self.ip += 1;
+            }
+            Instruction::Add => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+
+                match numeric::apply(NumericOp::Add, &a, &b) {
+                    Some(result) => self.stack.push(result),
+                    None => {
+                        let a_str = a.to_string();
+                        let b_str = b.to_string();
+                        self.stack.push(Value::String(format!("{a_str}{b_str}")));
                     }
-                    self.ip += 1;
                 }
-                Instruction::Mul => {
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Number(a * b));
-                    } else {
-                        self.stack.push(Value::Number(f64::NAN));
+                self.ip += 1;
+            }
+            Instruction::Sub
+            | Instruction::Mul
+            | Instruction::Div
+            | Instruction::Mod
+            | Instruction::Pow => {
+                let op = match &self.instructions[self.ip] {
+                    Instruction::Sub => NumericOp::Sub,
+                    Instruction::Mul => NumericOp::Mul,
+                    Instruction::Div => NumericOp::Div,
+                    Instruction::Mod => NumericOp::Mod,
+                    _ => NumericOp::Pow,
+                };
+                let b = self.pop()?;
+                let a = self.pop()?;
+                let result = numeric::apply(op, &a, &b).unwrap_or(Value::Number(f64::NAN));
+                self.stack.push(result);
+                self.ip += 1;
+            }
+            Instruction::Neg => {
+                let value = self.pop()?;
+                // Multiplying keeps the sign of zero, `-0.0` stays distinct from `0.0`
+                let result = numeric::apply(NumericOp::Mul, &value, &Value::Integer(-1))
+                    .unwrap_or(Value::Number(f64::NAN));
+                self.stack.push(result);
+                self.ip += 1;
+            }
+            Instruction::Inc | Instruction::Dec => {
+                let op = match &self.instructions[self.ip] {
+                    Instruction::Inc => NumericOp::Add,
+                    _ => NumericOp::Sub,
+                };
+                let value = self.pop()?;
+                let result = numeric::apply(op, &value, &Value::Integer(1))
+                    .unwrap_or(Value::Number(f64::NAN));
+                self.stack.push(result);
+                self.ip += 1;
+            }
+            Instruction::Eq => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.stack.push(Value::Boolean(numeric::equals(&a, &b)));
+                self.ip += 1;
+            }
+            Instruction::Neq => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.stack.push(Value::Boolean(!numeric::equals(&a, &b)));
+                self.ip += 1;
+            }
+            Instruction::Lt | Instruction::Gt | Instruction::Leq | Instruction::Geq => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                // Non-numbers and NaN compare false
+                let result = numeric::compare(&a, &b).is_some_and(|ordering| {
+                    match &self.instructions[self.ip] {
+                        Instruction::Lt => ordering.is_lt(),
+                        Instruction::Gt => ordering.is_gt(),
+                        Instruction::Leq => ordering.is_le(),
+                        _ => ordering.is_ge(),
                     }
-                    self.ip += 1;
+                });
+                self.stack.push(Value::Boolean(result));
+                self.ip += 1;
+            }
+            Instruction::JumpIfTrue(target) => {
+                let target = *target;
+                let cond = self.pop()?;
+                if cond.as_bool().unwrap_or(false) {
+                    self.ip = target;
+                    return Ok(());
                 }
-                Instruction::Div => {
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Number(a / b));
-                    } else {
-                        self.stack.push(Value::Number(f64::NAN));
-                    }
-                    self.ip += 1;
+                self.ip += 1;
+            }
+            Instruction::JumpIfFalse(target) => {
+                let target = *target;
+                let cond = self.pop()?;
+                if !cond.as_bool().unwrap_or(false) {
+                    self.ip = target;
+                    return Ok(());
                 }
-                Instruction::Eq => {
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    self.stack.push(Value::Boolean(a == b));
-                    self.ip += 1;
+                self.ip += 1;
+            }
+            Instruction::Jump(target) => {
+                self.ip = *target;
+                return Ok(());
+            }
+            Instruction::JumpTable(min, targets, default) => {
+                // Popped off the field, the table stays borrowed from the instructions
+                let value = self.stack.pop().ok_or(VMError::StackUnderflow)?;
+                let offset = match value {
+                    Value::Integer(n) => n.checked_sub(*min),
+                    Value::Number(n) if n.fract() == 0.0 => (n as i64).checked_sub(*min),
+                    _ => None,
+                };
+                self.ip = offset
+                    .and_then(|offset| usize::try_from(offset).ok())
+                    .and_then(|offset| targets.get(offset).copied())
+                    .unwrap_or(*default);
+                return Ok(());
+            }
+            Instruction::JumpIfNullish(target) => {
+                if matches!(self.stack.peek(), Some(Value::Null | Value::Undefined)) {
+                    self.stack.pop();
+                    self.stack.push(Value::Undefined);
+                    self.ip = *target;
+                    return Ok(());
                 }
-                Instruction::Neq => {
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    self.stack.push(Value::Boolean(a != b));
-                    self.ip += 1;
+                self.ip += 1;
+            }
+            Instruction::Pop => {
+                // Values below the running frame belong to its caller
+                let floor = self.stack.frames.last().map_or(0, |f| f.base_pointer);
+                if self.stack.values.len() > floor {
+                    self.stack.pop();
                 }
-                Instruction::Lt => {
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Boolean(a < b));
-                    } else {
-                        self.stack.push(Value::Boolean(false));
+                self.ip += 1;
+            }
+            Instruction::Dup => {
+                let top = self.stack.peek().cloned().unwrap_or(Value::Undefined);
+                self.stack.push(top);
+                self.ip += 1;
+            }
+            Instruction::Dup2 => {
+                let len = self.stack.values.len();
+                let top_two = self.stack.values[len.saturating_sub(2)..].to_vec();
+                self.stack.values.extend(top_two);
+                self.ip += 1;
+            }
+            Instruction::NewObject => {
+                let handle = self.heap.allocate(HeapEntry::Object(HashMap::new()));
+                self.stack.push(Value::Object(handle));
+                self.ip += 1;
+            }
+            Instruction::NewArray(count) => {
+                let start = self.stack.values.len().saturating_sub(*count);
+                let elements = self.stack.values.split_off(start);
+                let handle = self.heap.allocate(HeapEntry::Array(elements));
+                self.stack.push(Value::Array(handle));
+                self.ip += 1;
+            }
+            Instruction::ArrayExtend => {
+                let source = self.pop()?;
+                let elements = self.iterate(&source)?;
+                if let Some(Value::Array(handle)) = self.stack.peek() {
+                    if let Some(HeapEntry::Array(target)) = self.heap.get_mut(*handle) {
+                        target.extend(elements);
                     }
-                    self.ip += 1;
                 }
-                Instruction::Gt => {
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Boolean(a > b));
-                    } else {
-                        self.stack.push(Value::Boolean(false));
+                self.ip += 1;
+            }
+            Instruction::CallMethod(name, argc) => {
+                let name = name.clone();
+                let start = self.stack.values.len().saturating_sub(*argc);
+                let args = self.stack.values.split_off(start);
+                let receiver = self.pop()?;
+                match self.find_method(&receiver, &name) {
+                    Some(method) => {
+                        self.call_closure(method, args, &[], receiver, CallKind::Function)?;
                     }
-                    self.ip += 1;
-                }
-                Instruction::Leq => {
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Boolean(a <= b));
-                    } else {
-                        self.stack.push(Value::Boolean(false));
+                    None => {
+                        let result = self.call_method(&receiver, &name, args)?;
+                        self.stack.push(result);
+                        self.ip += 1;
                     }
-                    self.ip += 1;
                 }
-                Instruction::Geq => {
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Boolean(a >= b));
-                    } else {
-                        self.stack.push(Value::Boolean(false));
+            }
+            Instruction::GetProp(name) => {
+                let key = Value::String(name.clone());
+                let object = self.pop()?;
+                self.read_member(object, key)?;
+            }
+            Instruction::GetIndex => {
+                let key = self.pop()?;
+                let object = self.pop()?;
+                self.read_member(object, key)?;
+            }
+            Instruction::SetProp(name) => {
+                let key = Value::String(name.clone());
+                let value = self.pop()?;
+                let object = self.pop()?;
+                self.write_member(object, key, value)?;
+            }
+            Instruction::SetIndex => {
+                let value = self.pop()?;
+                let key = self.pop()?;
+                let object = self.pop()?;
+                self.write_member(object, key, value)?;
+            }
+            Instruction::NewClass(name) => {
+                let name = name.clone();
+                let superclass = match self.stack.pop().unwrap_or(Value::Undefined) {
+                    Value::Class(handle) => Some(handle),
+                    Value::Undefined => None,
+                    value => return Err(VMError::not_a_class(&value)),
+                };
+                let handle = self
+                    .heap
+                    .allocate(HeapEntry::Class(Box::new(Class::new(name, superclass))));
+                self.stack.push(Value::Class(handle));
+                self.ip += 1;
+            }
+            Instruction::DefineMethod(name, kind, is_static) => {
+                let (name, kind, is_static) = (name.clone(), *kind, *is_static);
+                let method = self.pop()?;
+                if let (Value::Function(method), Some(Value::Class(handle))) =
+                    (method, self.stack.peek())
+                {
+                    if let Some(HeapEntry::Class(class)) = self.heap.get_mut(*handle) {
+                        class.define(name, kind, is_static, method);
                     }
-                    self.ip += 1;
                 }
-                Instruction::JumpIfTrue(target) => {
-                    let cond = self.stack.pop().unwrap();
-                    if cond.as_bool().unwrap_or(false) {
-                        self.ip = *target;
-                        continue;
+                self.ip += 1;
+            }
+            Instruction::New(argc) => {
+                let start = self.stack.values.len().saturating_sub(*argc);
+                let args = self.stack.values.split_off(start);
+                let class = match self.stack.pop().unwrap_or(Value::Undefined) {
+                    Value::Class(handle) => handle,
+                    value => return Err(VMError::not_a_class(&value)),
+                };
+                let instance = self.heap.allocate(HeapEntry::Instance(Instance {
+                    class,
+                    fields: HashMap::new(),
+                }));
+                self.construct(class, Value::Object(instance), args)?;
+            }
+            Instruction::CallSuperConstructor => {
+                let args = self.pop()?;
+                let args = self.iterate(&args)?;
+                let this = self.pop()?;
+                let class = match self.stack.pop().unwrap_or(Value::Undefined) {
+                    Value::Class(handle) => handle,
+                    value => return Err(VMError::not_a_class(&value)),
+                };
+                self.construct(class, this, args)?;
+            }
+            Instruction::CallSuperMethod(name, argc) => {
+                let name = name.clone();
+                let start = self.stack.values.len().saturating_sub(*argc);
+                let args = self.stack.values.split_off(start);
+                let this = self.pop()?;
+                let class = self.stack.pop().unwrap_or(Value::Undefined);
+                // A static method reaches the static members of the parent
+                let is_static = matches!(this, Value::Class(_));
+                let method = match class {
+                    Value::Class(handle) => {
+                        self.lookup(handle, is_static, MethodKind::Method, &name)
                     }
-                    self.ip += 1;
+                    _ => None,
+                };
+                match method {
+                    Some(method) => {
+                        self.call_closure(method, args, &[], this, CallKind::Function)?;
+                    }
+                    None => return Err(VMError::unknown_method(&name, &class)),
                 }
-                Instruction::JumpIfFalse(target) => {
-                    let cond = self.stack.pop().unwrap();
-                    if !cond.as_bool().unwrap_or(false) {
-                        self.ip = *target;
-                        continue;
+            }
+            Instruction::StoreVar(index) => {
+                let idx = *index;
+                let value = self.pop()?;
+                if let Some(cell) = self.captured_cell(idx) {
+                    self.write_cell(cell, value);
+                } else {
+                    // Temporaries of a match can take more slots than were reserved
+                    let locals = match self.stack.frames.last_mut() {
+                        Some(frame) => &mut frame.locals,
+                        None => &mut self.locals,
+                    };
+                    if idx >= locals.len() {
+                        locals.resize(idx + 1, Value::Undefined);
                     }
-                    self.ip += 1;
+                    locals[idx] = value;
                 }
-                Instruction::Jump(target) => {
-                    self.ip = *target;
-                    continue;
+                self.ip += 1;
+            }
+            Instruction::LoadVar(index) => {
+                let idx = *index;
+                if let Some(cell) = self.captured_cell(idx) {
+                    self.stack.push(self.read_cell(cell));
+                } else if let Some(frame) = self.stack.frames.last() {
+                    let val = frame.locals.get(idx).cloned().unwrap_or(Value::Undefined);
+                    self.stack.push(val);
+                } else {
+                    self.locals
+                        .get(idx)
+                        .cloned()
+                        .map(|v| self.stack.push(v))
+                        .unwrap_or_else(|| self.stack.push(Value::Undefined));
                 }
-                Instruction::Pop => {
-                    self.stack.pop();
-                    self.ip += 1;
+                self.ip += 1;
+            }
+            Instruction::LoadUpvalue(index) => {
+                let value = self
+                    .upvalue_cell(*index)
+                    .map(|cell| self.read_cell(cell))
+                    .unwrap_or(Value::Undefined);
+                self.stack.push(value);
+                self.ip += 1;
+            }
+            Instruction::StoreUpvalue(index) => {
+                let index = *index;
+                let value = self.pop()?;
+                if let Some(cell) = self.upvalue_cell(index) {
+                    self.write_cell(cell, value);
                 }
-                Instruction::StoreVar(index) => {
-                    let idx = *index;
-                    if let Some(frame) = self.stack.frames.last_mut() {
-                        if idx < frame.locals.len() {
-                            frame.locals[idx] = self.stack.values.pop().unwrap();
-                        }
-                    } else {
-                        self.locals[idx] = self.stack.values.pop().unwrap();
+                self.ip += 1;
+            }
+            Instruction::CloseVar(index) => {
+                let idx = *index;
+                if let Some(cell) = self.captured_cell(idx) {
+                    let value = self.read_cell(cell);
+                    let (locals, cells) = match self.stack.frames.last_mut() {
+                        Some(frame) => (&mut frame.locals, &mut frame.cells),
+                        None => (&mut self.locals, &mut self.cells),
+                    };
+                    cells.remove(&idx);
+                    if idx >= locals.len() {
+                        locals.resize(idx + 1, Value::Undefined);
                     }
-                    self.ip += 1;
+                    locals[idx] = value;
                 }
-                Instruction::LoadVar(index) => {
-                    let idx = *index;
-                    if let Some(frame) = self.stack.frames.last() {
-                        let val = frame.locals.get(idx).cloned().unwrap_or(Value::Undefined);
-                        self.stack.push(val);
-                    } else {
-                        self.locals
-                            .get(idx)
-                            .cloned()
-                            .map(|v| self.stack.push(v))
-                            .unwrap_or_else(|| self.stack.push(Value::Undefined));
-                    }
-                    self.ip += 1;
+                self.ip += 1;
+            }
+            Instruction::MakeClosure(idx) => {
+                if let Some(Constant::Function(function)) = self.constants.get(*idx).cloned() {
+                    let closure = self.make_closure(function);
+                    self.stack.push(Value::Function(closure));
                 }
-                Instruction::Print => {
-                    let value = self.stack.pop().unwrap();
-                    println!("{:?}", value.to_string());
-                    self.ip += 1;
+                self.ip += 1;
+            }
+            Instruction::Print => {
+                let value = self.pop()?;
+                println!("{:?}", value.to_string());
+                self.ip += 1;
+            }
+            Instruction::CallValue(argc) => {
+                let argc = *argc;
+                self.call_value(argc, &[])?;
+            }
+            Instruction::CallNamed(argc, names) => {
+                let argc = *argc;
+                let names = names.clone();
+                self.call_value(argc, &names)?;
+            }
+            Instruction::Return => {
+                let frame = self
+                    .stack
+                    .frames
+                    .pop()
+                    .ok_or(VMError::ReturnOutsideFunction)?;
+
+                let ret = self.pop()?;
+
+                // Drop whatever an early return left behind, the caller's values stay.
+                self.stack.values.truncate(frame.base_pointer);
+
+                // Push the return value
+                match frame.kind {
+                    CallKind::Function | CallKind::Getter => self.stack.values.push(ret),
+                    CallKind::Construct(instance) => self.stack.values.push(instance),
+                    CallKind::Setter => {}
                 }
-                Instruction::CallFn(fn_name) => {
-                    let function = self.env.borrow_mut().get_function(fn_name, false);
-                    if function.is_none() {
-                        return Err(VMError::function_not_found(fn_name));
-                    }
-                    let function = function.unwrap();
-                    let argc = function.arity;
 
-                    // Extract arguments from stack (in order)
-                    let mut args = Vec::with_capacity(argc);
-                    for _ in 0..argc {
-                        args.push(self.stack.pop().unwrap());
+                self.instructions = frame.return_instructions;
+                self.constants = frame.return_constants;
+                self.handlers = frame.return_handlers;
+                self.ip = frame.return_ip;
+            }
+            Instruction::Throw => {
+                let value = self.pop()?;
+                self.thrown_trace = self.stack_trace();
+                if !self.unwind(value.clone()) {
+                    return Err(VMError::uncaught(&value, self.thrown_trace.clone()));
+                }
+            }
+            Instruction::Rethrow => {
+                let value = self.pop()?;
+                if !self.unwind(value.clone()) {
+                    return Err(VMError::uncaught(&value, self.thrown_trace.clone()));
+                }
+            }
+            Instruction::MatchArray(len, at_least) => {
+                let (len, at_least) = (*len, *at_least);
+                let value = self.pop()?;
+                let matches = match self.heap_entry(&value) {
+                    Some(HeapEntry::Array(elements)) if at_least => elements.len() >= len,
+                    Some(HeapEntry::Array(elements)) => elements.len() == len,
+                    _ => false,
+                };
+                self.stack.push(Value::Boolean(matches));
+                self.ip += 1;
+            }
+            Instruction::MatchObject(keys) => {
+                let keys = keys.clone();
+                let value = self.pop()?;
+                let matches = matches!(value, Value::Object(_))
+                    && keys.iter().all(|key| self.has_property(&value, key));
+                self.stack.push(Value::Boolean(matches));
+                self.ip += 1;
+            }
+            Instruction::ArraySlice(start) => {
+                let start = *start;
+                let value = self.pop()?;
+                let elements = match self.heap_entry(&value) {
+                    Some(HeapEntry::Array(elements)) => {
+                        elements.iter().skip(start).cloned().collect()
                     }
-                    args.reverse(); // ensure original order
+                    _ => Vec::new(),
+                };
+                let handle = self.heap.allocate(HeapEntry::Array(elements));
+                self.stack.push(Value::Array(handle));
+                self.ip += 1;
+            }
+            Instruction::MakeVariant(enum_name, name, fields) => {
+                let start = self.stack.values.len().saturating_sub(fields.len());
+                let values = self.stack.values.split_off(start);
+                let variant = Variant {
+                    enum_name: enum_name.clone(),
+                    name: name.clone(),
+                    fields: fields.iter().cloned().zip(values).collect(),
+                };
+                self.stack.push(Value::Variant(Rc::new(variant)));
+                self.ip += 1;
+            }
+            Instruction::MatchVariant(enum_name, name) => {
+                let value = self.stack.pop().ok_or(VMError::StackUnderflow)?;
+                let matches = matches!(&value, Value::Variant(variant)
+                    if variant.enum_name == *enum_name && variant.name == *name);
+                self.stack.push(Value::Boolean(matches));
+                self.ip += 1;
+            }
+            Instruction::NoMatch => {
+                let value = self.pop()?;
+                return Err(VMError::no_match(&value));
+            }
+            _ => self.ip += 1,
+        }
+        Ok(())
+    }
 
-                    // NOTE: not sure if we need locals here?
-                    let mut locals = vec![Value::Undefined; 16];
-                    for (i, arg) in args.into_iter().enumerate() {
-                        locals[i] = arg;
-                    }
+    /// Call the function below `argc` arguments, the last of them passed by `names`
+    fn call_value(&mut self, argc: usize, names: &[String]) -> VmResult<()> {
+        let start = self.stack.values.len().saturating_sub(argc);
+        let args = self.stack.values.split_off(start);
+        let closure = match self.stack.pop().unwrap_or(Value::Undefined) {
+            Value::Function(closure) => closure,
+            callee => return Err(VMError::callee_is_not_a_function(callee)),
+        };
+        self.call_closure(closure, args, names, Value::Undefined, CallKind::Function)
+    }
+
+    /// Run `closure` in a new frame, a method finds `this` in its first local
+    fn call_closure(
+        &mut self,
+        closure: Closure,
+        args: Vec<Value>,
+        names: &[String],
+        this: Value,
+        kind: CallKind,
+    ) -> VmResult<()> {
+        let argc = args.len();
+        let locals = self.bind_arguments(&closure.function, args, names, this)?;
+
+        // TODO: optimize by not cloning instructions/constants and instead just keep a reference to the function's bytecode.
+        let return_ip = self.ip + 1;
+        let base_pointer = self.stack.values.len();
+        self.stack.push_frame(Frame {
+            return_ip,
+            arg_count: argc,
+            base_pointer,
+            return_instructions: self.instructions.clone(),
+            return_constants: self.constants.clone(),
+            return_handlers: std::mem::take(&mut self.handlers),
+            function: closure.function.name.clone(),
+            locals,
+            upvalues: closure.upvalues.clone(),
+            cells: HashMap::new(),
+            kind,
+        });
+
+        self.instructions = closure.function.instructions.clone();
+        self.constants = closure.function.constants.clone();
+        self.handlers = closure.function.handlers.clone();
+        self.ip = 0;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> VmResult<Value> {
+        self.stack.pop().ok_or(VMError::StackUnderflow)
+    }
+
+    /// Continue at the innermost handler covering the running instruction, returning
+    /// from functions until one is found. False when nothing catches `value`
+    fn unwind(&mut self, value: Value) -> bool {
+        loop {
+            if let Some(handler) = self.handlers.iter().find(|handler| handler.covers(self.ip)) {
+                self.ip = handler.target;
+                // Drop the operands of the expression that threw
+                let base = self
+                    .stack
+                    .frames
+                    .last()
+                    .map_or(0, |frame| frame.base_pointer);
+                self.stack.values.truncate(base + handler.depth);
+                self.stack.push(value);
+                return true;
+            }
+            let Some(frame) = self.stack.frames.pop() else {
+                return false;
+            };
+            self.stack.values.truncate(frame.base_pointer);
+            self.instructions = frame.return_instructions;
+            self.constants = frame.return_constants;
+            self.handlers = frame.return_handlers;
+            // The call instruction is the one that threw in the caller
+            self.ip = frame.return_ip.saturating_sub(1);
+        }
+    }
+
+    /// The running functions, innermost first, with the line each one is at
+    fn stack_trace(&self) -> Vec<String> {
+        let mut trace = Vec::with_capacity(self.stack.frames.len() + 1);
+        let mut ip = self.ip;
+        for frame in self.stack.frames.iter().rev() {
+            let name = frame.function.as_deref();
+            let span = name.and_then(|name| self.debug_info.span_at(Some(name), ip));
+            trace.push(trace_entry(name.unwrap_or("<anonymous>"), span));
+            ip = frame.return_ip.saturating_sub(1);
+        }
+        trace.push(trace_entry("<main>", self.debug_info.span_at(None, ip)));
+        trace
+    }
+
+    /// The value a `catch` receives for a fault of the VM, an object holding the message
+    /// and the stack trace
+    fn error_value(&mut self, error: &VMError) -> Value {
+        let trace = self.stack_trace().into_iter().map(Value::String).collect();
+        let stack = self.heap.allocate(HeapEntry::Array(trace));
+        let properties = HashMap::from([
+            ("message".to_string(), Value::String(error.to_string())),
+            ("stack".to_string(), Value::Array(stack)),
+        ]);
+        Value::Object(self.heap.allocate(HeapEntry::Object(properties)))
+    }
 
-                    // TODO: optimize by not cloning instructions/constants and instead just keep a reference to the function's bytecode.
-                    let return_ip = self.ip + 1;
-                    self.stack.push_frame(Frame {
-                        return_ip,
-                        arg_count: argc,
-                        base_pointer: 0,
-                        return_instructions: self.instructions.clone(),
-                        return_constants: self.constants.clone(),
-                        locals: locals,
-                    });
-
-                    self.instructions = function.instructions.clone();
-                    self.constants = function.constants.clone();
-                    self.ip = 0;
+    /// Lay the arguments of a call out in the parameter slots of `function`, missing ones
+    /// are undefined and extra ones go to the rest parameter or are dropped
+    fn bind_arguments(
+        &mut self,
+        function: &FunctionObj,
+        mut args: Vec<Value>,
+        names: &[String],
+        this: Value,
+    ) -> VmResult<Vec<Value>> {
+        let named = args.split_off(args.len().saturating_sub(names.len()));
+        let extra = args.split_off(args.len().min(function.arity));
+        let positional = args.len();
+
+        // The parameters of a method come after `this`
+        let offset = usize::from(function.is_method);
+        let slots = offset + function.arity + usize::from(function.has_rest);
+        let mut locals = vec![Value::Undefined; slots.max(16)];
+        if function.is_method {
+            locals[0] = this;
+        }
+        for (i, arg) in args.into_iter().enumerate() {
+            locals[offset + i] = arg;
+        }
+        if function.has_rest {
+            let rest = self.heap.allocate(HeapEntry::Array(extra));
+            locals[offset + function.arity] = Value::Array(rest);
+        }
+
+        for (name, value) in names.iter().zip(named) {
+            match function.param_names.iter().position(|param| param == name) {
+                Some(idx) if idx >= positional && idx < function.arity => {
+                    locals[offset + idx] = value
                 }
-                Instruction::Return => {
-                    let frame = self.stack.frames.pop().unwrap();
+                _ => return Err(VMError::invalid_named_argument(name, function)),
+            }
+        }
+        Ok(locals)
+    }
 
-                    let ret = if self.stack.values.len() > 0 {
-                        self.stack.pop().unwrap()
-                    } else {
-                        Value::Undefined
-                    };
+    /// Create a closure over `function`, moving the locals it captures into heap cells
+    /// so the closure and the running function keep sharing them
+    fn make_closure(&mut self, function: FunctionObj) -> Closure {
+        let mut upvalues = Vec::with_capacity(function.captures.len());
+        for capture in &function.captures {
+            let cell = match *capture {
+                Capture::Local(idx) => self.capture_local(idx),
+                Capture::Upvalue(idx) => match self.upvalue_cell(idx) {
+                    Some(cell) => cell,
+                    None => self.heap.allocate(HeapEntry::Cell(Value::Undefined)),
+                },
+            };
+            upvalues.push(cell);
+        }
 
-                    // Leave the callee (function) on the stack.
-                    // self.stack.values.truncate(frame.base_pointer); // NOTE: base_pointer is 0 here so this doesn't matter.
+        let mut closure = Closure::new(function);
+        closure.upvalues = upvalues;
+        closure
+    }
 
-                    // Push the return value
-                    if ret != Value::Undefined {
-                        self.stack.values.push(ret);
-                    }
+    /// The cell holding a local of the running function, once a closure captured it
+    fn captured_cell(&self, idx: usize) -> Option<HandleId> {
+        match self.stack.frames.last() {
+            Some(frame) => frame.cells.get(&idx).copied(),
+            None => self.cells.get(&idx).copied(),
+        }
+    }
+
+    /// The cell of an upvalue of the running closure
+    fn upvalue_cell(&self, idx: usize) -> Option<HandleId> {
+        self.stack
+            .frames
+            .last()
+            .and_then(|frame| frame.upvalues.get(idx).copied())
+    }
+
+    /// Move a local of the running function into a heap cell, unless that already happened
+    fn capture_local(&mut self, idx: usize) -> HandleId {
+        if let Some(cell) = self.captured_cell(idx) {
+            return cell;
+        }
 
-                    self.instructions = frame.return_instructions;
-                    self.constants = frame.return_constants;
-                    self.ip = frame.return_ip;
+        let (locals, cells) = match self.stack.frames.last_mut() {
+            Some(frame) => (&frame.locals, &mut frame.cells),
+            None => (&self.locals, &mut self.cells),
+        };
+        let value = locals.get(idx).cloned().unwrap_or(Value::Undefined);
+        let cell = self.heap.allocate(HeapEntry::Cell(value));
+        cells.insert(idx, cell);
+        cell
+    }
+
+    fn read_cell(&self, cell: HandleId) -> Value {
+        match self.heap.get(cell) {
+            Some(HeapEntry::Cell(value)) => value.clone(),
+            _ => Value::Undefined,
+        }
+    }
+
+    fn write_cell(&mut self, cell: HandleId, value: Value) {
+        if let Some(HeapEntry::Cell(slot)) = self.heap.get_mut(cell) {
+            *slot = value;
+        }
+    }
+
+    /// Run the constructor of `class` on `this`, the call evaluates to `this`
+    fn construct(&mut self, class: HandleId, this: Value, args: Vec<Value>) -> VmResult<()> {
+        let constructor = match self.heap.get(class) {
+            Some(HeapEntry::Class(class)) => class.constructor.clone(),
+            _ => None,
+        };
+        match constructor {
+            Some(constructor) => {
+                let kind = CallKind::Construct(this.clone());
+                self.call_closure(constructor, args, &[], this, kind)
+            }
+            None => {
+                self.stack.push(this);
+                self.ip += 1;
+                Ok(())
+            }
+        }
+    }
+
+    /// Find a method, getter or setter in `class` or the classes it extends, `is_static`
+    /// picks the members of the classes themselves over those of their instances
+    fn lookup(
+        &self,
+        class: HandleId,
+        is_static: bool,
+        kind: MethodKind,
+        name: &str,
+    ) -> Option<Closure> {
+        let mut current = Some(class);
+        while let Some(HeapEntry::Class(class)) = current.and_then(|handle| self.heap.get(handle)) {
+            let members = if is_static {
+                &class.statics
+            } else {
+                &class.prototype
+            };
+            if let Some(method) = members.get(kind, name) {
+                return Some(method.clone());
+            }
+            current = class.superclass;
+        }
+        None
+    }
+
+    /// A static field of `class`, inherited from the classes it extends
+    fn static_field(&self, class: HandleId, name: &str) -> Option<Value> {
+        let mut current = Some(class);
+        while let Some(HeapEntry::Class(class)) = current.and_then(|handle| self.heap.get(handle)) {
+            if let Some(value) = class.fields.get(name) {
+                return Some(value.clone());
+            }
+            current = class.superclass;
+        }
+        None
+    }
+
+    /// The getter or setter `key` of an instance or a class, a field of the same name
+    /// the object holds itself comes first
+    fn find_accessor(&self, object: &Value, key: &Value, kind: MethodKind) -> Option<Closure> {
+        let Value::String(name) = key else {
+            return None;
+        };
+        match (object, self.heap_entry(object)?) {
+            (_, HeapEntry::Instance(instance)) if !instance.fields.contains_key(name) => {
+                self.lookup(instance.class, false, kind, name)
+            }
+            (Value::Class(handle), HeapEntry::Class(class)) if !class.fields.contains_key(name) => {
+                self.lookup(*handle, true, kind, name)
+            }
+            _ => None,
+        }
+    }
+
+    /// The function `receiver.name(...)` calls, a method of an instance or class or a
+    /// function stored in a property
+    fn find_method(&self, receiver: &Value, name: &str) -> Option<Closure> {
+        match (receiver, self.heap_entry(receiver)?) {
+            (_, HeapEntry::Object(_) | HeapEntry::Instance(_) | HeapEntry::Class(_)) => {
+                match self.get_property(receiver, &Value::String(name.to_string())) {
+                    Ok(Value::Function(closure)) => Some(closure),
+                    _ => None,
                 }
-                _ => self.ip += 1,
             }
+            _ => None,
+        }
+    }
+
+    /// Whether an object or instance has the property `name`, held itself or through
+    /// its class
+    fn has_property(&self, object: &Value, name: &str) -> bool {
+        match self.heap_entry(object) {
+            Some(HeapEntry::Object(properties)) => properties.contains_key(name),
+            Some(HeapEntry::Instance(instance)) => {
+                instance.fields.contains_key(name)
+                    || [MethodKind::Getter, MethodKind::Method]
+                        .into_iter()
+                        .any(|kind| self.lookup(instance.class, false, kind, name).is_some())
+            }
+            _ => false,
+        }
+    }
+
+    /// The heap entry behind an object, array or class
+    fn heap_entry(&self, value: &Value) -> Option<&HeapEntry> {
+        match value {
+            Value::Object(handle) | Value::Array(handle) | Value::Class(handle) => {
+                self.heap.get(*handle)
+            }
+            _ => None,
+        }
+    }
+
+    /// Push `object[key]`, a getter runs in a frame of its own and pushes its result
+    fn read_member(&mut self, object: Value, key: Value) -> VmResult<()> {
+        if let Some(getter) = self.find_accessor(&object, &key, MethodKind::Getter) {
+            return self.call_closure(getter, Vec::new(), &[], object, CallKind::Getter);
+        }
+        let value = self.get_property(&object, &key)?;
+        self.stack.push(value);
+        self.ip += 1;
+        Ok(())
+    }
+
+    /// Set `object[key]`, through a setter when the object has one
+    fn write_member(&mut self, object: Value, key: Value, value: Value) -> VmResult<()> {
+        if let Some(setter) = self.find_accessor(&object, &key, MethodKind::Setter) {
+            return self.call_closure(setter, vec![value], &[], object, CallKind::Setter);
         }
+        self.set_property(&object, &key, value)?;
+        self.ip += 1;
         Ok(())
     }
+
+    /// Read a property of an object, an element of an array or a character of a string
+    fn get_property(&self, object: &Value, key: &Value) -> VmResult<Value> {
+        let value = match object {
+            Value::Object(handle) => match self.heap.get(*handle) {
+                Some(HeapEntry::Object(properties)) => properties.get(&key.to_string()).cloned(),
+                // Fields first, then the methods shared through the class
+                Some(HeapEntry::Instance(instance)) => {
+                    let name = key.to_string();
+                    instance.fields.get(&name).cloned().or_else(|| {
+                        self.lookup(instance.class, false, MethodKind::Method, &name)
+                            .map(Value::Function)
+                    })
+                }
+                _ => None,
+            },
+            Value::Class(handle) => {
+                let name = key.to_string();
+                self.static_field(*handle, &name).or_else(|| {
+                    self.lookup(*handle, true, MethodKind::Method, &name)
+                        .map(Value::Function)
+                })
+            }
+            Value::Array(handle) => match (self.heap.get(*handle), key) {
+                (Some(HeapEntry::Array(elements)), Value::String(name)) if name == "len" => {
+                    Some(Value::Integer(elements.len() as i64))
+                }
+                (Some(HeapEntry::Array(elements)), Value::Integer(index)) => {
+                    usize::try_from(*index)
+                        .ok()
+                        .and_then(|index| elements.get(index).cloned())
+                }
+                _ => None,
+            },
+            Value::String(s) => match key {
+                Value::String(name) if name == "len" => {
+                    Some(Value::Integer(s.chars().count() as i64))
+                }
+                Value::Integer(index) => usize::try_from(*index)
+                    .ok()
+                    .and_then(|index| s.chars().nth(index))
+                    .map(|c| Value::String(c.to_string())),
+                _ => None,
+            },
+            // The fields of a variant by name, or by position for patterns
+            Value::Variant(variant) => match key {
+                Value::Integer(index) => usize::try_from(*index)
+                    .ok()
+                    .and_then(|index| variant.fields.get(index))
+                    .map(|(_, value)| value.clone()),
+                _ => variant.field(&key.to_string()).cloned(),
+            },
+            Value::Null | Value::Undefined => {
+                return Err(VMError::invalid_property_access(key, object));
+            }
+            _ => None,
+        };
+        Ok(value.unwrap_or(Value::Undefined))
+    }
+
+    /// The elements of an array, or the characters of a string
+    fn iterate(&self, value: &Value) -> VmResult<Vec<Value>> {
+        match value {
+            Value::Array(handle) => match self.heap.get(*handle) {
+                Some(HeapEntry::Array(elements)) => Ok(elements.clone()),
+                _ => Ok(Vec::new()),
+            },
+            Value::String(s) => Ok(s.chars().map(|c| Value::String(c.to_string())).collect()),
+            _ => Err(VMError::not_iterable(value)),
+        }
+    }
+
+    /// Call one of the built-in methods, `push` and `pop` on arrays
+    fn call_method(&mut self, receiver: &Value, name: &str, args: Vec<Value>) -> VmResult<Value> {
+        if let Value::Array(handle) = receiver {
+            if let Some(HeapEntry::Array(elements)) = self.heap.get_mut(*handle) {
+                match name {
+                    "push" => {
+                        elements.extend(args);
+                        return Ok(Value::Integer(elements.len() as i64));
+                    }
+                    "pop" => return Ok(elements.pop().unwrap_or(Value::Undefined)),
+                    _ => {}
+                }
+            }
+        }
+        Err(VMError::unknown_method(name, receiver))
+    }
+
+    /// Set a property of an object or an element of an array, arrays grow to fit the index
+    fn set_property(&mut self, object: &Value, key: &Value, value: Value) -> VmResult<()> {
+        match (object, key) {
+            (Value::Object(handle), _) => match self.heap.get_mut(*handle) {
+                Some(HeapEntry::Object(properties)) => {
+                    properties.insert(key.to_string(), value);
+                    return Ok(());
+                }
+                Some(HeapEntry::Instance(instance)) => {
+                    instance.fields.insert(key.to_string(), value);
+                    return Ok(());
+                }
+                _ => {}
+            },
+            (Value::Class(handle), _) => {
+                if let Some(HeapEntry::Class(class)) = self.heap.get_mut(*handle) {
+                    class.fields.insert(key.to_string(), value);
+                    return Ok(());
+                }
+            }
+            (Value::Array(handle), Value::Integer(index)) if *index >= 0 => {
+                if let Some(HeapEntry::Array(elements)) = self.heap.get_mut(*handle) {
+                    let index = *index as usize;
+                    if index >= elements.len() {
+                        elements.resize(index + 1, Value::Undefined);
+                    }
+                    elements[index] = value;
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
+        Err(VMError::invalid_property_assignment(key, object))
+    }
+}
+
+fn trace_entry(function: &str, span: Option<&Span>) -> String {
+    match span {
+        Some(span) => format!("at {} (line {})", function, span.start.line),
+        None => format!("at {}", function),
+    }
 }
@@ -19,7 +19,7 @@ fn test_plus_assign_expression() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(3.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(3)]);
 }
 
 #[test]
@@ -39,5 +39,5 @@ fn test_minus_assign_expression() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(1.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(1)]);
 }
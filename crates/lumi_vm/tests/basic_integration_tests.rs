@@ -15,7 +15,7 @@ fn test_value_is_number() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(42.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(42)]);
 }
 
 #[test]
@@ -66,7 +66,7 @@ fn test_variables_and_print() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(75.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(75)]);
 }
 
 #[test]
@@ -74,7 +74,7 @@ fn test_fn_and_call_fn() {
     let mut parser = Parser::new(
         r#"
         fn test(x, y) {
-            x + y;
+            return x + y;
         }
 
         test(1, 2);
@@ -87,7 +87,7 @@ fn test_fn_and_call_fn() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(3.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(3)]);
 }
 
 #[test]
@@ -110,7 +110,7 @@ fn test_fn_with_passed_vars_and_call_fn() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(7.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(7)]);
 }
 
 #[test]
@@ -131,7 +131,7 @@ fn test_fn_with_return_statement() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(6.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(6)]);
 }
 
 #[test]
@@ -154,7 +154,7 @@ fn test_fn_with_passed_variable() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(10.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(10)]);
 }
 
 #[test]
@@ -176,7 +176,7 @@ fn test_assign_fn_result_to_variable() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(7.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(7)]);
 }
 
 #[test]
@@ -199,7 +199,84 @@ fn test_assign_fn_result_to_variable_extended() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(9.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(9)]);
+}
+
+#[test]
+fn test_interpolated_string() {
+    let mut parser = Parser::new(
+        r#"
+        let x: int -> 2;
+        "count: ${x + 1}, next: ${x * 2}";
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    let mut bytecode_generator = BytecodeGenerator::new();
+    let bytecode = bytecode_generator.generate(&ast);
+
+    let mut vm = Vm::new();
+    vm.execute(bytecode).unwrap();
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::String("count: 3, next: 4".to_string())]
+    );
+}
+
+#[test]
+fn test_integer_and_float_arithmetic() {
+    let mut parser = Parser::new(
+        r#"
+        7 / 2;
+        7 % 2;
+        1 + 0.5;
+        9223372036854775807 + 1;
+        2 < 2.5;
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    let mut bytecode_generator = BytecodeGenerator::new();
+    let bytecode = bytecode_generator.generate(&ast);
+
+    let mut vm = Vm::new();
+    vm.execute(bytecode).unwrap();
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Number(3.5),
+            Value::Integer(1),
+            Value::Number(1.5),
+            Value::Number(9223372036854775808.0),
+            Value::Boolean(true),
+        ]
+    );
+}
+
+#[test]
+fn test_big_integer_arithmetic() {
+    let mut parser = Parser::new(
+        r#"
+        9007199254740993n * 1_000n + 7;
+        10n / 3n;
+        2n < 3;
+    "#,
+    );
+    let ast = parser.parse().unwrap();
+    let mut bytecode_generator = BytecodeGenerator::new();
+    let bytecode = bytecode_generator.generate(&ast);
+
+    let mut vm = Vm::new();
+    vm.execute(bytecode).unwrap();
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::BigInt("9007199254740993007".parse().unwrap()),
+            Value::BigInt(3.into()),
+            Value::Boolean(true),
+        ]
+    );
 }
 
 // TODO: doesn't work yet
@@ -226,5 +303,5 @@ fn test_assign_fn_result_to_variable_extended() {
 //     let mut vm = Vm::new();
 //     vm.execute(bytecode).unwrap();
 
-//     assert_eq!(vm.stack.values, vec![Value::Number(13.0)]);
+//     assert_eq!(vm.stack.values, vec![Value::Integer(13)]);
 // }
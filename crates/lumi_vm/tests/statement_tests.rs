@@ -1,6 +1,6 @@
 use lumi_bytecode::BytecodeGenerator;
 use lumi_parser::Parser;
-use lumi_vm::Vm;
+use lumi_vm::{HeapEntry, VMError, Value, Vm};
 
 #[test]
 fn test_print_statement() {
@@ -101,5 +101,1092 @@ fn test_calling_fn_twice() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![]);
+    assert_eq!(vm.stack.values, vec![Value::Undefined; 3]);
+}
+
+fn run(source: &str) -> Vm {
+    let mut parser = Parser::new(source);
+    let ast = parser.parse().unwrap();
+    let mut bytecode_generator = BytecodeGenerator::new();
+    let bytecode = bytecode_generator.generate(&ast);
+
+    let mut vm = Vm::new();
+    vm.execute(bytecode).unwrap();
+    vm
+}
+
+#[test]
+fn test_while_statement() {
+    let vm = run("let i -> 0; while (i < 3) { i += 1; } i;");
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(3)]);
+}
+
+#[test]
+fn test_do_while_runs_body_once() {
+    let vm = run("let i -> 10; do { i += 1; } while (i < 3); i;");
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(11)]);
+}
+
+#[test]
+fn test_loop_with_break() {
+    let vm = run("let i -> 0; loop { i += 1; if (i > 4) { break; } } i;");
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(5)]);
+}
+
+#[test]
+fn test_continue_in_for_statement_runs_step() {
+    let vm = run(r#"
+        let total -> 0;
+        for i in 1 to 5 step 1 {
+            if (i < 3) { continue; }
+            total += i;
+        }
+        total;
+    "#);
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(12)]);
+}
+
+#[test]
+fn test_labeled_break_leaves_outer_loop() {
+    let vm = run(r#"
+        let count -> 0;
+        outer: for i in 1 to 3 step 1 {
+            for j in 1 to 3 step 1 {
+                if (j > 1) { continue outer; }
+                if (i > 2) { break outer; }
+                count += 1;
+            }
+        }
+        count;
+    "#);
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(2)]);
+}
+
+#[test]
+fn test_return_value_from_function() {
+    let vm = run("fn add(a, b) { return a + b; } add(1, 2);");
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(3)]);
+}
+
+#[test]
+fn test_early_return_from_loop() {
+    let vm = run(r#"
+        fn firstAbove(limit) {
+            for i in 1 to 10 step 1 {
+                if (i > limit) { return i; }
+            }
+            return 0;
+        }
+        firstAbove(4);
+    "#);
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(5)]);
+}
+
+#[test]
+fn test_bare_return_leaves_caller_stack() {
+    let vm = run("1; fn nothing() { return; } nothing();");
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(1), Value::Undefined]);
+}
+
+#[test]
+fn test_return_value_does_not_depend_on_leftover_values() {
+    let vm = run(r#"
+        fn seven() { return 7; }
+        fn ignore() { seven(); }
+        fn leave() { seven(); return; }
+        ignore();
+        leave();
+    "#);
+
+    assert_eq!(vm.stack.values, vec![Value::Undefined, Value::Undefined]);
+}
+
+#[test]
+fn test_string_indexing_and_optional_chaining() {
+    let vm = run(r#"let s -> "abc"; s[1]; s?.[5];"#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::String("b".to_string()), Value::Undefined]
+    );
+}
+
+#[test]
+fn test_object_literal() {
+    let vm = run(r#"
+        let x -> 7;
+        let o -> { a: 1, "b c": 2, ["k"]: 3, x };
+        o.a += 4;
+        o.a;
+        o["b c"];
+        o.k;
+        o.x;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(5),
+            Value::Integer(2),
+            Value::Integer(3),
+            Value::Integer(7)
+        ]
+    );
+}
+
+#[test]
+fn test_array_literal_with_holes() {
+    let vm = run("let a -> [1, , 3]; a.len; a[1]; a[2];");
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::Integer(3), Value::Undefined, Value::Integer(3)]
+    );
+}
+
+#[test]
+fn test_array_spread() {
+    let vm = run(r#"
+        let a -> [1, 2];
+        let b -> [...a, 3, ..."xy", 4];
+        b.len;
+        b[2];
+        b[4];
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(6),
+            Value::Integer(3),
+            Value::String("y".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_array_push_pop_and_index_assignment() {
+    let vm = run(r#"
+        let a -> [];
+        a.push(1);
+        a.push(2, 3);
+        a.pop();
+        a[0] = 10;
+        a[0];
+        a.len;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(1),
+            Value::Integer(3),
+            Value::Integer(3),
+            Value::Integer(10),
+            Value::Integer(2)
+        ]
+    );
+}
+
+#[test]
+fn test_arrow_functions_are_values() {
+    let vm = run("let add -> (a, b) => a + b; add(2, 3); ((x) => x * 2)(21);");
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(5), Value::Integer(42)]);
+}
+
+#[test]
+fn test_closures_keep_their_own_captured_variables() {
+    let vm = run(r#"
+        fn makeCounter() {
+            let count -> 0;
+            return () => {
+                count += 1;
+                return count;
+            };
+        }
+        let a -> makeCounter();
+        let b -> makeCounter();
+        a();
+        a();
+        b();
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::Integer(1), Value::Integer(2), Value::Integer(1)]
+    );
+}
+
+#[test]
+fn test_closure_shares_variable_with_enclosing_function() {
+    let vm = run(r#"
+        fn outer() {
+            let x -> 1;
+            let get -> () => x;
+            x = 5;
+            return get();
+        }
+        outer();
+    "#);
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(5)]);
+}
+
+#[test]
+fn test_nested_closures_capture_through_each_level() {
+    let vm = run("fn make() { let n -> 10; return () => () => n + 1; } make()()();");
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(11)]);
+}
+
+#[test]
+fn test_closures_made_in_a_loop_capture_their_own_iteration() {
+    let vm = run(r#"
+        let fs -> [];
+        for i in 0 to 4 {
+            fs.push(() => i);
+        }
+        let gs -> [];
+        let n -> 0;
+        while (n < 3) {
+            let doubled -> n * 2;
+            gs.push(() => doubled + n);
+            n += 1;
+        }
+        fs[0]();
+        fs[3]();
+        gs[1]();
+    "#);
+
+    // Variables declared before the loop stay shared, `n` is 3 once the loop is done
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::Integer(0), Value::Integer(3), Value::Integer(5)]
+    );
+}
+
+#[test]
+fn test_recursive_and_mutually_recursive_functions() {
+    let vm = run(r#"
+        fn fact(n) {
+            if (n < 2) { return 1; }
+            return n * fact(n - 1);
+        }
+        fn isEven(n) {
+            if (n < 1) { return true; }
+            return isOdd(n - 1);
+        }
+        fn isOdd(n) {
+            if (n < 1) { return false; }
+            return isEven(n - 1);
+        }
+        fact(5);
+        isEven(4);
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::Integer(120), Value::Boolean(true)]
+    );
+}
+
+#[test]
+fn test_named_function_expression_calls_itself() {
+    let vm = run(r#"
+        let f -> fn fib(n) {
+            if (n < 2) { return n; }
+            return fib(n - 1) + fib(n - 2);
+        };
+        f(10);
+    "#);
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(55)]);
+}
+
+#[test]
+fn test_calling_a_non_function_fails() {
+    let mut parser = Parser::new("let n -> 1; n();");
+    let ast = parser.parse().unwrap();
+    let bytecode = BytecodeGenerator::new().generate(&ast);
+
+    let mut vm = Vm::new();
+    assert_eq!(
+        vm.execute(bytecode),
+        Err(VMError::CalleeIsNotFunction {
+            callee: "1".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_typed_function() {
+    let vm = run(r#"
+        fn add(a: int, b: int): int { return a + b; }
+        let double -> (x: int): int => x * 2;
+        add(1, 2);
+        double(21);
+    "#);
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(3), Value::Integer(42)]);
+}
+
+#[test]
+fn test_default_and_optional_parameters() {
+    let vm = run(r#"
+        fn greet(name, greeting -> "hi") { return greeting; }
+        greet("lumi");
+        greet("lumi", "hey");
+        fn scale(x, by -> x * 2) { return by; }
+        scale(4);
+        fn maybe(x?) { return x; }
+        maybe();
+        maybe(1);
+        fn second(a, b?) { return b; }
+        second(1);
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::String("hi".to_string()),
+            Value::String("hey".to_string()),
+            Value::Integer(8),
+            Value::Undefined,
+            Value::Integer(1),
+            Value::Undefined
+        ]
+    );
+}
+
+#[test]
+fn test_rest_parameter_collects_extra_arguments() {
+    let vm = run(r#"
+        fn count(first, ...rest) { return rest.len; }
+        fn second(first, ...rest) { return rest[1]; }
+        count(1);
+        count(1, 2, 3);
+        second(1, 2, 3);
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::Integer(0), Value::Integer(2), Value::Integer(3)]
+    );
+}
+
+#[test]
+fn test_named_arguments() {
+    let vm = run(r#"
+        fn sub(a, b -> 10) { return a - b; }
+        sub(b: 1, a: 5);
+        sub(5, b: 2);
+        sub(a: 15);
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::Integer(4), Value::Integer(3), Value::Integer(5)]
+    );
+}
+
+#[test]
+fn test_unknown_named_argument_fails() {
+    let mut parser = Parser::new("fn f(a) { return a; } f(b: 1);");
+    let ast = parser.parse().unwrap();
+    let bytecode = BytecodeGenerator::new().generate(&ast);
+
+    let mut vm = Vm::new();
+    assert_eq!(
+        vm.execute(bytecode),
+        Err(VMError::InvalidNamedArgument {
+            function: "f".to_string(),
+            name: "b".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_conditional_expression() {
+    let vm = run(r#"
+        let big -> 5 > 3 ? "big" : "small";
+        big;
+        1 > 2 ? 1 : 2 > 3 ? 2 : 3;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::String("big".to_string()), Value::Integer(3)]
+    );
+}
+
+#[test]
+fn test_nullish_coalescing() {
+    let vm = run(r#"
+        null ?? 1;
+        undefined ?? null ?? 2;
+        0 ?? 3;
+        let a -> null;
+        a ??= 4;
+        a ??= 5;
+        a;
+        let o -> { x: null, y: 1 };
+        o.x ??= 6;
+        o.y ??= 7;
+        o.x;
+        o.y;
+        let arr -> [null];
+        arr[0] ??= 8;
+        arr[0];
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(1),
+            Value::Integer(2),
+            Value::Integer(0),
+            Value::Integer(4),
+            Value::Integer(6),
+            Value::Integer(1),
+            Value::Integer(8)
+        ]
+    );
+}
+
+#[test]
+fn test_compound_assignment_operators() {
+    let vm = run(r#"
+        let a -> 6;
+        a *= 7;
+        a;
+        let b -> 7;
+        b /= 2;
+        b;
+        let c -> 17;
+        c %= 5;
+        c;
+        let d -> 3;
+        d **= 3;
+        d;
+        let o -> { n: 2, xs: [5] };
+        o.n **= 10;
+        o.xs[0] *= 3;
+        o.n;
+        o.xs[0];
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(42),
+            Value::Number(3.5),
+            Value::Integer(2),
+            Value::Integer(27),
+            Value::Integer(1024),
+            Value::Integer(15)
+        ]
+    );
+}
+
+#[test]
+fn test_exponent_operator() {
+    let vm = run(r#"
+        2 ** 3 ** 2;
+        2 ** -1;
+        1.5 ** 2;
+        2 ** 64;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(512),
+            Value::Number(0.5),
+            Value::Number(2.25),
+            Value::Number(18446744073709551616.0)
+        ]
+    );
+}
+
+#[test]
+fn test_update_operators() {
+    let vm = run(r#"
+        let i -> 1;
+        i++;
+        ++i;
+        i--;
+        --i;
+        i;
+        let o -> { n: 10 };
+        o.n++;
+        ++o.n;
+        o.n;
+        let xs -> [5];
+        xs[0]--;
+        --xs[0];
+        xs[0];
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(1),
+            Value::Integer(3),
+            Value::Integer(3),
+            Value::Integer(1),
+            Value::Integer(1),
+            Value::Integer(10),
+            Value::Integer(12),
+            Value::Integer(12),
+            Value::Integer(5),
+            Value::Integer(3),
+            Value::Integer(3)
+        ]
+    );
+}
+
+#[test]
+fn test_class_fields_constructor_and_methods() {
+    let vm = run(r#"
+        class Point {
+            x -> 0;
+            y -> 0;
+            label -> "p";
+            constructor(x, y) {
+                this.x -> x;
+                this.y -> y;
+            }
+            fn sum() { return this.x + this.y; }
+            fn scale(n) { this.x *= n; this.y *= n; return this; }
+        }
+        let p -> new Point(1, 2);
+        p.sum();
+        p.scale(3).sum();
+        p.label;
+        let q -> new Point(5, 5);
+        q.sum();
+        p.sum();
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(3),
+            Value::Integer(9),
+            Value::String("p".to_string()),
+            Value::Integer(10),
+            Value::Integer(9)
+        ]
+    );
+}
+
+#[test]
+fn test_class_static_members() {
+    let vm = run(r#"
+        class Counter {
+            static count -> 0;
+            static fn increment() { this.count += 1; return this.count; }
+        }
+        Counter.increment();
+        Counter.increment();
+        Counter.count;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::Integer(1), Value::Integer(2), Value::Integer(2)]
+    );
+}
+
+#[test]
+fn test_class_getters_and_setters() {
+    let vm = run(r#"
+        class Temperature {
+            celsius -> 0;
+            get fahrenheit() { return this.celsius * 9 / 5 + 32; }
+            set fahrenheit(value) { this.celsius -> (value - 32) * 5 / 9; }
+        }
+        let t -> new Temperature();
+        t.fahrenheit;
+        t.fahrenheit -> 212;
+        t.celsius;
+        t.fahrenheit;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Number(32.0),
+            Value::Number(100.0),
+            Value::Number(212.0)
+        ]
+    );
+}
+
+#[test]
+fn test_class_inheritance_and_super() {
+    let vm = run(r#"
+        class Animal {
+            legs -> 4;
+            constructor(name) { this.name -> name; }
+            fn describe() { return this.name + " has legs"; }
+            fn speak() { return "..."; }
+            static fn kind() { return "animal"; }
+        }
+        class Bird extends Animal {
+            wings -> 2;
+            constructor(name) {
+                super(name);
+                this.legs -> 2;
+            }
+            fn speak() { return "tweet " + super.speak(); }
+            static fn kind() { return "bird, " + super.kind(); }
+        }
+        class Parrot extends Bird {}
+        let b -> new Parrot("polly");
+        b.name;
+        b.legs;
+        b.wings;
+        b.describe();
+        b.speak();
+        Parrot.kind();
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::String("polly".to_string()),
+            Value::Integer(2),
+            Value::Integer(2),
+            Value::String("polly has legs".to_string()),
+            Value::String("tweet ...".to_string()),
+            Value::String("bird, animal".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_throw_and_catch() {
+    let vm = run(r#"
+        let log -> [];
+        try {
+            log.push(1);
+            throw "boom";
+            log.push(2);
+        } catch (e) {
+            log.push(e);
+        } finally {
+            log.push(3);
+        }
+        try { log.push(4); } finally { log.push(5); }
+        log;
+    "#);
+
+    let Some(Value::Array(handle)) = vm.stack.values.last() else {
+        panic!("Expected the log array");
+    };
+    let Some(HeapEntry::Array(log)) = vm.heap.get(*handle) else {
+        panic!("Expected the log array on the heap");
+    };
+    assert_eq!(
+        log,
+        &vec![
+            Value::Integer(1),
+            Value::String("boom".to_string()),
+            Value::Integer(3),
+            Value::Integer(4),
+            Value::Integer(5)
+        ]
+    );
+}
+
+#[test]
+fn test_throw_unwinds_frames() {
+    let vm = run(r#"
+        fn inner(n) {
+            if (n > 2) { throw n * 10; }
+            return inner(n + 1);
+        }
+        let cleaned -> 0;
+        fn outer() {
+            try { return inner(0); } finally { cleaned += 1; }
+        }
+        let caught -> 0;
+        try { outer(); } catch (e) { caught -> e; }
+        caught;
+        cleaned;
+    "#);
+
+    assert_eq!(vm.stack.values, vec![Value::Integer(30), Value::Integer(1)]);
+}
+
+#[test]
+fn test_catch_drops_operands_of_the_throwing_expression() {
+    let vm = run(r#"
+        fn t() { throw 1; }
+        fn f() {
+            try { let x -> 5 + t(); } catch (e) {}
+        }
+        "first";
+        try { let y -> [1, 2, t()]; } catch (e) {}
+        f();
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::String("first".to_string()), Value::Undefined]
+    );
+}
+
+#[test]
+fn test_finally_runs_on_return_and_break() {
+    let vm = run(r#"
+        let runs -> 0;
+        fn f() {
+            try { return "returned"; } finally { runs += 1; }
+        }
+        let result -> f();
+        let i -> 0;
+        while (i < 10) {
+            try {
+                i += 1;
+                if (i > 2) { break; }
+                continue;
+            } finally {
+                runs += 1;
+            }
+        }
+        result;
+        runs;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::String("returned".to_string()), Value::Integer(4)]
+    );
+}
+
+#[test]
+fn test_vm_errors_are_catchable() {
+    let vm = run(r#"
+        fn call(f) { return f(); }
+        let message -> "";
+        let trace -> [];
+        try {
+            call(5);
+        } catch (e) {
+            message -> e.message;
+            trace -> e.stack;
+        }
+        message;
+        trace[0];
+        trace[1];
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::String("Callee is not a function: 5".to_string()),
+            Value::String("at call (line 2)".to_string()),
+            Value::String("at <main> (line 6)".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_uncaught_throw() {
+    let mut parser = Parser::new(
+        r#"
+        fn fail() { throw "bad"; }
+        fn wrap() {
+            try { fail(); } finally { let cleaned -> true; }
+        }
+        try { wrap(); } finally { print "cleanup"; }
+        "#,
+    );
+    let ast = parser.parse().unwrap();
+    let bytecode = BytecodeGenerator::new().generate(&ast);
+
+    let mut vm = Vm::new();
+    assert_eq!(
+        vm.execute(bytecode),
+        Err(VMError::Uncaught {
+            value: "bad".to_string(),
+            trace: vec![
+                "at fail (line 2)".to_string(),
+                "at wrap (line 4)".to_string(),
+                "at <main> (line 6)".to_string()
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_switch_with_jump_table() {
+    let vm = run(r#"
+        let names -> [];
+        for i in 0 to 5 {
+            switch (i) {
+                case 1: names.push("one");
+                case 2, 3: names.push("few");
+                case 4: names.push("four");
+                default: names.push("other");
+            }
+        }
+        names;
+    "#);
+
+    let Some(Value::Array(handle)) = vm.stack.values.last() else {
+        panic!("Expected an array");
+    };
+    let Some(HeapEntry::Array(names)) = vm.heap.get(*handle) else {
+        panic!("Expected an array");
+    };
+    let expected: Vec<Value> = ["other", "one", "few", "few", "four", "other"]
+        .into_iter()
+        .map(|name| Value::String(name.to_string()))
+        .collect();
+    assert_eq!(*names, expected);
+}
+
+#[test]
+fn test_switch_with_compare_chain() {
+    let vm = run(r#"
+        let total -> 0;
+        fn count(word) {
+            switch (word) {
+                case "a": total += 1;
+                case "b": total += 10;
+            }
+        }
+        count("a");
+        count("b");
+        count("c");
+        total;
+    "#);
+
+    assert_eq!(vm.stack.values.last(), Some(&Value::Integer(11)));
+}
+
+#[test]
+fn test_break_in_switch_leaves_only_the_switch() {
+    let vm = run(r#"
+        let seen -> [];
+        for i in 1 to 3 {
+            switch (i) {
+                case 1: seen.push("one"); break; seen.push("skipped");
+                case 2: continue;
+                default: seen.push("d");
+            }
+            seen.push(i);
+        }
+        switch ("top") { case "top": break; }
+        seen;
+    "#);
+
+    let Some(Value::Array(handle)) = vm.stack.values.last() else {
+        panic!("Expected an array");
+    };
+    let Some(HeapEntry::Array(seen)) = vm.heap.get(*handle) else {
+        panic!("Expected an array");
+    };
+    assert_eq!(
+        *seen,
+        vec![
+            Value::String("one".to_string()),
+            Value::Integer(1),
+            Value::String("d".to_string()),
+            Value::Integer(3)
+        ]
+    );
+}
+
+#[test]
+fn test_match_patterns() {
+    let vm = run(r#"
+        fn describe(value) {
+            return match (value) {
+                0 => "zero",
+                1 to 9 => "digit",
+                "hi" => "greeting",
+                [] => "empty",
+                [first, ...rest] => first + rest.len,
+                { x, y: 0 } => x,
+                n if n < 0 => "negative",
+                _ => "other",
+            };
+        }
+        let results -> [
+            describe(0), describe(7), describe("hi"), describe([]), describe([5, 6, 7]),
+            describe({ x: "on axis", y: 0 }), describe({ x: 1, y: 2 }), describe(-3), describe(42)
+        ];
+        results;
+    "#);
+
+    let Some(Value::Array(handle)) = vm.stack.values.last() else {
+        panic!("Expected an array");
+    };
+    let Some(HeapEntry::Array(results)) = vm.heap.get(*handle) else {
+        panic!("Expected an array");
+    };
+    assert_eq!(
+        *results,
+        vec![
+            Value::String("zero".to_string()),
+            Value::String("digit".to_string()),
+            Value::String("greeting".to_string()),
+            Value::String("empty".to_string()),
+            Value::Integer(7),
+            Value::String("on axis".to_string()),
+            Value::String("other".to_string()),
+            Value::String("negative".to_string()),
+            Value::String("other".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_match_with_jump_table_and_binding() {
+    let vm = run(r#"
+        let small -> match (2) { 1 => "one", 2 => "two", 3 => "three", n => n * 10 };
+        let large -> match (8) { 1 => "one", 2 => "two", 3 => "three", n => n * 10 };
+        small;
+        large;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::String("two".to_string()), Value::Integer(80)]
+    );
+}
+
+#[test]
+fn test_match_without_matching_arm_throws() {
+    let vm = run(r#"
+        let message -> "";
+        try {
+            let result -> match (5) { 1 => "one", 2 => "two" };
+        } catch (e) {
+            message -> e.message;
+        }
+        message;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::String("No match arm matches 5".to_string())]
+    );
+}
+
+#[test]
+fn test_enum_variants_and_matching() {
+    let vm = run(r#"
+        enum Shape { Circle(r: float), Rect(w: float, h: float), Empty }
+        fn area(shape) {
+            return match (shape) {
+                Shape.Circle(r) => 3 * r * r,
+                Shape.Rect(w, h) => w * h,
+                Shape.Empty => 0,
+            };
+        }
+        let rect -> Shape.Rect(2, 5);
+        area(Shape.Circle(2));
+        area(rect);
+        area(Shape.Empty);
+        rect.h;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(12),
+            Value::Integer(10),
+            Value::Integer(0),
+            Value::Integer(5),
+        ]
+    );
+}
+
+#[test]
+fn test_enum_variant_display() {
+    let vm = run(r#"
+        enum Shape { Rect(w, h), Empty }
+        let message -> "";
+        try {
+            let result -> match (Shape.Rect(2, 3)) { Shape.Empty => 0 };
+        } catch (e) {
+            message -> e.message;
+        }
+        message;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::String(
+            "No match arm matches Shape.Rect(2, 3)".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_destructuring_declarations() {
+    let vm = run(r#"
+        let [a, _, ...rest] -> [1, 2, 3, 4];
+        let { x, y: renamed, z -> 10 } -> { x: 5, y: 6 };
+        let [[first], { inner }] -> [[7], { inner: 8 }];
+        a; rest.len; rest[1]; x; renamed; z; first; inner;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(1),
+            Value::Integer(2),
+            Value::Integer(4),
+            Value::Integer(5),
+            Value::Integer(6),
+            Value::Integer(10),
+            Value::Integer(7),
+            Value::Integer(8),
+        ]
+    );
+}
+
+#[test]
+fn test_destructuring_parameters_loops_and_assignment() {
+    let vm = run(r#"
+        fn area({ w, h -> 2 }) { return w * h; }
+        let total -> 0;
+        for [count, price] in [[2, 5], [3, 10]] {
+            total += count * price;
+        }
+        let a -> 1;
+        let b -> 2;
+        [a, b] -> [b, a];
+        area({ w: 3 });
+        area({ w: 3, h: 4 });
+        total;
+        a;
+        b;
+    "#);
+
+    assert_eq!(
+        vm.stack.values,
+        vec![
+            Value::Integer(6),
+            Value::Integer(12),
+            Value::Integer(40),
+            Value::Integer(2),
+            Value::Integer(1),
+        ]
+    );
 }
@@ -27,7 +27,7 @@ fn store_and_load_variable_test() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(42.0)]); // The variable x should hold the value 42.0
+    assert_eq!(vm.stack.values, vec![Value::Integer(42)]); // The variable x should hold the value 42.0
 }
 
 #[test]
@@ -43,7 +43,7 @@ fn store_and_load_multiple_variables_test() {
 
     assert_eq!(
         vm.stack.values,
-        vec![Value::Number(42.0), Value::Number(58.0)]
+        vec![Value::Integer(42), Value::Integer(58)]
     );
 }
 
@@ -58,7 +58,7 @@ fn add_two_number_variables() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(100.0)]); // The result of x + y should be 100.0
+    assert_eq!(vm.stack.values, vec![Value::Integer(100)]); // The result of x + y should be 100.0
 }
 
 #[test]
@@ -72,7 +72,7 @@ fn subtract_two_number_variables() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(-16.0)]); // The result of x + y should be 100.0
+    assert_eq!(vm.stack.values, vec![Value::Integer(-16)]); // The result of x + y should be 100.0
 }
 
 #[test]
@@ -86,7 +86,7 @@ fn multiply_two_number_variables() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(2436.0)]); // The result of x * y should be 2436.0
+    assert_eq!(vm.stack.values, vec![Value::Integer(2436)]); // The result of x * y should be 2436.0
 }
 
 #[test]
@@ -100,7 +100,7 @@ fn divide_two_number_variables() {
     let mut vm = Vm::new();
     vm.execute(bytecode).unwrap();
 
-    assert_eq!(vm.stack.values, vec![Value::Number(42.0)]); // The result of x / y should be 42.0
+    assert_eq!(vm.stack.values, vec![Value::Number(42.0)]); // Dividing integers gives a float
 }
 
 #[test]
@@ -122,7 +122,7 @@ fn test_reassigning_variables() {
     vm.execute(bytecode).unwrap();
 
     // The result of x * y should be 66.0.
-    assert_eq!(vm.stack.values, vec![Value::Number(66.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(66)]);
 }
 
 #[test]
@@ -145,5 +145,5 @@ fn test_declaring_reassinging_variables() {
     vm.execute(bytecode).unwrap();
 
     // The result of z and x + y should be 22.0 + 5.0 = 27.0
-    assert_eq!(vm.stack.values, vec![Value::Number(27.0)]);
+    assert_eq!(vm.stack.values, vec![Value::Integer(27)]);
 }
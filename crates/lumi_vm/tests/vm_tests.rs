@@ -1,5 +1,7 @@
-use lumi_bytecode::{Bytecode, Constant, FunctionObj, Instruction};
-use lumi_vm::{Value, Vm};
+use std::collections::HashMap;
+
+use lumi_bytecode::{Bytecode, Constant, DebugInfo, FunctionObj, Instruction};
+use lumi_vm::{HeapEntry, VMError, Value, Vm};
 
 #[test]
 fn test_execute_basic_add() {
@@ -11,13 +13,15 @@ fn test_execute_basic_add() {
             Instruction::Add,          // Add the two values
         ],
         constants: vec![
-            Constant::Number(5.0), // Constant at index 0
-            Constant::Number(3.0), // Constant at index 1
+            Constant::Integer(5), // Constant at index 0
+            Constant::Integer(3), // Constant at index 1
         ],
+        handlers: vec![],
+        debug_info: DebugInfo::default(),
     };
 
     vm.execute(bytecode).unwrap();
-    assert_eq!(vm.stack.values, vec![Value::Number(8.0)]); // Check if the result is 8.0
+    assert_eq!(vm.stack.values, vec![Value::Integer(8)]); // Check if the result is 8.0
 }
 
 #[test]
@@ -29,11 +33,13 @@ fn test_store_and_load_var_variable() {
             Instruction::StoreVar(0),  // Store it in variable index 0
             Instruction::LoadVar(0),   // Load variable index 0
         ],
-        constants: vec![Constant::Number(42.0)], // Constant at index 0
+        constants: vec![Constant::Integer(42)], // Constant at index 0
+        handlers: vec![],
+        debug_info: DebugInfo::default(),
     };
 
     vm.execute(bytecode).unwrap();
-    assert_eq!(vm.stack.values, vec![Value::Number(42.0)]); // Check if the variable holds 42.0
+    assert_eq!(vm.stack.values, vec![Value::Integer(42)]); // Check if the variable holds 42.0
 }
 
 #[test]
@@ -46,7 +52,9 @@ fn test_print_statement() {
             Instruction::LoadVar(0),
             Instruction::Print,
         ],
-        constants: vec![Constant::Number(42.0)], // Constant at index 0
+        constants: vec![Constant::Integer(42)], // Constant at index 0
+        handlers: vec![],
+        debug_info: DebugInfo::default(),
     };
 
     vm.execute(bytecode).unwrap();
@@ -66,15 +74,20 @@ fn test_fn_statement() {
     let mut vm = Vm::new();
     let bytecode = Bytecode {
         instructions: vec![
-            Instruction::PushConst(0),
+            Instruction::MakeClosure(0),
+            Instruction::StoreVar(0),
+            Instruction::LoadVar(0),
             Instruction::PushConst(1),
             Instruction::PushConst(2),
-            Instruction::CallFn("test".to_string()),
+            Instruction::CallValue(2),
         ],
         constants: vec![
             Constant::Function(FunctionObj {
                 name: Some("test".to_string()),
                 arity: 2,
+                param_names: vec!["x".to_string(), "y".to_string()],
+                has_rest: false,
+                is_method: false,
                 instructions: vec![
                     Instruction::LoadVar(0),
                     Instruction::LoadVar(1),
@@ -82,12 +95,136 @@ fn test_fn_statement() {
                     Instruction::Return,
                 ],
                 constants: vec![],
+                captures: vec![],
+                handlers: vec![],
             }),
-            Constant::Number(1.0),
-            Constant::Number(2.0),
+            Constant::Integer(1),
+            Constant::Integer(2),
+        ],
+        handlers: vec![],
+        debug_info: DebugInfo::default(),
+    };
+
+    vm.execute(bytecode).unwrap();
+    assert_eq!(vm.stack.values, vec![Value::Integer(3)]);
+}
+
+#[test]
+fn test_get_and_set_property() {
+    let mut vm = Vm::new();
+    let handle = vm.heap.allocate(HeapEntry::Object(HashMap::new()));
+    vm.locals[0] = Value::Object(handle);
+
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::LoadVar(0),
+            Instruction::PushConst(0),
+            Instruction::SetProp("count".to_string()),
+            Instruction::LoadVar(0),
+            Instruction::Dup,
+            Instruction::GetProp("count".to_string()),
+            Instruction::PushConst(0),
+            Instruction::Add,
+            Instruction::SetProp("count".to_string()),
+            Instruction::LoadVar(0),
+            Instruction::GetProp("count".to_string()),
+            Instruction::LoadVar(0),
+            Instruction::GetProp("missing".to_string()),
+        ],
+        constants: vec![Constant::Integer(2)],
+        handlers: vec![],
+        debug_info: DebugInfo::default(),
+    };
+
+    vm.execute(bytecode).unwrap();
+    assert_eq!(vm.stack.values, vec![Value::Integer(4), Value::Undefined]);
+}
+
+#[test]
+fn test_get_and_set_index() {
+    let mut vm = Vm::new();
+    let handle = vm.heap.allocate(HeapEntry::Array(vec![Value::Integer(1)]));
+    vm.locals[0] = Value::Array(handle);
+
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::LoadVar(0),
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::SetIndex,
+            Instruction::LoadVar(0),
+            Instruction::PushConst(0),
+            Instruction::GetIndex,
+            Instruction::LoadVar(0),
+            Instruction::PushConst(1),
+            Instruction::GetIndex,
         ],
+        constants: vec![Constant::Integer(2), Constant::String("x".to_string())],
+        handlers: vec![],
+        debug_info: DebugInfo::default(),
     };
 
     vm.execute(bytecode).unwrap();
-    assert_eq!(vm.stack.values, vec![Value::Number(3.0)]);
+    assert_eq!(
+        vm.stack.values,
+        vec![Value::String("x".to_string()), Value::Undefined]
+    );
+    assert!(matches!(
+        vm.heap.get(handle),
+        Some(HeapEntry::Array(elements)) if elements.len() == 3
+    ));
+}
+
+#[test]
+fn test_property_of_undefined_is_an_error() {
+    let mut vm = Vm::new();
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::LoadVar(0),
+            Instruction::GetProp("name".to_string()),
+        ],
+        constants: vec![],
+        handlers: vec![],
+        debug_info: DebugInfo::default(),
+    };
+
+    assert_eq!(
+        vm.execute(bytecode),
+        Err(VMError::InvalidPropertyAccess {
+            property: "name".to_string(),
+            target: "undefined".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_optional_chain_short_circuits() {
+    let mut vm = Vm::new();
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::LoadVar(0),
+            Instruction::JumpIfNullish(4),
+            Instruction::GetProp("a".to_string()),
+            Instruction::GetProp("b".to_string()),
+        ],
+        constants: vec![],
+        handlers: vec![],
+        debug_info: DebugInfo::default(),
+    };
+
+    vm.execute(bytecode).unwrap();
+    assert_eq!(vm.stack.values, vec![Value::Undefined]);
+}
+
+#[test]
+fn test_return_outside_function() {
+    let mut vm = Vm::new();
+    let bytecode = Bytecode {
+        instructions: vec![Instruction::PushConst(0), Instruction::Return],
+        constants: vec![Constant::Integer(1)],
+        handlers: vec![],
+        debug_info: DebugInfo::default(),
+    };
+
+    assert_eq!(vm.execute(bytecode), Err(VMError::ReturnOutsideFunction));
 }